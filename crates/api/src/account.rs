@@ -11,44 +11,52 @@ use openapi::{
         configuration::Configuration,
         my_account_api::{
             GetAccountDetailsMyDetailsGetError, GetPendingItemsMyPendingItemsGetError,
-            get_account_details_my_details_get, get_pending_items_my_pending_items_get,
+            GetRateLimitsMyRatesGetError, get_account_details_my_details_get,
+            get_pending_items_my_pending_items_get, get_rate_limits_my_rates_get,
         },
     },
     models::{
         AccountAchievementSchema, CharactersListSchema, DataPageAccountAchievementSchema,
-        DataPagePendingItemSchema, MyAccountDetails, PendingItemSchema,
+        DataPagePendingItemSchema, MyAccountDetails, PendingItemSchema, RateLimitsDataSchema,
     },
 };
 use std::sync::Arc;
 
-#[derive(Default, Debug)]
-pub struct AccountApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncAccountApi {
     configuration: Arc<Configuration>,
 }
 
-impl AccountApi {
+impl AsyncAccountApi {
     pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
-    pub fn characters(
+    pub async fn characters(
         &self,
         account: &str,
     ) -> Result<CharactersListSchema, Error<GetAccountCharactersAccountsAccountCharactersGetError>>
     {
-        RUNTIME.block_on(get_account_characters_accounts_account_characters_get(
-            &self.configuration,
-            account,
-        ))
+        get_account_characters_accounts_account_characters_get(&self.configuration, account).await
     }
 
-    pub fn details(&self) -> Result<MyAccountDetails, Error<GetAccountDetailsMyDetailsGetError>> {
-        RUNTIME
-            .block_on(get_account_details_my_details_get(&self.configuration))
+    pub async fn details(
+        &self,
+    ) -> Result<MyAccountDetails, Error<GetAccountDetailsMyDetailsGetError>> {
+        get_account_details_my_details_get(&self.configuration)
+            .await
             .map(|schema| schema.data)
     }
 
-    pub fn achievements(
+    pub async fn rate_limits(
+        &self,
+    ) -> Result<RateLimitsDataSchema, Error<GetRateLimitsMyRatesGetError>> {
+        get_rate_limits_my_rates_get(&self.configuration)
+            .await
+            .map(|schema| schema.data)
+    }
+
+    pub async fn achievements(
         &self,
         account: &str,
     ) -> Result<
@@ -60,15 +68,58 @@ impl AccountApi {
             account,
         }
         .send()
+        .await
     }
 
-    pub fn pending_items(
+    pub async fn pending_items(
         &self,
     ) -> Result<Vec<PendingItemSchema>, Error<GetPendingItemsMyPendingItemsGetError>> {
         PendingItemsRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct AccountApi(AsyncAccountApi);
+
+impl AccountApi {
+    pub(crate) const fn new(api: AsyncAccountApi) -> Self {
+        Self(api)
+    }
+
+    pub fn characters(
+        &self,
+        account: &str,
+    ) -> Result<CharactersListSchema, Error<GetAccountCharactersAccountsAccountCharactersGetError>>
+    {
+        RUNTIME.block_on(self.0.characters(account))
+    }
+
+    pub fn details(&self) -> Result<MyAccountDetails, Error<GetAccountDetailsMyDetailsGetError>> {
+        RUNTIME.block_on(self.0.details())
+    }
+
+    pub fn rate_limits(&self) -> Result<RateLimitsDataSchema, Error<GetRateLimitsMyRatesGetError>> {
+        RUNTIME.block_on(self.0.rate_limits())
+    }
+
+    pub fn achievements(
+        &self,
+        account: &str,
+    ) -> Result<
+        Vec<AccountAchievementSchema>,
+        Error<GetAccountAchievementsAccountsAccountAchievementsGetError>,
+    > {
+        RUNTIME.block_on(self.0.achievements(account))
+    }
+
+    pub fn pending_items(
+        &self,
+    ) -> Result<Vec<PendingItemSchema>, Error<GetPendingItemsMyPendingItemsGetError>> {
+        RUNTIME.block_on(self.0.pending_items())
     }
 }
 
@@ -82,15 +133,16 @@ impl Paginate for AchievementsRequest<'_> {
     type Page = DataPageAccountAchievementSchema;
     type Error = GetAccountAchievementsAccountsAccountAchievementsGetError;
 
-    fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_account_achievements_accounts_account_achievements_get(
+    async fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_account_achievements_accounts_account_achievements_get(
             self.configuration,
             self.account,
             None,
             None,
             Some(current_page),
             Some(100),
-        ))
+        )
+        .await
     }
 }
 
@@ -113,12 +165,9 @@ impl Paginate for PendingItemsRequest<'_> {
     type Page = DataPagePendingItemSchema;
     type Error = GetPendingItemsMyPendingItemsGetError;
 
-    fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_pending_items_my_pending_items_get(
-            self.configuration,
-            Some(current_page),
-            Some(100),
-        ))
+    async fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_pending_items_my_pending_items_get(self.configuration, Some(current_page), Some(100))
+            .await
     }
 }
 
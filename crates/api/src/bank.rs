@@ -12,33 +12,54 @@ use openapi::{
 };
 use std::sync::Arc;
 
-#[derive(Default, Debug)]
-pub struct BankApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncBankApi {
     configuration: Arc<Configuration>,
 }
 
-impl BankApi {
+impl AsyncBankApi {
     #[must_use]
     pub const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
-    pub fn get_items(
+    pub async fn get_items(
         &self,
     ) -> Result<Vec<SimpleItemSchema>, Error<GetBankItemsMyBankItemsGetError>> {
         BankItemsRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
     }
 
-    pub fn get_details(&self) -> Result<BankSchema, Error<GetBankDetailsMyBankGetError>> {
-        RUNTIME
-            .block_on(get_bank_details_my_bank_get(&self.configuration))
+    pub async fn get_details(&self) -> Result<BankSchema, Error<GetBankDetailsMyBankGetError>> {
+        get_bank_details_my_bank_get(&self.configuration)
+            .await
             .map(|s| s.data)
     }
 }
 
+#[derive(Default, Debug)]
+pub struct BankApi(AsyncBankApi);
+
+impl BankApi {
+    #[must_use]
+    pub const fn new(api: AsyncBankApi) -> Self {
+        Self(api)
+    }
+
+    pub fn get_items(
+        &self,
+    ) -> Result<Vec<SimpleItemSchema>, Error<GetBankItemsMyBankItemsGetError>> {
+        RUNTIME.block_on(self.0.get_items())
+    }
+
+    pub fn get_details(&self) -> Result<BankSchema, Error<GetBankDetailsMyBankGetError>> {
+        RUNTIME.block_on(self.0.get_details())
+    }
+}
+
 struct BankItemsRequest<'a> {
     configuration: &'a Configuration,
 }
@@ -48,13 +69,9 @@ impl Paginate for BankItemsRequest<'_> {
     type Page = DataPageSimpleItemSchema;
     type Error = GetBankItemsMyBankItemsGetError;
 
-    fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_bank_items_my_bank_items_get(
-            self.configuration,
-            None,
-            Some(current_page),
-            Some(100),
-        ))
+    async fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_bank_items_my_bank_items_get(self.configuration, None, Some(current_page), Some(100))
+            .await
     }
 }
 
@@ -0,0 +1,303 @@
+use http::Extensions;
+use log::debug;
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next, Result};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use thiserror::Error;
+
+/// Request as stored in a cassette. Headers are left out so that the account
+/// token is never written to disk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path and query of the request URL.
+    pub path: String,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+impl Interaction {
+    /// Builds an interaction answering `method` requests to `path` with a JSON
+    /// `body`, regardless of the request body.
+    #[must_use]
+    pub fn json(method: &str, path: &str, status: u16, body: impl Into<String>) -> Self {
+        Self {
+            request: RecordedRequest {
+                method: method.to_owned(),
+                path: path.to_owned(),
+                body: None,
+            },
+            response: RecordedResponse {
+                status,
+                headers: vec![("content-type".to_owned(), "application/json".to_owned())],
+                body: body.into(),
+            },
+        }
+    }
+
+    fn matches(&self, request: &RecordedRequest) -> bool {
+        self.request.method == request.method
+            && self.request.path == request.path
+            && (self.request.body.is_none() || self.request.body == request.body)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CassetteError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse cassette: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("failed to serialize cassette: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("failed to build response: {0}")]
+    Http(#[from] http::Error),
+    #[error("no recorded interaction left for {method} {path}")]
+    NoInteraction { method: String, path: String },
+}
+
+#[derive(Debug)]
+enum Mode {
+    /// Forwards requests and appends each exchange to the cassette file.
+    Record(PathBuf),
+    /// Answers requests from the recorded interactions without network.
+    Replay,
+}
+
+/// Records request/response pairs to a RON file, or replays them in order
+/// without reaching the server.
+///
+/// During replay, each request is answered by the first interaction not yet
+/// played that has the same method, path and body, so that successive
+/// responses to the same request (e.g. an error then a success) are replayed
+/// in the order they were recorded.
+#[derive(Debug)]
+pub struct CassetteMiddleware {
+    mode: Mode,
+    tracks: Mutex<Vec<Track>>,
+}
+
+#[derive(Debug)]
+struct Track {
+    interaction: Interaction,
+    played: bool,
+}
+
+impl CassetteMiddleware {
+    /// Records the interactions to `path`, overwriting any existing cassette.
+    #[must_use]
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: Mode::Record(path.into()),
+            tracks: Mutex::default(),
+        }
+    }
+
+    /// Replays the interactions stored at `path`.
+    pub fn replay(path: impl AsRef<Path>) -> std::result::Result<Self, CassetteError> {
+        let content = fs::read_to_string(path)?;
+        Ok(Self::from_interactions(ron::from_str(&content)?))
+    }
+
+    /// Replays the given `interactions`.
+    #[must_use]
+    pub fn from_interactions(interactions: Vec<Interaction>) -> Self {
+        Self {
+            mode: Mode::Replay,
+            tracks: Mutex::new(
+                interactions
+                    .into_iter()
+                    .map(|interaction| Track {
+                        interaction,
+                        played: false,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns the number of interactions not played yet.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.tracks
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|t| !t.played)
+            .count()
+    }
+
+    #[must_use]
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.tracks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|t| t.interaction.clone())
+            .collect()
+    }
+
+    fn play(&self, request: &RecordedRequest) -> std::result::Result<Response, CassetteError> {
+        let response = self
+            .tracks
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|t| !t.played && t.interaction.matches(request))
+            .map(|t| {
+                t.played = true;
+                t.interaction.response.clone()
+            })
+            .ok_or_else(|| CassetteError::NoInteraction {
+                method: request.method.clone(),
+                path: request.path.clone(),
+            })?;
+        to_response(&response)
+    }
+
+    fn store(
+        &self,
+        path: &Path,
+        interaction: Interaction,
+    ) -> std::result::Result<(), CassetteError> {
+        let mut tracks = self.tracks.lock().unwrap();
+        tracks.push(Track {
+            interaction,
+            played: true,
+        });
+        let interactions = tracks.iter().map(|t| &t.interaction).collect::<Vec<_>>();
+        let content = ron::ser::to_string_pretty(&interactions, PrettyConfig::default());
+        drop(tracks);
+        let content = content?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+fn to_request(request: &Request) -> RecordedRequest {
+    let url = request.url();
+    RecordedRequest {
+        method: request.method().to_string(),
+        path: url
+            .query()
+            .map_or_else(|| url.path().to_owned(), |q| format!("{}?{q}", url.path())),
+        body: request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .map(|b| String::from_utf8_lossy(b).into_owned()),
+    }
+}
+
+fn to_response(recorded: &RecordedResponse) -> std::result::Result<Response, CassetteError> {
+    let mut builder = http::Response::builder().status(recorded.status);
+    for (name, value) in &recorded.headers {
+        builder = builder.header(name, value);
+    }
+    Ok(Response::from(builder.body(recorded.body.clone())?))
+}
+
+#[async_trait::async_trait]
+impl Middleware for CassetteMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let request = to_request(&req);
+        let Mode::Record(ref path) = self.mode else {
+            debug!("replaying {} {}", request.method, request.path);
+            return self
+                .play(&request)
+                .map_err(reqwest_middleware::Error::middleware);
+        };
+        let res = next.run(req, extensions).await?;
+        let response = RecordedResponse {
+            status: res.status().as_u16(),
+            headers: res
+                .headers()
+                .iter()
+                .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_owned())))
+                .collect(),
+            body: res.text().await?,
+        };
+        debug!("recording {} {}", request.method, request.path);
+        self.store(
+            path,
+            Interaction {
+                request,
+                response: response.clone(),
+            },
+        )
+        .map_err(reqwest_middleware::Error::middleware)?;
+        to_response(&response).map_err(reqwest_middleware::Error::middleware)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArtifactApi;
+    use reqwest_middleware::ClientBuilder;
+    use std::sync::Arc;
+
+    const STATUS: &str = r#"{"data":{"version":"7.0.0","server_time":"2025-01-01T00:00:00Z","max_level":50,"max_skill_level":50,"characters_online":1,"season":null,"rate_limits":[]}}"#;
+
+    fn api(cassette: &Arc<CassetteMiddleware>) -> ArtifactApi {
+        ArtifactApi::with_client(
+            "http://localhost".to_owned(),
+            "token".to_owned(),
+            ClientBuilder::new(reqwest::Client::new())
+                .with_arc(cassette.clone())
+                .build(),
+        )
+    }
+
+    #[test]
+    fn replays_interactions_in_order() {
+        let cassette = Arc::new(CassetteMiddleware::from_interactions(vec![
+            Interaction::json("GET", "/", 500, "{}"),
+            Interaction::json("GET", "/", 200, STATUS),
+        ]));
+        let api = api(&cassette);
+
+        assert!(api.server.status().is_none());
+        assert_eq!(api.server.status().unwrap().data.version, "7.0.0");
+        assert_eq!(cassette.remaining(), 0);
+        assert!(api.server.status().is_none());
+    }
+
+    #[test]
+    fn replays_cassette_from_disk() {
+        let path = std::env::temp_dir().join(format!("cassette_{}.ron", std::process::id()));
+        let interactions = vec![Interaction::json("GET", "/", 200, STATUS)];
+        fs::write(
+            &path,
+            ron::ser::to_string_pretty(&interactions, PrettyConfig::default()).unwrap(),
+        )
+        .unwrap();
+        let cassette = Arc::new(CassetteMiddleware::replay(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(cassette.interactions(), interactions);
+        assert!(api(&cassette).server.status().is_some());
+    }
+}
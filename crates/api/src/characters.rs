@@ -9,20 +9,36 @@ use openapi::{
 };
 use std::sync::Arc;
 
-#[derive(Default, Debug)]
-pub struct CharactersApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncCharactersApi {
     configuration: Arc<Configuration>,
 }
 
-impl CharactersApi {
+impl AsyncCharactersApi {
     pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
+    pub async fn get(
+        &self,
+        name: &str,
+    ) -> Result<CharacterResponseSchema, Error<GetCharacterCharactersNameGetError>> {
+        get_character_characters_name_get(&self.configuration, name).await
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct CharactersApi(AsyncCharactersApi);
+
+impl CharactersApi {
+    pub(crate) const fn new(api: AsyncCharactersApi) -> Self {
+        Self(api)
+    }
+
     pub fn get(
         &self,
         name: &str,
     ) -> Result<CharacterResponseSchema, Error<GetCharacterCharactersNameGetError>> {
-        RUNTIME.block_on(get_character_characters_name_get(&self.configuration, name))
+        RUNTIME.block_on(self.0.get(name))
     }
 }
@@ -14,30 +14,51 @@ use openapi::{
 };
 use std::sync::Arc;
 
-#[derive(Default, Debug)]
-pub struct EventsApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncEventsApi {
     configuration: Arc<Configuration>,
 }
 
-impl EventsApi {
+impl AsyncEventsApi {
     pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
-    pub fn get_all(&self) -> Result<Vec<EventSchema>, Error<GetAllEventsEventsGetError>> {
+    pub async fn get_all(&self) -> Result<Vec<EventSchema>, Error<GetAllEventsEventsGetError>> {
         EventsRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
     }
 
-    pub fn get_active(
+    pub async fn get_active(
         &self,
     ) -> Result<Vec<ActiveEventSchema>, Error<GetAllActiveEventsEventsActiveGetError>> {
         ActiveEventsRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct EventsApi(AsyncEventsApi);
+
+impl EventsApi {
+    pub(crate) const fn new(api: AsyncEventsApi) -> Self {
+        Self(api)
+    }
+
+    pub fn get_all(&self) -> Result<Vec<EventSchema>, Error<GetAllEventsEventsGetError>> {
+        RUNTIME.block_on(self.0.get_all())
+    }
+
+    pub fn get_active(
+        &self,
+    ) -> Result<Vec<ActiveEventSchema>, Error<GetAllActiveEventsEventsActiveGetError>> {
+        RUNTIME.block_on(self.0.get_active())
     }
 }
 
@@ -50,13 +71,8 @@ impl Paginate for EventsRequest<'_> {
     type Page = StaticDataPageEventSchema;
     type Error = GetAllEventsEventsGetError;
 
-    fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_all_events_events_get(
-            self.configuration,
-            None,
-            Some(page),
-            Some(100),
-        ))
+    async fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_all_events_events_get(self.configuration, None, Some(page), Some(100)).await
     }
 }
 
@@ -79,12 +95,9 @@ impl Paginate for ActiveEventsRequest<'_> {
     type Page = StaticDataPageActiveEventSchema;
     type Error = GetAllActiveEventsEventsActiveGetError;
 
-    fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_all_active_events_events_active_get(
-            self.configuration,
-            Some(current_page),
-            Some(100),
-        ))
+    async fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_all_active_events_events_active_get(self.configuration, Some(current_page), Some(100))
+            .await
     }
 }
 
@@ -16,17 +16,17 @@ use openapi::{
 };
 use std::{result::Result, sync::Arc, vec::Vec};
 
-#[derive(Default, Debug)]
-pub struct GrandExchangeApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncGrandExchangeApi {
     configuration: Arc<Configuration>,
 }
 
-impl GrandExchangeApi {
+impl AsyncGrandExchangeApi {
     pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
-    pub fn sell_history(
+    pub async fn sell_history(
         &self,
         item_code: &str,
     ) -> Result<Vec<GeOrderHistorySchema>, Error<GetGeHistoryGrandexchangeHistoryCodeGetError>>
@@ -36,25 +36,54 @@ impl GrandExchangeApi {
             code: item_code,
         }
         .send()
+        .await
     }
 
-    pub fn sell_orders(
+    pub async fn sell_orders(
         &self,
     ) -> Result<Vec<GeOrderSchema>, Error<GetGeOrdersGrandexchangeOrdersGetError>> {
         SellOrdersRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
+    }
+
+    pub async fn get_sell_order(
+        &self,
+        id: &str,
+    ) -> Result<GeOrderResponseSchema, Error<GetGeOrderGrandexchangeOrdersIdGetError>> {
+        get_ge_order_grandexchange_orders_id_get(&self.configuration, id).await
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct GrandExchangeApi(AsyncGrandExchangeApi);
+
+impl GrandExchangeApi {
+    pub(crate) const fn new(api: AsyncGrandExchangeApi) -> Self {
+        Self(api)
+    }
+
+    pub fn sell_history(
+        &self,
+        item_code: &str,
+    ) -> Result<Vec<GeOrderHistorySchema>, Error<GetGeHistoryGrandexchangeHistoryCodeGetError>>
+    {
+        RUNTIME.block_on(self.0.sell_history(item_code))
+    }
+
+    pub fn sell_orders(
+        &self,
+    ) -> Result<Vec<GeOrderSchema>, Error<GetGeOrdersGrandexchangeOrdersGetError>> {
+        RUNTIME.block_on(self.0.sell_orders())
     }
 
     pub fn get_sell_order(
         &self,
         id: &str,
     ) -> Result<GeOrderResponseSchema, Error<GetGeOrderGrandexchangeOrdersIdGetError>> {
-        RUNTIME.block_on(get_ge_order_grandexchange_orders_id_get(
-            &self.configuration,
-            id,
-        ))
+        RUNTIME.block_on(self.0.get_sell_order(id))
     }
 }
 
@@ -71,14 +100,15 @@ impl Paginate for SellHistoryRequest<'_> {
     type Page = DataPageGeOrderHistorySchema;
     type Error = GetGeHistoryGrandexchangeHistoryCodeGetError;
 
-    fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_ge_history_grandexchange_history_code_get(
+    async fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_ge_history_grandexchange_history_code_get(
             self.configuration,
             self.code,
             None,
             Some(current_page),
             Some(100),
-        ))
+        )
+        .await
     }
 }
 
@@ -97,8 +127,8 @@ impl Paginate for SellOrdersRequest<'_> {
     type Page = DataPageGeOrderSchema;
     type Error = GetGeOrdersGrandexchangeOrdersGetError;
 
-    fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_ge_orders_grandexchange_orders_get(
+    async fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_ge_orders_grandexchange_orders_get(
             self.configuration,
             None,
             None,
@@ -106,7 +136,8 @@ impl Paginate for SellOrdersRequest<'_> {
             None,
             Some(page),
             Some(100),
-        ))
+        )
+        .await
     }
 }
 
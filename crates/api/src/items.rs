@@ -9,21 +9,35 @@ use openapi::{
 };
 use std::sync::Arc;
 
-#[derive(Default, Debug)]
-pub struct ItemsApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncItemsApi {
     configuration: Arc<Configuration>,
 }
 
-impl ItemsApi {
+impl AsyncItemsApi {
     pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
-    pub fn get_all(&self) -> Result<Vec<ItemSchema>, Error<GetAllItemsItemsGetError>> {
+    pub async fn get_all(&self) -> Result<Vec<ItemSchema>, Error<GetAllItemsItemsGetError>> {
         ItemsRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct ItemsApi(AsyncItemsApi);
+
+impl ItemsApi {
+    pub(crate) const fn new(api: AsyncItemsApi) -> Self {
+        Self(api)
+    }
+
+    pub fn get_all(&self) -> Result<Vec<ItemSchema>, Error<GetAllItemsItemsGetError>> {
+        RUNTIME.block_on(self.0.get_all())
     }
 }
 
@@ -36,8 +50,8 @@ impl Paginate for ItemsRequest<'_> {
     type Page = StaticDataPageItemSchema;
     type Error = GetAllItemsItemsGetError;
 
-    fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_all_items_items_get(
+    async fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_all_items_items_get(
             self.configuration,
             None,
             None,
@@ -47,7 +61,8 @@ impl Paginate for ItemsRequest<'_> {
             None,
             Some(current_page),
             Some(100),
-        ))
+        )
+        .await
     }
 }
 
@@ -1,11 +1,12 @@
 use derive_more::Deref;
-use openapi::apis::{Error, configuration::Configuration};
-use rate_limiter::RateLimiterMiddleware;
-use reqwest_middleware::ClientBuilder;
-use std::{
-    sync::{Arc, LazyLock},
-    thread::{self},
+use futures_util::{StreamExt, stream};
+use openapi::{
+    apis::{Error, configuration::Configuration},
+    models::RateLimitsDataSchema,
 };
+use rate_limiter::RateLimiterMiddleware;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use std::sync::{Arc, LazyLock};
 use tokio::runtime::Runtime;
 
 static RUNTIME: LazyLock<Runtime> =
@@ -13,34 +14,49 @@ static RUNTIME: LazyLock<Runtime> =
 
 mod rate_limiter;
 
-pub use account::AccountApi;
-pub use bank::BankApi;
-pub use characters::CharactersApi;
-pub use events::EventsApi;
-pub use grand_exchange::GrandExchangeApi;
-pub use items::ItemsApi;
-pub use maps::MapsApi;
-pub use monsters::MonstersApi;
-pub use my_characters::MyCharacterApi;
-pub use npcs::NpcsApi;
-pub use resources::ResourcesApi;
-pub use server::ServerApi;
-pub use tasks::TasksApi;
+pub use account::{AccountApi, AsyncAccountApi};
+pub use bank::{AsyncBankApi, BankApi};
+pub use cassette::CassetteMiddleware;
+pub use characters::{AsyncCharactersApi, CharactersApi};
+pub use events::{AsyncEventsApi, EventsApi};
+pub use grand_exchange::{AsyncGrandExchangeApi, GrandExchangeApi};
+pub use items::{AsyncItemsApi, ItemsApi};
+pub use logs::{AsyncLogsApi, LogsApi};
+pub use maps::{AsyncMapsApi, MapsApi};
+pub use monsters::{AsyncMonstersApi, MonstersApi};
+pub use my_characters::{AsyncMyCharacterApi, MyCharacterApi};
+pub use npcs::{AsyncNpcsApi, NpcsApi};
+pub use resources::{AsyncResourcesApi, ResourcesApi};
+pub use server::{AsyncServerApi, ServerApi};
+pub use simulation::{AsyncSimulationApi, SimulationApi};
+pub use tasks::{AsyncTasksApi, TasksApi};
 
 pub mod account;
 pub mod bank;
+pub mod cassette;
 pub mod characters;
 pub mod events;
 pub mod grand_exchange;
 pub mod items;
+pub mod logs;
 pub mod maps;
 pub mod monsters;
 pub mod my_characters;
 pub mod npcs;
 pub mod resources;
 pub mod server;
+pub mod simulation;
 pub mod tasks;
 
+/// Base path of the offline API, never reached since an empty cassette answers
+/// every request.
+const OFFLINE_URL: &str = "http://offline.invalid";
+
+/// Number of pages requested at once when fetching a paginated resource.
+const PAGE_CONCURRENCY: usize = 4;
+
+/// Blocking facade over [`AsyncArtifactApi`], running each request on the
+/// shared runtime.
 #[derive(Default, Debug, Clone, Deref)]
 #[deref(forward)]
 pub struct ArtifactApi(Arc<ArtifactApiInner>);
@@ -53,46 +69,181 @@ pub struct ArtifactApiInner {
     pub events: EventsApi,
     pub grand_exchange: GrandExchangeApi,
     pub items: ItemsApi,
+    pub logs: LogsApi,
     pub maps: MapsApi,
     pub monsters: MonstersApi,
     pub my_character: MyCharacterApi,
     pub npcs: NpcsApi,
     pub resources: ResourcesApi,
     pub server: ServerApi,
+    pub simulation: SimulationApi,
     pub tasks: TasksApi,
+    asynchronous: AsyncArtifactApi,
 }
 
 impl ArtifactApi {
+    /// See [`AsyncArtifactApi::new`].
     #[must_use]
     pub fn new(base_path: String, token: String) -> Self {
-        let conf = Arc::new({
-            let mut c = Configuration::new();
-            c.base_path = base_path;
-            c.client = ClientBuilder::from_client(c.client)
-                .with(RateLimiterMiddleware::new())
-                .build();
-            c
+        AsyncArtifactApi::new(base_path, token).into()
+    }
+
+    /// See [`AsyncArtifactApi::with_rate_limits`].
+    #[must_use]
+    pub fn with_rate_limits(
+        base_path: String,
+        token: String,
+        limits: &RateLimitsDataSchema,
+    ) -> Self {
+        AsyncArtifactApi::with_rate_limits(base_path, token, limits).into()
+    }
+
+    /// Builds the API on top of the given HTTP `client`, e.g. to record or
+    /// replay requests with a [`CassetteMiddleware`].
+    #[must_use]
+    pub fn with_client(base_path: String, token: String, client: ClientWithMiddleware) -> Self {
+        AsyncArtifactApi::with_client(base_path, token, client).into()
+    }
+
+    /// Builds an API failing every request without reaching the network, for
+    /// clients working from their cache only.
+    #[must_use]
+    pub fn offline() -> Self {
+        AsyncArtifactApi::offline().into()
+    }
+
+    /// Returns the async API this facade is built on.
+    #[must_use]
+    pub fn asynchronous(&self) -> &AsyncArtifactApi {
+        &self.asynchronous
+    }
+}
+
+impl From<AsyncArtifactApi> for ArtifactApi {
+    fn from(api: AsyncArtifactApi) -> Self {
+        Self(
+            ArtifactApiInner {
+                account: AccountApi::new(api.account.clone()),
+                bank: BankApi::new(api.bank.clone()),
+                character: CharactersApi::new(api.character.clone()),
+                events: EventsApi::new(api.events.clone()),
+                grand_exchange: GrandExchangeApi::new(api.grand_exchange.clone()),
+                items: ItemsApi::new(api.items.clone()),
+                logs: LogsApi::new(api.logs.clone()),
+                maps: MapsApi::new(api.maps.clone()),
+                monsters: MonstersApi::new(api.monsters.clone()),
+                my_character: MyCharacterApi::new(api.my_character.clone()),
+                npcs: NpcsApi::new(api.npcs.clone()),
+                resources: ResourcesApi::new(api.resources.clone()),
+                server: ServerApi::new(api.server.clone()),
+                simulation: SimulationApi::new(api.simulation.clone()),
+                tasks: TasksApi::new(api.tasks.clone()),
+                asynchronous: api,
+            }
+            .into(),
+        )
+    }
+}
+
+#[derive(Default, Debug, Clone, Deref)]
+#[deref(forward)]
+pub struct AsyncArtifactApi(Arc<AsyncArtifactApiInner>);
+
+#[derive(Default, Debug)]
+pub struct AsyncArtifactApiInner {
+    pub account: AsyncAccountApi,
+    pub bank: AsyncBankApi,
+    pub character: AsyncCharactersApi,
+    pub events: AsyncEventsApi,
+    pub grand_exchange: AsyncGrandExchangeApi,
+    pub items: AsyncItemsApi,
+    pub logs: AsyncLogsApi,
+    pub maps: AsyncMapsApi,
+    pub monsters: AsyncMonstersApi,
+    pub my_character: AsyncMyCharacterApi,
+    pub npcs: AsyncNpcsApi,
+    pub resources: AsyncResourcesApi,
+    pub server: AsyncServerApi,
+    pub simulation: AsyncSimulationApi,
+    pub tasks: AsyncTasksApi,
+}
+
+impl AsyncArtifactApi {
+    /// See [`ArtifactApi::offline`].
+    #[must_use]
+    pub fn offline() -> Self {
+        Self::with_client(
+            OFFLINE_URL.to_owned(),
+            String::new(),
+            ClientBuilder::new(reqwest::Client::new())
+                .with(CassetteMiddleware::from_interactions(vec![]))
+                .build(),
+        )
+    }
+
+    /// Builds the API without reaching the network: the rate limits are
+    /// loaded from `/my/rates` along with the first request.
+    #[must_use]
+    pub fn new(base_path: String, token: String) -> Self {
+        let rate_limiter = RateLimiterMiddleware::lazy(base_path.clone(), token.clone());
+        Self::with_rate_limiter(base_path, token, rate_limiter)
+    }
+
+    /// Builds the API enforcing the given rate `limits` instead of loading
+    /// them from the server.
+    #[must_use]
+    pub fn with_rate_limits(
+        base_path: String,
+        token: String,
+        limits: &RateLimitsDataSchema,
+    ) -> Self {
+        Self::with_rate_limiter(base_path, token, RateLimiterMiddleware::new(limits))
+    }
+
+    fn with_rate_limiter(
+        base_path: String,
+        token: String,
+        rate_limiter: RateLimiterMiddleware,
+    ) -> Self {
+        Self::with_client(
+            base_path,
+            token,
+            ClientBuilder::new(reqwest::Client::new())
+                .with(rate_limiter)
+                .build(),
+        )
+    }
+
+    /// Builds the API on top of the given HTTP `client`, e.g. to record or
+    /// replay requests with a [`CassetteMiddleware`].
+    #[must_use]
+    pub fn with_client(base_path: String, token: String, client: ClientWithMiddleware) -> Self {
+        let conf = Arc::new(Configuration {
+            base_path,
+            client,
+            ..Default::default()
         });
-        let auth_conf = Arc::new({
-            let mut c = (*conf).clone();
-            c.bearer_access_token = Some(token);
-            c
+        let auth_conf = Arc::new(Configuration {
+            bearer_access_token: Some(token),
+            ..(*conf).clone()
         });
         Self(
-            ArtifactApiInner {
-                account: AccountApi::new(auth_conf.clone()),
-                bank: BankApi::new(auth_conf.clone()),
-                character: CharactersApi::new(conf.clone()),
-                events: EventsApi::new(conf.clone()),
-                grand_exchange: GrandExchangeApi::new(conf.clone()),
-                items: ItemsApi::new(conf.clone()),
-                maps: MapsApi::new(conf.clone()),
-                monsters: MonstersApi::new(conf.clone()),
-                my_character: MyCharacterApi::new(auth_conf),
-                npcs: NpcsApi::new(conf.clone()),
-                resources: ResourcesApi::new(conf.clone()),
-                server: ServerApi::new(conf.clone()),
-                tasks: TasksApi::new(conf),
+            AsyncArtifactApiInner {
+                account: AsyncAccountApi::new(auth_conf.clone()),
+                bank: AsyncBankApi::new(auth_conf.clone()),
+                character: AsyncCharactersApi::new(conf.clone()),
+                events: AsyncEventsApi::new(conf.clone()),
+                grand_exchange: AsyncGrandExchangeApi::new(conf.clone()),
+                items: AsyncItemsApi::new(conf.clone()),
+                logs: AsyncLogsApi::new(auth_conf.clone()),
+                maps: AsyncMapsApi::new(conf.clone()),
+                monsters: AsyncMonstersApi::new(conf.clone()),
+                my_character: AsyncMyCharacterApi::new(auth_conf.clone()),
+                npcs: AsyncNpcsApi::new(conf.clone()),
+                resources: AsyncResourcesApi::new(conf.clone()),
+                server: AsyncServerApi::new(conf.clone()),
+                simulation: AsyncSimulationApi::new(auth_conf),
+                tasks: AsyncTasksApi::new(conf),
             }
             .into(),
         )
@@ -104,42 +255,148 @@ pub trait Paginate {
     type Page: DataPage<Self::Data>;
     type Error;
 
-    fn send(&self) -> Result<Vec<Self::Data>, Error<Self::Error>>
+    /// Requests the first page then the remaining ones, at most
+    /// `PAGE_CONCURRENCY` at a time. Pages failing after the first one are
+    /// logged and skipped.
+    fn send(&self) -> impl Future<Output = Result<Vec<Self::Data>, Error<Self::Error>>> + Send
     where
-        Self: std::marker::Sync,
-        <Self as Paginate>::Page: std::marker::Send,
-        <Self as Paginate>::Error: std::marker::Send,
+        Self: Sync,
+        Self::Data: Send,
+        Self::Page: Send,
+        Self::Error: Send,
     {
-        let mut data: Vec<Self::Data> = vec![];
-        let response = self.request_page(1)?;
-        let pages = response.pages();
-
-        data.extend(response.data());
-        if pages > 1 {
-            thread::scope(|s| {
-                let mut handles = vec![];
-                for p in 2..=pages {
-                    handles.push(s.spawn(move || self.request_page(p)));
-                }
-                for h in handles {
-                    let resp = match h.join().unwrap() {
-                        Ok(resp) => resp,
-                        Err(e) => {
-                            log::error!("failed to get page: {e}");
-                            continue;
-                        }
-                    };
-                    data.extend(resp.data());
+        async move {
+            let response = self.request_page(1).await?;
+            let pages = response.pages();
+            let mut data = response.data();
+            let mut responses = stream::iter(2..=pages)
+                .map(|p| self.request_page(p))
+                .buffered(PAGE_CONCURRENCY);
+            while let Some(response) = responses.next().await {
+                match response {
+                    Ok(resp) => data.extend(resp.data()),
+                    Err(e) => log::error!("failed to get page: {e}"),
                 }
-            });
+            }
+            Ok(data)
         }
-        Ok(data)
     }
 
-    fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>>;
+    fn request_page(
+        &self,
+        current_page: u32,
+    ) -> impl Future<Output = Result<Self::Page, Error<Self::Error>>> + Send;
 }
 
 pub trait DataPage<T> {
     fn data(self) -> Vec<T>;
     fn pages(&self) -> u32;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::Extensions;
+    use reqwest::{Request, Response};
+    use reqwest_middleware::{Middleware, Next};
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    /// Serves `pages` pages of bank items holding one item each, the first
+    /// pages being the slowest to answer.
+    struct StubBank {
+        pages: u32,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+        requests: AtomicUsize,
+    }
+
+    impl StubBank {
+        fn new(pages: u32) -> Arc<Self> {
+            Arc::new(Self {
+                pages,
+                in_flight: AtomicUsize::new(0),
+                max_in_flight: AtomicUsize::new(0),
+                requests: AtomicUsize::new(0),
+            })
+        }
+
+        fn api(self: &Arc<Self>) -> AsyncArtifactApi {
+            AsyncArtifactApi::with_client(
+                "http://localhost".to_owned(),
+                "token".to_owned(),
+                ClientBuilder::new(reqwest::Client::new())
+                    .with_arc(self.clone())
+                    .build(),
+            )
+        }
+
+        fn expected_codes(&self) -> Vec<String> {
+            (1..=self.pages).map(|p| format!("item{p}")).collect()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Middleware for StubBank {
+        async fn handle(
+            &self,
+            req: Request,
+            _extensions: &mut Extensions,
+            _next: Next<'_>,
+        ) -> reqwest_middleware::Result<Response> {
+            let page = req
+                .url()
+                .query_pairs()
+                .find(|(k, _)| k == "page")
+                .and_then(|(_, v)| v.parse::<u32>().ok())
+                .unwrap_or(1);
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(u64::from(self.pages - page) * 10)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            let body = format!(
+                r#"{{"data":[{{"code":"item{page}","quantity":1}}],"total":{0},"page":{page},"size":1,"pages":{0}}}"#,
+                self.pages
+            );
+            Ok(Response::from(
+                http::Response::builder()
+                    .status(200)
+                    .header("content-type", "application/json")
+                    .body(body)
+                    .unwrap(),
+            ))
+        }
+    }
+
+    #[test]
+    fn pages_are_fetched_at_most_four_at_a_time_and_kept_in_order() {
+        let stub = StubBank::new(10);
+        let items = RUNTIME.block_on(stub.api().bank.get_items()).unwrap();
+
+        assert_eq!(
+            items.into_iter().map(|i| i.code).collect::<Vec<_>>(),
+            stub.expected_codes()
+        );
+        assert_eq!(stub.requests.load(Ordering::SeqCst), 10);
+        assert_eq!(stub.max_in_flight.load(Ordering::SeqCst), PAGE_CONCURRENCY);
+    }
+
+    #[test]
+    fn blocking_facade_runs_async_requests() {
+        let stub = StubBank::new(3);
+        let api = ArtifactApi::from(stub.api());
+
+        assert_eq!(
+            api.bank
+                .get_items()
+                .unwrap()
+                .into_iter()
+                .map(|i| i.code)
+                .collect::<Vec<_>>(),
+            stub.expected_codes()
+        );
+    }
+}
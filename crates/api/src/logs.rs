@@ -0,0 +1,185 @@
+use crate::{DataPage, Paginate, RUNTIME};
+use chrono::{DateTime, FixedOffset};
+use openapi::{
+    apis::{
+        Error,
+        configuration::Configuration,
+        my_characters_api::{
+            GetAllCharactersLogsMyLogsGetError, GetCharacterLogsMyLogsNameGetError,
+            get_all_characters_logs_my_logs_get, get_character_logs_my_logs_name_get,
+        },
+    },
+    models::{DataPageLogSchema, LogSchema},
+};
+use std::sync::Arc;
+
+#[derive(Default, Debug, Clone)]
+pub struct AsyncLogsApi {
+    configuration: Arc<Configuration>,
+}
+
+impl AsyncLogsApi {
+    pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
+        Self { configuration }
+    }
+
+    pub async fn all(&self) -> Result<Vec<LogSchema>, Error<GetAllCharactersLogsMyLogsGetError>> {
+        AllLogsRequest {
+            configuration: &self.configuration,
+        }
+        .send()
+        .await
+    }
+
+    pub async fn character(
+        &self,
+        name: &str,
+    ) -> Result<Vec<LogSchema>, Error<GetCharacterLogsMyLogsNameGetError>> {
+        CharacterLogsRequest {
+            configuration: &self.configuration,
+            name,
+        }
+        .send()
+        .await
+    }
+
+    /// Returns the logs of all characters created at or after `since`, only
+    /// requesting the pages needed to reach it.
+    pub async fn all_since(
+        &self,
+        since: DateTime<FixedOffset>,
+    ) -> Result<Vec<LogSchema>, Error<GetAllCharactersLogsMyLogsGetError>> {
+        send_since(
+            &AllLogsRequest {
+                configuration: &self.configuration,
+            },
+            since,
+        )
+        .await
+    }
+
+    /// Returns the logs of the character `name` created at or after `since`,
+    /// only requesting the pages needed to reach it.
+    pub async fn character_since(
+        &self,
+        name: &str,
+        since: DateTime<FixedOffset>,
+    ) -> Result<Vec<LogSchema>, Error<GetCharacterLogsMyLogsNameGetError>> {
+        send_since(
+            &CharacterLogsRequest {
+                configuration: &self.configuration,
+                name,
+            },
+            since,
+        )
+        .await
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct LogsApi(AsyncLogsApi);
+
+impl LogsApi {
+    pub(crate) const fn new(api: AsyncLogsApi) -> Self {
+        Self(api)
+    }
+
+    pub fn all(&self) -> Result<Vec<LogSchema>, Error<GetAllCharactersLogsMyLogsGetError>> {
+        RUNTIME.block_on(self.0.all())
+    }
+
+    pub fn character(
+        &self,
+        name: &str,
+    ) -> Result<Vec<LogSchema>, Error<GetCharacterLogsMyLogsNameGetError>> {
+        RUNTIME.block_on(self.0.character(name))
+    }
+
+    /// Returns the logs of all characters created at or after `since`, only
+    /// requesting the pages needed to reach it.
+    pub fn all_since(
+        &self,
+        since: DateTime<FixedOffset>,
+    ) -> Result<Vec<LogSchema>, Error<GetAllCharactersLogsMyLogsGetError>> {
+        RUNTIME.block_on(self.0.all_since(since))
+    }
+
+    /// Returns the logs of the character `name` created at or after `since`,
+    /// only requesting the pages needed to reach it.
+    pub fn character_since(
+        &self,
+        name: &str,
+        since: DateTime<FixedOffset>,
+    ) -> Result<Vec<LogSchema>, Error<GetCharacterLogsMyLogsNameGetError>> {
+        RUNTIME.block_on(self.0.character_since(name, since))
+    }
+}
+
+/// Requests pages one by one until reaching a log older than `since`. Logs are
+/// served from the most recent to the oldest.
+async fn send_since<R>(
+    request: &R,
+    since: DateTime<FixedOffset>,
+) -> Result<Vec<LogSchema>, Error<R::Error>>
+where
+    R: Paginate<Data = LogSchema> + Sync,
+{
+    let mut logs = vec![];
+    let mut current_page = 1;
+    loop {
+        let page = request.request_page(current_page).await?;
+        let pages = page.pages();
+        let data = page.data();
+        let reached = data.iter().any(|l| l.created_at < since);
+        logs.extend(data.into_iter().filter(|l| l.created_at >= since));
+        if reached || current_page >= pages {
+            return Ok(logs);
+        }
+        current_page += 1;
+    }
+}
+
+struct AllLogsRequest<'a> {
+    configuration: &'a Configuration,
+}
+
+impl Paginate for AllLogsRequest<'_> {
+    type Data = LogSchema;
+    type Page = DataPageLogSchema;
+    type Error = GetAllCharactersLogsMyLogsGetError;
+
+    async fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_all_characters_logs_my_logs_get(self.configuration, Some(current_page), Some(100)).await
+    }
+}
+
+struct CharacterLogsRequest<'a> {
+    configuration: &'a Configuration,
+    name: &'a str,
+}
+
+impl Paginate for CharacterLogsRequest<'_> {
+    type Data = LogSchema;
+    type Page = DataPageLogSchema;
+    type Error = GetCharacterLogsMyLogsNameGetError;
+
+    async fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_character_logs_my_logs_name_get(
+            self.configuration,
+            self.name,
+            Some(current_page),
+            Some(100),
+        )
+        .await
+    }
+}
+
+impl DataPage<LogSchema> for DataPageLogSchema {
+    fn data(self) -> Vec<LogSchema> {
+        self.data
+    }
+
+    fn pages(&self) -> u32 {
+        self.pages
+    }
+}
@@ -12,28 +12,49 @@ use openapi::{
 };
 use std::sync::Arc;
 
-#[derive(Default, Debug)]
-pub struct MapsApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncMapsApi {
     configuration: Arc<Configuration>,
 }
 
-impl MapsApi {
+impl AsyncMapsApi {
     pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
-    pub fn get_all(&self) -> Result<Vec<MapSchema>, Error<GetAllMapsMapsGetError>> {
+    pub async fn get_all(&self) -> Result<Vec<MapSchema>, Error<GetAllMapsMapsGetError>> {
         MapsRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
+    }
+
+    pub async fn get_by_id(
+        &self,
+        id: i32,
+    ) -> Result<MapResponseSchema, Error<GetMapByIdMapsIdMapIdGetError>> {
+        get_map_by_id_maps_id_map_id_get(&self.configuration, id).await
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct MapsApi(AsyncMapsApi);
+
+impl MapsApi {
+    pub(crate) const fn new(api: AsyncMapsApi) -> Self {
+        Self(api)
+    }
+
+    pub fn get_all(&self) -> Result<Vec<MapSchema>, Error<GetAllMapsMapsGetError>> {
+        RUNTIME.block_on(self.0.get_all())
     }
 
     pub fn get_by_id(
         &self,
         id: i32,
     ) -> Result<MapResponseSchema, Error<GetMapByIdMapsIdMapIdGetError>> {
-        RUNTIME.block_on(get_map_by_id_maps_id_map_id_get(&self.configuration, id))
+        RUNTIME.block_on(self.0.get_by_id(id))
     }
 }
 
@@ -46,8 +67,8 @@ impl Paginate for MapsRequest<'_> {
     type Page = StaticDataPageMapSchema;
     type Error = GetAllMapsMapsGetError;
 
-    fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_all_maps_maps_get(
+    async fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_all_maps_maps_get(
             self.configuration,
             None,
             None,
@@ -57,7 +78,8 @@ impl Paginate for MapsRequest<'_> {
             None,
             Some(page),
             Some(100),
-        ))
+        )
+        .await
     }
 }
 
@@ -9,21 +9,37 @@ use openapi::{
 };
 use std::sync::Arc;
 
-#[derive(Default, Debug)]
-pub struct MonstersApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncMonstersApi {
     configuration: Arc<Configuration>,
 }
 
-impl MonstersApi {
+impl AsyncMonstersApi {
     pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
-    pub fn get_all(&self) -> Result<Vec<MonsterSchema>, Error<GetAllMonstersMonstersGetError>> {
+    pub async fn get_all(
+        &self,
+    ) -> Result<Vec<MonsterSchema>, Error<GetAllMonstersMonstersGetError>> {
         MonstersRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct MonstersApi(AsyncMonstersApi);
+
+impl MonstersApi {
+    pub(crate) const fn new(api: AsyncMonstersApi) -> Self {
+        Self(api)
+    }
+
+    pub fn get_all(&self) -> Result<Vec<MonsterSchema>, Error<GetAllMonstersMonstersGetError>> {
+        RUNTIME.block_on(self.0.get_all())
     }
 }
 
@@ -36,8 +52,8 @@ impl Paginate for MonstersRequest<'_> {
     type Page = StaticDataPageMonsterSchema;
     type Error = GetAllMonstersMonstersGetError;
 
-    fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_all_monsters_monsters_get(
+    async fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_all_monsters_monsters_get(
             self.configuration,
             None,
             None,
@@ -45,7 +61,8 @@ impl Paginate for MonstersRequest<'_> {
             None,
             Some(page),
             Some(100),
-        ))
+        )
+        .await
     }
 }
 
@@ -75,17 +75,17 @@ use std::sync::Arc;
 
 use crate::RUNTIME;
 
-#[derive(Default, Debug)]
-pub struct MyCharacterApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncMyCharacterApi {
     configuration: Arc<Configuration>,
 }
 
-impl MyCharacterApi {
+impl AsyncMyCharacterApi {
     pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
-    pub fn r#move(
+    pub async fn r#move(
         &self,
         name: &str,
         x: i32,
@@ -96,62 +96,45 @@ impl MyCharacterApi {
             y: Some(y),
             map_id: None,
         };
-        RUNTIME.block_on(action_move_my_name_action_move_post(
-            &self.configuration,
-            name,
-            dest,
-        ))
+        action_move_my_name_action_move_post(&self.configuration, name, dest).await
     }
 
-    pub fn transition(
+    pub async fn transition(
         &self,
         name: &str,
     ) -> Result<
         CharacterTransitionResponseSchema,
         Error<ActionTransitionMyNameActionTransitionPostError>,
     > {
-        RUNTIME.block_on(action_transition_my_name_action_transition_post(
-            &self.configuration,
-            name,
-        ))
+        action_transition_my_name_action_transition_post(&self.configuration, name).await
     }
 
-    pub fn fight(
+    pub async fn fight(
         &self,
         name: &str,
-        participants: Option<&[String; 2]>,
+        participants: Option<&[String]>,
     ) -> Result<CharacterFightResponseSchema, Error<ActionFightMyNameActionFightPostError>> {
         let schema = FightRequestSchema {
-            participants: participants.map(|p| p.to_vec()),
+            participants: participants.map(<[String]>::to_vec),
         };
-        RUNTIME.block_on(action_fight_my_name_action_fight_post(
-            &self.configuration,
-            name,
-            Some(schema),
-        ))
+        action_fight_my_name_action_fight_post(&self.configuration, name, Some(schema)).await
     }
 
-    pub fn rest(
+    pub async fn rest(
         &self,
         name: &str,
     ) -> Result<CharacterRestResponseSchema, Error<ActionRestMyNameActionRestPostError>> {
-        RUNTIME.block_on(action_rest_my_name_action_rest_post(
-            &self.configuration,
-            name,
-        ))
+        action_rest_my_name_action_rest_post(&self.configuration, name).await
     }
 
-    pub fn gather(
+    pub async fn gather(
         &self,
         name: &str,
     ) -> Result<SkillResponseSchema, Error<ActionGatheringMyNameActionGatheringPostError>> {
-        RUNTIME.block_on(action_gathering_my_name_action_gathering_post(
-            &self.configuration,
-            name,
-        ))
+        action_gathering_my_name_action_gathering_post(&self.configuration, name).await
     }
 
-    pub fn craft(
+    pub async fn craft(
         &self,
         name: &str,
         item_code: &str,
@@ -161,14 +144,10 @@ impl MyCharacterApi {
             code: item_code.to_owned(),
             quantity: Some(quantity),
         };
-        RUNTIME.block_on(action_crafting_my_name_action_crafting_post(
-            &self.configuration,
-            name,
-            schema,
-        ))
+        action_crafting_my_name_action_crafting_post(&self.configuration, name, schema).await
     }
 
-    pub fn recycle(
+    pub async fn recycle(
         &self,
         name: &str,
         item_code: &str,
@@ -180,28 +159,20 @@ impl MyCharacterApi {
             quantity: Some(quantity),
             enhanced: Some(enhanced),
         };
-        RUNTIME.block_on(action_recycling_my_name_action_recycling_post(
-            &self.configuration,
-            name,
-            schema,
-        ))
+        action_recycling_my_name_action_recycling_post(&self.configuration, name, schema).await
     }
 
-    pub fn delete(
+    pub async fn delete(
         &self,
         name: &str,
         item_code: &str,
         quantity: u32,
     ) -> Result<DeleteItemResponseSchema, Error<ActionDeleteItemMyNameActionDeletePostError>> {
         let schema = SimpleItemSchema::new(item_code.to_owned(), quantity);
-        RUNTIME.block_on(action_delete_item_my_name_action_delete_post(
-            &self.configuration,
-            name,
-            schema,
-        ))
+        action_delete_item_my_name_action_delete_post(&self.configuration, name, schema).await
     }
 
-    pub fn deposit_item(
+    pub async fn deposit_item(
         &self,
         name: &str,
         items: &[SimpleItemSchema],
@@ -209,16 +180,15 @@ impl MyCharacterApi {
         BankItemTransactionResponseSchema,
         Error<ActionDepositBankItemMyNameActionBankDepositItemPostError>,
     > {
-        RUNTIME.block_on(
-            action_deposit_bank_item_my_name_action_bank_deposit_item_post(
-                &self.configuration,
-                name,
-                items.to_vec(),
-            ),
+        action_deposit_bank_item_my_name_action_bank_deposit_item_post(
+            &self.configuration,
+            name,
+            items.to_vec(),
         )
+        .await
     }
 
-    pub fn withdraw_item(
+    pub async fn withdraw_item(
         &self,
         name: &str,
         items: &[SimpleItemSchema],
@@ -226,16 +196,15 @@ impl MyCharacterApi {
         BankItemTransactionResponseSchema,
         Error<ActionWithdrawBankItemMyNameActionBankWithdrawItemPostError>,
     > {
-        RUNTIME.block_on(
-            action_withdraw_bank_item_my_name_action_bank_withdraw_item_post(
-                &self.configuration,
-                name,
-                items.to_vec(),
-            ),
+        action_withdraw_bank_item_my_name_action_bank_withdraw_item_post(
+            &self.configuration,
+            name,
+            items.to_vec(),
         )
+        .await
     }
 
-    pub fn deposit_gold(
+    pub async fn deposit_gold(
         &self,
         name: &str,
         quantity: u32,
@@ -244,16 +213,11 @@ impl MyCharacterApi {
         Error<ActionDepositBankGoldMyNameActionBankDepositGoldPostError>,
     > {
         let s = DepositWithdrawGoldSchema { quantity };
-        RUNTIME.block_on(
-            action_deposit_bank_gold_my_name_action_bank_deposit_gold_post(
-                &self.configuration,
-                name,
-                s,
-            ),
-        )
+        action_deposit_bank_gold_my_name_action_bank_deposit_gold_post(&self.configuration, name, s)
+            .await
     }
 
-    pub fn withdraw_gold(
+    pub async fn withdraw_gold(
         &self,
         name: &str,
         quantity: u32,
@@ -262,125 +226,98 @@ impl MyCharacterApi {
         Error<ActionWithdrawBankGoldMyNameActionBankWithdrawGoldPostError>,
     > {
         let s = DepositWithdrawGoldSchema { quantity };
-        RUNTIME.block_on(
-            action_withdraw_bank_gold_my_name_action_bank_withdraw_gold_post(
-                &self.configuration,
-                name,
-                s,
-            ),
+        action_withdraw_bank_gold_my_name_action_bank_withdraw_gold_post(
+            &self.configuration,
+            name,
+            s,
         )
+        .await
     }
 
-    pub fn expand_bank(
+    pub async fn expand_bank(
         &self,
         name: &str,
     ) -> Result<
         BankExtensionTransactionResponseSchema,
         Error<ActionBuyBankExpansionMyNameActionBankBuyExpansionPostError>,
     > {
-        RUNTIME.block_on(
-            action_buy_bank_expansion_my_name_action_bank_buy_expansion_post(
-                &self.configuration,
-                name,
-            ),
-        )
+        action_buy_bank_expansion_my_name_action_bank_buy_expansion_post(&self.configuration, name)
+            .await
     }
 
-    pub fn equip(
+    pub async fn equip(
         &self,
         name: &str,
         items: &[EquipSchema],
     ) -> Result<EquipmentResponseSchema, Error<ActionEquipItemMyNameActionEquipPostError>> {
-        RUNTIME.block_on(action_equip_item_my_name_action_equip_post(
-            &self.configuration,
-            name,
-            items.to_vec(),
-        ))
+        action_equip_item_my_name_action_equip_post(&self.configuration, name, items.to_vec()).await
     }
 
-    pub fn unequip(
+    pub async fn unequip(
         &self,
         name: &str,
         slot: &[UnequipSchema],
     ) -> Result<EquipmentResponseSchema, Error<ActionUnequipItemMyNameActionUnequipPostError>> {
-        RUNTIME.block_on(action_unequip_item_my_name_action_unequip_post(
-            &self.configuration,
-            name,
-            slot.to_vec(),
-        ))
+        action_unequip_item_my_name_action_unequip_post(&self.configuration, name, slot.to_vec())
+            .await
     }
 
-    pub fn use_item(
+    pub async fn use_item(
         &self,
         name: &str,
         item_code: &str,
         quantity: u32,
     ) -> Result<UseItemResponseSchema, Error<ActionUseItemMyNameActionUsePostError>> {
         let schema = SimpleItemSchema::new(item_code.to_owned(), quantity);
-        RUNTIME.block_on(action_use_item_my_name_action_use_post(
-            &self.configuration,
-            name,
-            schema,
-        ))
+        action_use_item_my_name_action_use_post(&self.configuration, name, schema).await
     }
 
-    pub fn accept_task(
+    pub async fn accept_task(
         &self,
         name: &str,
     ) -> Result<TaskResponseSchema, Error<ActionAcceptNewTaskMyNameActionTaskNewPostError>> {
-        RUNTIME.block_on(action_accept_new_task_my_name_action_task_new_post(
-            &self.configuration,
-            name,
-        ))
+        action_accept_new_task_my_name_action_task_new_post(&self.configuration, name).await
     }
 
-    pub fn cancel_task(
+    pub async fn cancel_task(
         &self,
         name: &str,
     ) -> Result<TaskCancelledResponseSchema, Error<ActionTaskCancelMyNameActionTaskCancelPostError>>
     {
-        RUNTIME.block_on(action_task_cancel_my_name_action_task_cancel_post(
-            &self.configuration,
-            name,
-        ))
+        action_task_cancel_my_name_action_task_cancel_post(&self.configuration, name).await
     }
 
-    pub fn trade_task_item(
+    pub async fn trade_task_item(
         &self,
         name: &str,
         item_code: &str,
         quantity: u32,
     ) -> Result<TaskTradeResponseSchema, Error<ActionTaskTradeMyNameActionTaskTradePostError>> {
-        RUNTIME.block_on(action_task_trade_my_name_action_task_trade_post(
+        action_task_trade_my_name_action_task_trade_post(
             &self.configuration,
             name,
             SimpleItemSchema::new(item_code.to_owned(), quantity),
-        ))
+        )
+        .await
     }
 
-    pub fn complete_task(
+    pub async fn complete_task(
         &self,
         name: &str,
     ) -> Result<RewardDataResponseSchema, Error<ActionCompleteTaskMyNameActionTaskCompletePostError>>
     {
-        RUNTIME.block_on(action_complete_task_my_name_action_task_complete_post(
-            &self.configuration,
-            name,
-        ))
+        action_complete_task_my_name_action_task_complete_post(&self.configuration, name).await
     }
 
-    pub fn exchange_tasks_coins(
+    pub async fn exchange_tasks_coins(
         &self,
         name: &str,
     ) -> Result<RewardDataResponseSchema, Error<ActionTaskExchangeMyNameActionTaskExchangePostError>>
     {
-        RUNTIME.block_on(action_task_exchange_my_name_action_task_exchange_post(
-            &self.configuration,
-            name,
-        ))
+        action_task_exchange_my_name_action_task_exchange_post(&self.configuration, name).await
     }
 
-    pub fn npc_buy(
+    pub async fn npc_buy(
         &self,
         name: &str,
         code: String,
@@ -390,14 +327,10 @@ impl MyCharacterApi {
         Error<ActionNpcBuyItemMyNameActionNpcBuyPostError>,
     > {
         let schema = NpcMerchantBuySchema::new(code, quantity);
-        RUNTIME.block_on(action_npc_buy_item_my_name_action_npc_buy_post(
-            &self.configuration,
-            name,
-            schema,
-        ))
+        action_npc_buy_item_my_name_action_npc_buy_post(&self.configuration, name, schema).await
     }
 
-    pub fn npc_sell(
+    pub async fn npc_sell(
         &self,
         name: &str,
         code: String,
@@ -407,42 +340,30 @@ impl MyCharacterApi {
         Error<ActionNpcSellItemMyNameActionNpcSellPostError>,
     > {
         let schema = NpcMerchantBuySchema::new(code, quantity);
-        RUNTIME.block_on(action_npc_sell_item_my_name_action_npc_sell_post(
-            &self.configuration,
-            name,
-            schema,
-        ))
+        action_npc_sell_item_my_name_action_npc_sell_post(&self.configuration, name, schema).await
     }
 
-    pub fn give_item(
+    pub async fn give_item(
         &self,
         name: &str,
         items: &[SimpleItemSchema],
         character: &str,
     ) -> Result<GiveItemResponseSchema, Error<ActionGiveItemsMyNameActionGiveItemPostError>> {
         let schema = GiveItemsSchema::new(items.to_vec(), character.to_owned());
-        RUNTIME.block_on(action_give_items_my_name_action_give_item_post(
-            &self.configuration,
-            name,
-            schema,
-        ))
+        action_give_items_my_name_action_give_item_post(&self.configuration, name, schema).await
     }
 
-    pub fn give_gold(
+    pub async fn give_gold(
         &self,
         name: &str,
         quantity: u32,
         character: &str,
     ) -> Result<GiveGoldResponseSchema, Error<ActionGiveGoldMyNameActionGiveGoldPostError>> {
         let schema = GiveGoldSchema::new(quantity, character.to_owned());
-        RUNTIME.block_on(action_give_gold_my_name_action_give_gold_post(
-            &self.configuration,
-            name,
-            schema,
-        ))
+        action_give_gold_my_name_action_give_gold_post(&self.configuration, name, schema).await
     }
 
-    pub fn claim_pending_item(
+    pub async fn claim_pending_item(
         &self,
         name: &str,
         id: &str,
@@ -450,14 +371,11 @@ impl MyCharacterApi {
         ClaimPendingItemResponseSchema,
         Error<ActionClaimPendingItemMyNameActionClaimItemIdPostError>,
     > {
-        RUNTIME.block_on(action_claim_pending_item_my_name_action_claim_item_id_post(
-            &self.configuration,
-            name,
-            id,
-        ))
+        action_claim_pending_item_my_name_action_claim_item_id_post(&self.configuration, name, id)
+            .await
     }
 
-    pub fn ge_buy_order(
+    pub async fn ge_buy_order(
         &self,
         name: &str,
         id: &str,
@@ -467,14 +385,11 @@ impl MyCharacterApi {
         Error<ActionGeBuyItemMyNameActionGrandexchangeBuyPostError>,
     > {
         let schema = GeBuyOrderSchema::new(id.to_owned(), quantity);
-        RUNTIME.block_on(action_ge_buy_item_my_name_action_grandexchange_buy_post(
-            &self.configuration,
-            name,
-            schema,
-        ))
+        action_ge_buy_item_my_name_action_grandexchange_buy_post(&self.configuration, name, schema)
+            .await
     }
 
-    pub fn ge_create_order(
+    pub async fn ge_create_order(
         &self,
         name: &str,
         item_code: &str,
@@ -485,16 +400,15 @@ impl MyCharacterApi {
         Error<ActionGeCreateSellOrderMyNameActionGrandexchangeCreateSellOrderPostError>,
     > {
         let schema = GeOrderCreationSchema::new(item_code.to_owned(), quantity, price);
-        RUNTIME.block_on(
-            action_ge_create_sell_order_my_name_action_grandexchange_create_sell_order_post(
-                &self.configuration,
-                name,
-                schema,
-            ),
+        action_ge_create_sell_order_my_name_action_grandexchange_create_sell_order_post(
+            &self.configuration,
+            name,
+            schema,
         )
+        .await
     }
 
-    pub fn ge_cancel_order(
+    pub async fn ge_cancel_order(
         &self,
         name: &str,
         id: &str,
@@ -502,16 +416,15 @@ impl MyCharacterApi {
         GeTransactionResponseSchema,
         Error<ActionGeCancelOrderMyNameActionGrandexchangeCancelPostError>,
     > {
-        RUNTIME.block_on(
-            action_ge_cancel_order_my_name_action_grandexchange_cancel_post(
-                &self.configuration,
-                name,
-                GeCancelOrderSchema::new(id.to_owned()),
-            ),
+        action_ge_cancel_order_my_name_action_grandexchange_cancel_post(
+            &self.configuration,
+            name,
+            GeCancelOrderSchema::new(id.to_owned()),
         )
+        .await
     }
 
-    //pub fn christmas_exchange(
+    //pub async fn christmas_exchange(
     //    &self,
     //    name: &str,
     //) -> Result<
@@ -519,5 +432,292 @@ impl MyCharacterApi {
     //    Error<ActionChristmasExchangeMyNameActionChristmasExchangePostError>,
     //> {
     //    action_christmas_exchange_my_name_action_christmas_exchange_post(&self.configuration, name)
+    //        .await
     //}
 }
+
+#[derive(Default, Debug)]
+pub struct MyCharacterApi(AsyncMyCharacterApi);
+
+impl MyCharacterApi {
+    pub(crate) const fn new(api: AsyncMyCharacterApi) -> Self {
+        Self(api)
+    }
+
+    pub fn r#move(
+        &self,
+        name: &str,
+        x: i32,
+        y: i32,
+    ) -> Result<CharacterMovementResponseSchema, Error<ActionMoveMyNameActionMovePostError>> {
+        RUNTIME.block_on(self.0.r#move(name, x, y))
+    }
+
+    pub fn transition(
+        &self,
+        name: &str,
+    ) -> Result<
+        CharacterTransitionResponseSchema,
+        Error<ActionTransitionMyNameActionTransitionPostError>,
+    > {
+        RUNTIME.block_on(self.0.transition(name))
+    }
+
+    pub fn fight(
+        &self,
+        name: &str,
+        participants: Option<&[String]>,
+    ) -> Result<CharacterFightResponseSchema, Error<ActionFightMyNameActionFightPostError>> {
+        RUNTIME.block_on(self.0.fight(name, participants))
+    }
+
+    pub fn rest(
+        &self,
+        name: &str,
+    ) -> Result<CharacterRestResponseSchema, Error<ActionRestMyNameActionRestPostError>> {
+        RUNTIME.block_on(self.0.rest(name))
+    }
+
+    pub fn gather(
+        &self,
+        name: &str,
+    ) -> Result<SkillResponseSchema, Error<ActionGatheringMyNameActionGatheringPostError>> {
+        RUNTIME.block_on(self.0.gather(name))
+    }
+
+    pub fn craft(
+        &self,
+        name: &str,
+        item_code: &str,
+        quantity: u32,
+    ) -> Result<SkillResponseSchema, Error<ActionCraftingMyNameActionCraftingPostError>> {
+        RUNTIME.block_on(self.0.craft(name, item_code, quantity))
+    }
+
+    pub fn recycle(
+        &self,
+        name: &str,
+        item_code: &str,
+        quantity: u32,
+        enhanced: bool,
+    ) -> Result<RecyclingResponseSchema, Error<ActionRecyclingMyNameActionRecyclingPostError>> {
+        RUNTIME.block_on(self.0.recycle(name, item_code, quantity, enhanced))
+    }
+
+    pub fn delete(
+        &self,
+        name: &str,
+        item_code: &str,
+        quantity: u32,
+    ) -> Result<DeleteItemResponseSchema, Error<ActionDeleteItemMyNameActionDeletePostError>> {
+        RUNTIME.block_on(self.0.delete(name, item_code, quantity))
+    }
+
+    pub fn deposit_item(
+        &self,
+        name: &str,
+        items: &[SimpleItemSchema],
+    ) -> Result<
+        BankItemTransactionResponseSchema,
+        Error<ActionDepositBankItemMyNameActionBankDepositItemPostError>,
+    > {
+        RUNTIME.block_on(self.0.deposit_item(name, items))
+    }
+
+    pub fn withdraw_item(
+        &self,
+        name: &str,
+        items: &[SimpleItemSchema],
+    ) -> Result<
+        BankItemTransactionResponseSchema,
+        Error<ActionWithdrawBankItemMyNameActionBankWithdrawItemPostError>,
+    > {
+        RUNTIME.block_on(self.0.withdraw_item(name, items))
+    }
+
+    pub fn deposit_gold(
+        &self,
+        name: &str,
+        quantity: u32,
+    ) -> Result<
+        BankGoldTransactionResponseSchema,
+        Error<ActionDepositBankGoldMyNameActionBankDepositGoldPostError>,
+    > {
+        RUNTIME.block_on(self.0.deposit_gold(name, quantity))
+    }
+
+    pub fn withdraw_gold(
+        &self,
+        name: &str,
+        quantity: u32,
+    ) -> Result<
+        BankGoldTransactionResponseSchema,
+        Error<ActionWithdrawBankGoldMyNameActionBankWithdrawGoldPostError>,
+    > {
+        RUNTIME.block_on(self.0.withdraw_gold(name, quantity))
+    }
+
+    pub fn expand_bank(
+        &self,
+        name: &str,
+    ) -> Result<
+        BankExtensionTransactionResponseSchema,
+        Error<ActionBuyBankExpansionMyNameActionBankBuyExpansionPostError>,
+    > {
+        RUNTIME.block_on(self.0.expand_bank(name))
+    }
+
+    pub fn equip(
+        &self,
+        name: &str,
+        items: &[EquipSchema],
+    ) -> Result<EquipmentResponseSchema, Error<ActionEquipItemMyNameActionEquipPostError>> {
+        RUNTIME.block_on(self.0.equip(name, items))
+    }
+
+    pub fn unequip(
+        &self,
+        name: &str,
+        slot: &[UnequipSchema],
+    ) -> Result<EquipmentResponseSchema, Error<ActionUnequipItemMyNameActionUnequipPostError>> {
+        RUNTIME.block_on(self.0.unequip(name, slot))
+    }
+
+    pub fn use_item(
+        &self,
+        name: &str,
+        item_code: &str,
+        quantity: u32,
+    ) -> Result<UseItemResponseSchema, Error<ActionUseItemMyNameActionUsePostError>> {
+        RUNTIME.block_on(self.0.use_item(name, item_code, quantity))
+    }
+
+    pub fn accept_task(
+        &self,
+        name: &str,
+    ) -> Result<TaskResponseSchema, Error<ActionAcceptNewTaskMyNameActionTaskNewPostError>> {
+        RUNTIME.block_on(self.0.accept_task(name))
+    }
+
+    pub fn cancel_task(
+        &self,
+        name: &str,
+    ) -> Result<TaskCancelledResponseSchema, Error<ActionTaskCancelMyNameActionTaskCancelPostError>>
+    {
+        RUNTIME.block_on(self.0.cancel_task(name))
+    }
+
+    pub fn trade_task_item(
+        &self,
+        name: &str,
+        item_code: &str,
+        quantity: u32,
+    ) -> Result<TaskTradeResponseSchema, Error<ActionTaskTradeMyNameActionTaskTradePostError>> {
+        RUNTIME.block_on(self.0.trade_task_item(name, item_code, quantity))
+    }
+
+    pub fn complete_task(
+        &self,
+        name: &str,
+    ) -> Result<RewardDataResponseSchema, Error<ActionCompleteTaskMyNameActionTaskCompletePostError>>
+    {
+        RUNTIME.block_on(self.0.complete_task(name))
+    }
+
+    pub fn exchange_tasks_coins(
+        &self,
+        name: &str,
+    ) -> Result<RewardDataResponseSchema, Error<ActionTaskExchangeMyNameActionTaskExchangePostError>>
+    {
+        RUNTIME.block_on(self.0.exchange_tasks_coins(name))
+    }
+
+    pub fn npc_buy(
+        &self,
+        name: &str,
+        code: String,
+        quantity: u32,
+    ) -> Result<
+        NpcMerchantTransactionResponseSchema,
+        Error<ActionNpcBuyItemMyNameActionNpcBuyPostError>,
+    > {
+        RUNTIME.block_on(self.0.npc_buy(name, code, quantity))
+    }
+
+    pub fn npc_sell(
+        &self,
+        name: &str,
+        code: String,
+        quantity: u32,
+    ) -> Result<
+        NpcMerchantTransactionResponseSchema,
+        Error<ActionNpcSellItemMyNameActionNpcSellPostError>,
+    > {
+        RUNTIME.block_on(self.0.npc_sell(name, code, quantity))
+    }
+
+    pub fn give_item(
+        &self,
+        name: &str,
+        items: &[SimpleItemSchema],
+        character: &str,
+    ) -> Result<GiveItemResponseSchema, Error<ActionGiveItemsMyNameActionGiveItemPostError>> {
+        RUNTIME.block_on(self.0.give_item(name, items, character))
+    }
+
+    pub fn give_gold(
+        &self,
+        name: &str,
+        quantity: u32,
+        character: &str,
+    ) -> Result<GiveGoldResponseSchema, Error<ActionGiveGoldMyNameActionGiveGoldPostError>> {
+        RUNTIME.block_on(self.0.give_gold(name, quantity, character))
+    }
+
+    pub fn claim_pending_item(
+        &self,
+        name: &str,
+        id: &str,
+    ) -> Result<
+        ClaimPendingItemResponseSchema,
+        Error<ActionClaimPendingItemMyNameActionClaimItemIdPostError>,
+    > {
+        RUNTIME.block_on(self.0.claim_pending_item(name, id))
+    }
+
+    pub fn ge_buy_order(
+        &self,
+        name: &str,
+        id: &str,
+        quantity: u32,
+    ) -> Result<
+        GeTransactionResponseSchema,
+        Error<ActionGeBuyItemMyNameActionGrandexchangeBuyPostError>,
+    > {
+        RUNTIME.block_on(self.0.ge_buy_order(name, id, quantity))
+    }
+
+    pub fn ge_create_order(
+        &self,
+        name: &str,
+        item_code: &str,
+        quantity: u32,
+        price: u32,
+    ) -> Result<
+        GeCreateOrderTransactionResponseSchema,
+        Error<ActionGeCreateSellOrderMyNameActionGrandexchangeCreateSellOrderPostError>,
+    > {
+        RUNTIME.block_on(self.0.ge_create_order(name, item_code, quantity, price))
+    }
+
+    pub fn ge_cancel_order(
+        &self,
+        name: &str,
+        id: &str,
+    ) -> Result<
+        GeTransactionResponseSchema,
+        Error<ActionGeCancelOrderMyNameActionGrandexchangeCancelPostError>,
+    > {
+        RUNTIME.block_on(self.0.ge_cancel_order(name, id))
+    }
+}
@@ -12,28 +12,49 @@ use openapi::{
 };
 use std::sync::Arc;
 
-#[derive(Default, Debug)]
-pub struct NpcsApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncNpcsApi {
     configuration: Arc<Configuration>,
 }
 
-impl NpcsApi {
+impl AsyncNpcsApi {
     pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
-    pub fn get_all(&self) -> Result<Vec<NpcSchema>, Error<GetAllNpcsNpcsDetailsGetError>> {
+    pub async fn get_all(&self) -> Result<Vec<NpcSchema>, Error<GetAllNpcsNpcsDetailsGetError>> {
         NpcsRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
     }
 
-    pub fn get_items(&self) -> Result<Vec<NpcItemSchema>, Error<GetAllNpcsItemsNpcsItemsGetError>> {
+    pub async fn get_items(
+        &self,
+    ) -> Result<Vec<NpcItemSchema>, Error<GetAllNpcsItemsNpcsItemsGetError>> {
         NpcsItemsRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct NpcsApi(AsyncNpcsApi);
+
+impl NpcsApi {
+    pub(crate) const fn new(api: AsyncNpcsApi) -> Self {
+        Self(api)
+    }
+
+    pub fn get_all(&self) -> Result<Vec<NpcSchema>, Error<GetAllNpcsNpcsDetailsGetError>> {
+        RUNTIME.block_on(self.0.get_all())
+    }
+
+    pub fn get_items(&self) -> Result<Vec<NpcItemSchema>, Error<GetAllNpcsItemsNpcsItemsGetError>> {
+        RUNTIME.block_on(self.0.get_items())
     }
 }
 
@@ -46,8 +67,8 @@ impl Paginate for NpcsRequest<'_> {
     type Page = StaticDataPageNpcSchema;
     type Error = GetAllNpcsNpcsDetailsGetError;
 
-    fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_all_npcs_npcs_details_get(
+    async fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_all_npcs_npcs_details_get(
             self.configuration,
             None,
             None,
@@ -55,7 +76,8 @@ impl Paginate for NpcsRequest<'_> {
             None,
             Some(page),
             Some(100),
-        ))
+        )
+        .await
     }
 }
 
@@ -78,15 +100,16 @@ impl Paginate for NpcsItemsRequest<'_> {
     type Page = StaticDataPageNpcItemSchema;
     type Error = GetAllNpcsItemsNpcsItemsGetError;
 
-    fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_all_npcs_items_npcs_items_get(
+    async fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_all_npcs_items_npcs_items_get(
             self.configuration,
             None,
             None,
             None,
             Some(page),
             Some(100),
-        ))
+        )
+        .await
     }
 }
 
@@ -1,44 +1,182 @@
-use governor::{
-    Quota, RateLimiter,
-    clock::{Clock, DefaultClock},
-    state::InMemoryState,
-    state::NotKeyed,
-};
+use crate::AsyncAccountApi;
+use governor::{Quota, RateLimiter, clock::DefaultClock, state::InMemoryState, state::NotKeyed};
 use http::Extensions;
+use log::{debug, warn};
+use openapi::{
+    apis::{Error, configuration::Configuration, my_account_api::GetRateLimitsMyRatesGetError},
+    models::{RateLimitScopeSchema, RateLimitWindowSchema, RateLimitsDataSchema},
+};
 use reqwest::{Request, Response};
 use reqwest_middleware::{Middleware, Next, Result};
-use std::{num::NonZeroU32, thread::sleep};
+use std::{num::NonZeroU32, sync::Arc, time::Duration};
+use tokio::sync::OnceCell;
+
+type Limiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
 
 const fn nz(n: u32) -> NonZeroU32 {
     NonZeroU32::new(n).expect("rate limit value must be non-zero")
 }
 
-/// block the current thread via `std::thread::sleep` until a
-/// token is available.
-fn wait_for(limiter: &RateLimiter<NotKeyed, InMemoryState, DefaultClock>) {
-    loop {
-        match limiter.check() {
-            Ok(()) => return,
-            Err(not_until) => {
-                sleep(not_until.wait_time_from(limiter.clock().now()));
-            }
+/// Waits until a token is available, yielding to the runtime instead of
+/// blocking the worker thread.
+async fn wait_for(limiter: &Limiter) {
+    limiter.until_ready().await;
+}
+
+/// Rate limit scopes published by the server through `/my/rates`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Action,
+    Data,
+    Account,
+    Simulation,
+}
+
+impl Scope {
+    /// Classifies a request into the scope it is counted against by the server,
+    /// based on its path.
+    pub fn of(req: &Request) -> Self {
+        Self::of_path(req.url().path())
+    }
+
+    fn of_path(path: &str) -> Self {
+        let segments = path.trim_start_matches('/').split('/').collect::<Vec<_>>();
+        match segments.as_slice() {
+            ["my", _, "action", ..] => Self::Action,
+            ["simulation", ..] => Self::Simulation,
+            ["my" | "token", ..]
+            | ["accounts", "create" | "forgot_password" | "reset_password"]
+            | ["characters", "create" | "delete"] => Self::Account,
+            _ => Self::Data,
         }
     }
 }
 
 #[derive(Debug)]
 pub struct RateLimiterMiddleware {
-    limiter: RateLimiter<NotKeyed, InMemoryState, DefaultClock>,
+    /// Base path and token the limits are loaded with on the first request
+    /// when they were not given upfront.
+    remote: Option<(String, String)>,
+    limiters: OnceCell<Limiters>,
 }
 
 impl RateLimiterMiddleware {
+    /// Enforces the given `limits`, see [`Limiters::new`].
+    #[must_use]
+    pub fn new(limits: &RateLimitsDataSchema) -> Self {
+        Self {
+            remote: None,
+            limiters: OnceCell::new_with(Some(Limiters::new(limits))),
+        }
+    }
+
+    /// Enforces the limits published by the server at `base_path`, loaded
+    /// from `/my/rates` when the first request goes through.
     #[must_use]
-    pub fn new() -> Self {
+    pub fn lazy(base_path: String, token: String) -> Self {
+        Self {
+            remote: Some((base_path, token)),
+            limiters: OnceCell::new(),
+        }
+    }
+
+    async fn limiters(&self, scope: Scope) -> &[Limiter] {
+        self.limiters
+            .get_or_init(|| async {
+                let Some((base_path, token)) = &self.remote else {
+                    return Limiters::fallback();
+                };
+                load_rate_limits(base_path, token).await.map_or_else(
+                    |e| {
+                        warn!("failed to load rate limits, using fallback: {e}");
+                        Limiters::fallback()
+                    },
+                    |limits| Limiters::new(&limits),
+                )
+            })
+            .await
+            .of(scope)
+    }
+}
+
+/// Fetches the account rate limits with a client that is not rate limited
+/// itself, since the limiter depends on them.
+async fn load_rate_limits(
+    base_path: &str,
+    token: &str,
+) -> std::result::Result<RateLimitsDataSchema, Error<GetRateLimitsMyRatesGetError>> {
+    let conf = Configuration {
+        base_path: base_path.to_owned(),
+        bearer_access_token: Some(token.to_owned()),
+        ..Default::default()
+    };
+    AsyncAccountApi::new(Arc::new(conf)).rate_limits().await
+}
+
+#[derive(Debug)]
+struct Limiters {
+    action: Vec<Limiter>,
+    data: Vec<Limiter>,
+    account: Vec<Limiter>,
+    simulation: Vec<Limiter>,
+}
+
+impl Limiters {
+    /// Builds one limiter per window of each scope published by the server.
+    /// Requests already made in the current windows are deducted from the
+    /// initial budget.
+    fn new(limits: &RateLimitsDataSchema) -> Self {
+        Self {
+            action: limiters(&limits.action),
+            data: limiters(&limits.data),
+            account: limiters(&limits.account),
+            simulation: limiters(&limits.simulation),
+        }
+    }
+
+    /// Limits used when the server ones could not be loaded.
+    fn fallback() -> Self {
+        // Server used to enforce a hard global limit of 10 requests/second.
+        let limiter = || vec![RateLimiter::direct(Quota::per_second(nz(10)))];
         Self {
-            // Server enforces a hard global limit of 10 requests/second.
-            limiter: RateLimiter::direct(Quota::per_second(nz(10))),
+            action: limiter(),
+            data: limiter(),
+            account: limiter(),
+            simulation: limiter(),
         }
     }
+
+    fn of(&self, scope: Scope) -> &[Limiter] {
+        match scope {
+            Scope::Action => &self.action,
+            Scope::Data => &self.data,
+            Scope::Account => &self.account,
+            Scope::Simulation => &self.simulation,
+        }
+    }
+}
+
+fn limiters(scope: &RateLimitScopeSchema) -> Vec<Limiter> {
+    [
+        (&scope.second, Duration::from_secs(1)),
+        (&scope.minute, Duration::from_mins(1)),
+        (&scope.hour, Duration::from_hours(1)),
+        (&scope.day, Duration::from_hours(24)),
+    ]
+    .into_iter()
+    .filter_map(|(window, period)| window.as_ref().and_then(|w| limiter(w, period)))
+    .collect()
+}
+
+fn limiter(window: &RateLimitWindowSchema, period: Duration) -> Option<Limiter> {
+    let limit = NonZeroU32::new(u32::try_from(window.limit).ok()?)?;
+    let quota = Quota::with_period(period / limit.get())?.allow_burst(limit);
+    let limiter = RateLimiter::direct(quota);
+    let used = window.limit.saturating_sub(window.remaining.max(0));
+    if let Some(used) = NonZeroU32::new(u32::try_from(used).unwrap_or(0)) {
+        let _ = limiter.check_n(used);
+    }
+    Some(limiter)
 }
 
 #[async_trait::async_trait]
@@ -49,7 +187,11 @@ impl Middleware for RateLimiterMiddleware {
         extensions: &mut Extensions,
         next: Next<'_>,
     ) -> Result<Response> {
-        wait_for(&self.limiter);
+        let scope = Scope::of(&req);
+        debug!("rate limiting {} as {scope:?}", req.url().path());
+        for limiter in self.limiters(scope).await {
+            wait_for(limiter).await;
+        }
 
         next.run(req, extensions).await
     }
@@ -67,15 +209,16 @@ mod tests {
 
     #[test]
     fn enforces_per_second_rate() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
         let lim = RateLimiter::direct(Quota::per_second(nz(8)).allow_burst(nz(1)));
         let start = Instant::now();
 
         // First call should be near-instant.
-        wait_for(&lim);
+        rt.block_on(wait_for(&lim));
         assert!(start.elapsed() < Duration::from_millis(10));
 
         // Second immediate call must wait.
-        wait_for(&lim);
+        rt.block_on(wait_for(&lim));
         let elapsed = start.elapsed();
         // With burst=1, second call must wait at least ~125ms (1/8s).
         assert!(
@@ -86,12 +229,15 @@ mod tests {
 
     #[test]
     fn sequential_rate_limit() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
         let lim = RateLimiter::direct(Quota::per_second(nz(8)).allow_burst(nz(1)));
         let n = 16;
         let start = Instant::now();
-        for _ in 0..n {
-            wait_for(&lim);
-        }
+        rt.block_on(async {
+            for _ in 0..n {
+                wait_for(&lim).await;
+            }
+        });
         let elapsed = start.elapsed();
         // 16 calls with burst=1: first is instant, 15 more at ~125ms each = ~1875ms.
         let min_expected = Duration::from_millis((n - 1) * 120);
@@ -102,36 +248,96 @@ mod tests {
     }
 
     #[test]
-    fn concurrency_stress_inside_block_on() {
-        let rt = tokio::runtime::Runtime::new().unwrap();
+    fn concurrency_stress_on_runtime() {
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()
+            .unwrap();
         let lim = Arc::new(RateLimiter::direct(
             Quota::per_second(nz(8)).allow_burst(nz(1)),
         ));
-        let thread_count = 14;
-        let calls_per_thread = 3;
-        let total_calls = thread_count * calls_per_thread;
+        let task_count = 14;
+        let calls_per_task = 3;
+        let total_calls = task_count * calls_per_task;
 
-        let rt_ref = &rt;
         let start = Instant::now();
-        std::thread::scope(|s| {
-            for _ in 0..thread_count {
-                let lim = lim.clone();
-                s.spawn(move || {
-                    rt_ref.block_on(async {
-                        for _ in 0..calls_per_thread {
-                            wait_for(&lim);
+        rt.block_on(async {
+            let handles = (0..task_count)
+                .map(|_| {
+                    let lim = lim.clone();
+                    tokio::spawn(async move {
+                        for _ in 0..calls_per_task {
+                            wait_for(&lim).await;
                         }
-                    });
-                });
+                    })
+                })
+                .collect::<Vec<_>>();
+            for h in handles {
+                h.await.unwrap();
             }
         });
         let elapsed = start.elapsed();
 
-        // 42 calls at 8/s with burst=1: first instant, remaining 41 at ~125ms each.
+        // 42 calls at 8/s with burst=1 on 2 workers: first instant, remaining 41
+        // at ~125ms each.
         let min_expected = Duration::from_millis((total_calls - 1) * 120);
         assert!(
             elapsed >= min_expected,
-            "{total_calls} concurrent calls inside block_on took {elapsed:?}, expected ≥ {min_expected:?}"
+            "{total_calls} concurrent calls took {elapsed:?}, expected ≥ {min_expected:?}"
         );
     }
+
+    #[test]
+    fn classifies_request_scopes() {
+        assert_eq!(Scope::of_path("/my/char1/action/fight"), Scope::Action);
+        assert_eq!(Scope::of_path("/my/bank/items"), Scope::Account);
+        assert_eq!(Scope::of_path("/my/logs/char1"), Scope::Account);
+        assert_eq!(Scope::of_path("/characters/create"), Scope::Account);
+        assert_eq!(Scope::of_path("/simulation/fight"), Scope::Simulation);
+        assert_eq!(Scope::of_path("/items"), Scope::Data);
+        assert_eq!(Scope::of_path("/characters/char1"), Scope::Data);
+        assert_eq!(Scope::of_path("/accounts/acc1/characters"), Scope::Data);
+    }
+
+    #[test]
+    fn window_budget_is_reduced_by_used_requests() {
+        let window = RateLimitWindowSchema {
+            limit: 5,
+            remaining: 2,
+            ..Default::default()
+        };
+        let lim = limiter(&window, Duration::from_mins(1)).unwrap();
+
+        assert!(lim.check().is_ok());
+        assert!(lim.check().is_ok());
+        assert!(lim.check().is_err());
+    }
+
+    #[test]
+    fn lazy_limits_fall_back_when_unreachable() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let middleware = RateLimiterMiddleware::lazy("http://127.0.0.1:1".into(), String::new());
+
+        assert!(middleware.limiters.get().is_none());
+        assert_eq!(rt.block_on(middleware.limiters(Scope::Data)).len(), 1);
+        assert!(middleware.limiters.initialized());
+    }
+
+    #[test]
+    fn one_limiter_per_published_window() {
+        let window = |limit| RateLimitWindowSchema {
+            limit,
+            remaining: limit,
+            ..Default::default()
+        };
+        let scope = RateLimitScopeSchema {
+            second: Some(window(20)),
+            minute: Some(window(200)),
+            hour: None,
+            day: Some(window(0)),
+        };
+
+        assert_eq!(limiters(&scope).len(), 2);
+    }
 }
@@ -9,21 +9,37 @@ use openapi::{
 };
 use std::sync::Arc;
 
-#[derive(Default, Debug)]
-pub struct ResourcesApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncResourcesApi {
     configuration: Arc<Configuration>,
 }
 
-impl ResourcesApi {
+impl AsyncResourcesApi {
     pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
-    pub fn get_all(&self) -> Result<Vec<ResourceSchema>, Error<GetAllResourcesResourcesGetError>> {
+    pub async fn get_all(
+        &self,
+    ) -> Result<Vec<ResourceSchema>, Error<GetAllResourcesResourcesGetError>> {
         ResourcesRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct ResourcesApi(AsyncResourcesApi);
+
+impl ResourcesApi {
+    pub(crate) const fn new(api: AsyncResourcesApi) -> Self {
+        Self(api)
+    }
+
+    pub fn get_all(&self) -> Result<Vec<ResourceSchema>, Error<GetAllResourcesResourcesGetError>> {
+        RUNTIME.block_on(self.0.get_all())
     }
 }
 
@@ -36,8 +52,8 @@ impl Paginate for ResourcesRequest<'_> {
     type Page = StaticDataPageResourceSchema;
     type Error = GetAllResourcesResourcesGetError;
 
-    fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_all_resources_resources_get(
+    async fn request_page(&self, page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_all_resources_resources_get(
             self.configuration,
             None,
             None,
@@ -45,7 +61,8 @@ impl Paginate for ResourcesRequest<'_> {
             None,
             Some(page),
             Some(100),
-        ))
+        )
+        .await
     }
 }
 
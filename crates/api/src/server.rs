@@ -5,21 +5,34 @@ use openapi::{
 };
 use std::sync::Arc;
 
-#[derive(Default, Debug)]
-pub struct ServerApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncServerApi {
     configuration: Arc<Configuration>,
 }
 
-impl ServerApi {
+impl AsyncServerApi {
     pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
+    //TODO: return result
+    #[must_use]
+    pub async fn status(&self) -> Option<StatusResponseSchema> {
+        get_server_details_get(&self.configuration).await.ok()
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct ServerApi(AsyncServerApi);
+
+impl ServerApi {
+    pub(crate) const fn new(api: AsyncServerApi) -> Self {
+        Self(api)
+    }
+
     //TODO: return result
     #[must_use]
     pub fn status(&self) -> Option<StatusResponseSchema> {
-        RUNTIME
-            .block_on(get_server_details_get(&self.configuration))
-            .ok()
+        RUNTIME.block_on(self.0.status())
     }
 }
@@ -0,0 +1,60 @@
+use crate::RUNTIME;
+use openapi::{
+    apis::{
+        Error,
+        configuration::Configuration,
+        simulation_api::{
+            FightSimulationSimulationFightPostError, fight_simulation_simulation_fight_post,
+        },
+    },
+    models::{CombatSimulationRequestSchema, CombatSimulationResponseSchema, FakeCharacterSchema},
+};
+use std::sync::Arc;
+
+#[derive(Default, Debug, Clone)]
+pub struct AsyncSimulationApi {
+    configuration: Arc<Configuration>,
+}
+
+impl AsyncSimulationApi {
+    pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
+        Self { configuration }
+    }
+
+    /// Runs `iterations` fights between the given `characters` and `monster`
+    /// on the server. Requires a member account.
+    pub async fn fight(
+        &self,
+        characters: Vec<FakeCharacterSchema>,
+        monster: &str,
+        iterations: u32,
+    ) -> Result<CombatSimulationResponseSchema, Error<FightSimulationSimulationFightPostError>>
+    {
+        fight_simulation_simulation_fight_post(
+            &self.configuration,
+            CombatSimulationRequestSchema::new(characters, monster.to_owned(), iterations),
+        )
+        .await
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct SimulationApi(AsyncSimulationApi);
+
+impl SimulationApi {
+    pub(crate) const fn new(api: AsyncSimulationApi) -> Self {
+        Self(api)
+    }
+
+    /// Runs `iterations` fights between the given `characters` and `monster`
+    /// on the server. Requires a member account.
+    pub fn fight(
+        &self,
+        characters: Vec<FakeCharacterSchema>,
+        monster: &str,
+        iterations: u32,
+    ) -> Result<CombatSimulationResponseSchema, Error<FightSimulationSimulationFightPostError>>
+    {
+        RUNTIME.block_on(self.0.fight(characters, monster, iterations))
+    }
+}
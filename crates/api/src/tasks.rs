@@ -14,30 +14,53 @@ use openapi::{
 };
 use std::sync::Arc;
 
-#[derive(Default, Debug)]
-pub struct TasksApi {
+#[derive(Default, Debug, Clone)]
+pub struct AsyncTasksApi {
     configuration: Arc<Configuration>,
 }
 
-impl TasksApi {
+impl AsyncTasksApi {
     pub(crate) const fn new(configuration: Arc<Configuration>) -> Self {
         Self { configuration }
     }
 
-    pub fn get_all(&self) -> Result<Vec<TaskFullSchema>, Error<GetAllTasksTasksListGetError>> {
+    pub async fn get_all(
+        &self,
+    ) -> Result<Vec<TaskFullSchema>, Error<GetAllTasksTasksListGetError>> {
         TasksRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
     }
 
-    pub fn get_rewards(
+    pub async fn get_rewards(
         &self,
     ) -> Result<Vec<DropRateSchema>, Error<GetAllTasksRewardsTasksRewardsGetError>> {
         TasksRewardsRequest {
             configuration: &self.configuration,
         }
         .send()
+        .await
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct TasksApi(AsyncTasksApi);
+
+impl TasksApi {
+    pub(crate) const fn new(api: AsyncTasksApi) -> Self {
+        Self(api)
+    }
+
+    pub fn get_all(&self) -> Result<Vec<TaskFullSchema>, Error<GetAllTasksTasksListGetError>> {
+        RUNTIME.block_on(self.0.get_all())
+    }
+
+    pub fn get_rewards(
+        &self,
+    ) -> Result<Vec<DropRateSchema>, Error<GetAllTasksRewardsTasksRewardsGetError>> {
+        RUNTIME.block_on(self.0.get_rewards())
     }
 }
 
@@ -50,8 +73,8 @@ impl Paginate for TasksRequest<'_> {
     type Page = StaticDataPageTaskFullSchema;
     type Error = GetAllTasksTasksListGetError;
 
-    fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_all_tasks_tasks_list_get(
+    async fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_all_tasks_tasks_list_get(
             self.configuration,
             None,
             None,
@@ -59,7 +82,8 @@ impl Paginate for TasksRequest<'_> {
             None,
             Some(current_page),
             Some(100),
-        ))
+        )
+        .await
     }
 }
 
@@ -82,12 +106,9 @@ impl Paginate for TasksRewardsRequest<'_> {
     type Page = StaticDataPageDropRateSchema;
     type Error = GetAllTasksRewardsTasksRewardsGetError;
 
-    fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
-        RUNTIME.block_on(get_all_tasks_rewards_tasks_rewards_get(
-            self.configuration,
-            Some(current_page),
-            Some(100),
-        ))
+    async fn request_page(&self, current_page: u32) -> Result<Self::Page, Error<Self::Error>> {
+        get_all_tasks_rewards_tasks_rewards_get(self.configuration, Some(current_page), Some(100))
+            .await
     }
 }
 
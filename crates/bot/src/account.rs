@@ -1,16 +1,25 @@
 use crate::{
-    bank::BankController, bot_config::BotConfig, character::CharacterController,
-    gear_finder::GearFinder, leveling_helper::LevelingHelper, orderboard::OrderBoard,
+    bank::BankController,
+    bot_config::BotConfig,
+    character::CharacterController,
+    gear_finder::{GearFinder, GearRequest},
+    leveling_helper::LevelingHelper,
+    orderboard::OrderBoard,
+    reservable::Reservable,
 };
 use derive_more::Deref;
 use itertools::Itertools;
 use sdk::{
-    AccountClient, Client, Code, CollectionClient, ItemContainer, ItemsClient, NpcsClient, Skill,
-    SpaceLimited,
-    entities::{Character, Item},
+    AccountClient, Client, Code, CollectionClient, ItemContainer, ItemsClient, NpcsClient,
+    Quantity, Skill, SpaceLimited,
+    entities::{Character, CharacterName, Item},
+    gear::Gear,
     items::ItemSource,
 };
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+};
 
 #[derive(Clone, Deref)]
 #[deref(forward)]
@@ -23,6 +32,16 @@ pub struct AccountControllerInner {
     npcs: NpcsClient,
     pub bank: BankController,
     pub characters: RwLock<Vec<CharacterController>>,
+    /// Gear allocated to each character by [`AccountController::allocate_gear`],
+    /// kept until the account equipment or what the characters do change.
+    allocation: Mutex<Option<Allocation>>,
+}
+
+/// Gear allocation along with the requests and pool it was computed from.
+struct Allocation {
+    requests: Vec<GearRequest>,
+    pool: HashMap<String, u32>,
+    gears: HashMap<CharacterName, Gear>,
 }
 
 impl AccountController {
@@ -42,6 +61,7 @@ impl AccountController {
                 bank,
                 npcs,
                 characters: RwLock::default(),
+                allocation: Mutex::default(),
             }
             .into(),
         )
@@ -118,6 +138,105 @@ impl AccountController {
             .sum()
     }
 
+    /// Returns the quantity of each item held in the bank and available in the
+    /// inventories of the characters.
+    #[must_use]
+    pub fn available_items(&self) -> HashMap<String, u32> {
+        let mut items = self
+            .bank
+            .content()
+            .iter()
+            .map(|i| (i.code().to_owned(), i.quantity()))
+            .collect::<HashMap<_, _>>();
+        for char in self.characters() {
+            for (code, quantity) in char.inventory.available_items() {
+                *items.entry(code).or_default() += quantity;
+            }
+        }
+        items
+    }
+
+    /// Returns the gear allocated to the character `name`, computing the
+    /// allocation of the account again if the equipable items of the bank or
+    /// the gear requests of the characters changed since the last call, see
+    /// [`Self::allocate_gear`]. Items that cannot be equipped are left out of
+    /// both, so that they do not trigger a new allocation.
+    ///
+    /// The allocation is computed without holding the lock, the one stored
+    /// being replaced only if it was not computed from the same requests and
+    /// pool in the meantime.
+    #[must_use]
+    pub fn allocated_gear(&self, name: &CharacterName, gear_finder: &GearFinder) -> Option<Gear> {
+        let requests = self.gear_requests();
+        let pool = self.gear_pool();
+        let is_current = |a: &Option<Allocation>| {
+            a.as_ref()
+                .is_some_and(|a| a.requests == requests && a.pool == pool)
+        };
+        {
+            let allocation = self.allocation.lock().unwrap();
+            if is_current(&allocation) {
+                return allocation.as_ref()?.gears.get(name).cloned();
+            }
+        }
+        let gears = self.allocate(gear_finder, requests.clone(), pool.clone());
+        let gear = gears.get(name).cloned();
+        let mut allocation = self.allocation.lock().unwrap();
+        if !is_current(&allocation) {
+            *allocation = Some(Allocation {
+                requests,
+                pool,
+                gears,
+            });
+        }
+        gear
+    }
+
+    /// Shares the equipment of the account between the characters according
+    /// to what they are currently doing, see [`GearAllocator::resolve`].
+    ///
+    /// The shared pool is made of the equipable items not reserved in the
+    /// bank, each character also getting the items it equips, the ones
+    /// available in its inventory and the ones reserved to it in the bank,
+    /// which the others cannot take.
+    ///
+    /// [`GearAllocator::resolve`]: crate::gear_finder::GearAllocator::resolve
+    #[must_use]
+    pub fn allocate_gear(&self, gear_finder: &GearFinder) -> HashMap<CharacterName, Gear> {
+        self.allocate(gear_finder, self.gear_requests(), self.gear_pool())
+    }
+
+    fn allocate(
+        &self,
+        gear_finder: &GearFinder,
+        requests: Vec<GearRequest>,
+        pool: HashMap<String, u32>,
+    ) -> HashMap<CharacterName, Gear> {
+        gear_finder
+            .allocate(requests, pool)
+            .with_excluded_items(self.config.excluded_items())
+            .resolve()
+    }
+
+    fn gear_requests(&self) -> Vec<GearRequest> {
+        self.characters()
+            .iter()
+            .filter_map(CharacterController::gear_request)
+            .collect_vec()
+    }
+
+    fn gear_pool(&self) -> HashMap<String, u32> {
+        self.bank
+            .content()
+            .iter()
+            .filter(|i| self.items.get(i.code()).is_some_and(|i| i.is_equipable()))
+            .map(|i| {
+                let unreserved = i.quantity().saturating_sub(self.bank.reserved(i.code()));
+                (i.code().to_owned(), unreserved)
+            })
+            .collect()
+    }
+
     #[must_use]
     pub fn total_of(&self, item: &str) -> u32 {
         self.bank.total_of(item)
@@ -147,6 +147,15 @@ impl BankController {
             .sum()
     }
 
+    /// Returns the items reserved to the given `owner`.
+    pub(crate) fn reserved_to(&self, owner: &CharacterName) -> HashMap<String, u32> {
+        self.reservations()
+            .iter()
+            .filter(|(k, _)| k.owner == *owner)
+            .map(|(k, q)| (k.item.clone(), *q))
+            .collect()
+    }
+
     pub(crate) fn available_for(&self, name: &CharacterName) -> HashMap<String, u32> {
         self.content()
             .iter()
@@ -3,10 +3,11 @@ use figment::{
     Figment,
     providers::{Format, Toml},
 };
-use sdk::{models::TaskType, skill::Skill};
+use sdk::{ClientBuilder, models::TaskType, skill::Skill};
 use serde::Deserialize;
 use std::{
     collections::HashSet,
+    env,
     fmt::Display,
     sync::{
         Arc, RwLock,
@@ -15,6 +16,28 @@ use std::{
 };
 use strum_macros::{AsRefStr, EnumIs, EnumIter, EnumString};
 
+const CONFIG_FILE: &str = "ArtifactsMMO.toml";
+const TOKEN_VAR: &str = "ARTIFACTSMMO_TOKEN";
+/// Minutes given by default to the orders of a gear roadmap before the task
+/// needing it is cancelled.
+const DEFAULT_GEAR_ROADMAP_DEADLINE: u32 = 120;
+
+/// Returns a client builder configured from the config file.
+///
+/// The optional `[client]` table can set the `url`, `token`, `cache_dir` and
+/// `cache_ttl`.
+/// The token is taken from the `ARTIFACTSMMO_TOKEN` environment variable when
+/// set.
+pub fn client_builder() -> anyhow::Result<ClientBuilder> {
+    let builder: ClientBuilder = Figment::from(Toml::file(CONFIG_FILE))
+        .focus("client")
+        .extract()?;
+    Ok(match env::var(TOKEN_VAR) {
+        Ok(token) => builder.token(token),
+        Err(_) => builder,
+    })
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct BotConfig {
     inner: Arc<RwLock<Arc<BotConfigInner>>>,
@@ -28,6 +51,13 @@ impl BotConfig {
         }
     }
 
+    /// Parses the config from the `content` of a config file.
+    pub fn from_toml(content: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            inner: RwLock::new(Arc::new(Figment::from(Toml::string(content)).extract()?)).into(),
+        })
+    }
+
     pub fn reload(&self) {
         *self.inner.write().unwrap() = BotConfigInner::from_file().into();
     }
@@ -42,6 +72,14 @@ impl BotConfig {
         self.inner().excluded_items.clone()
     }
 
+    /// Returns the minutes given to the orders of a gear roadmap.
+    #[must_use]
+    pub fn gear_roadmap_deadline(&self) -> u32 {
+        self.inner()
+            .gear_roadmap_deadline
+            .unwrap_or(DEFAULT_GEAR_ROADMAP_DEADLINE)
+    }
+
     #[must_use]
     pub fn get_char_config(&self, i: usize) -> Option<Arc<CharConfig>> {
         self.inner().get_char_config(i)
@@ -62,13 +100,17 @@ pub struct BotConfigInner {
     /// Unknown codes are warned and ignored at startup.
     #[serde(default)]
     pub excluded_items: Vec<String>,
+    /// Minutes after which a task waiting for the orders of its gear roadmap
+    /// is cancelled.
+    #[serde(default)]
+    pub gear_roadmap_deadline: Option<u32>,
 }
 
 impl BotConfigInner {
     #[must_use]
     pub fn from_file() -> Self {
         Figment::new()
-            .merge(Toml::file_exact("ArtifactsMMO.toml"))
+            .merge(Toml::file_exact(CONFIG_FILE))
             .extract()
             .unwrap()
     }
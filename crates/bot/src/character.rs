@@ -4,7 +4,7 @@ use crate::{
     bank::BankController,
     bot_config::{BotConfig, CharConfig, Goal},
     error::{
-        BankCleanupError, BankExpansionCommandError, BuyNpcCommandError,
+        BankCleanupError, BankExpansionCommandError, BossFightCommandError, BuyNpcCommandError,
         BuyNpcOrderProgressionError, CombatLevelingError, CraftCommandError,
         CraftOrderProgressionError, CraftSkillLevelingError, DeleteCommandError,
         DepositItemCommandError, EquipCommandError, EquipGearCommandError, FoodOrderingError,
@@ -16,14 +16,15 @@ use crate::{
         TasksCoinExchangeOrderProgressionError, UnequipCommandError, UseItemCommandError,
         WithdrawItemCommandError,
     },
-    gear_finder::{Filter, GearFinder, GearPurpose},
+    gear_finder::{Filter, GearFinder, GearPurpose, GearRequest, GroupMember},
+    group_fight::{GROUP_FIGHT_TIMEOUT, GroupFight},
     inventory::InventoryController,
-    leveling_helper::LevelingHelper,
-    orderboard::{Order, OrderBoard, Purpose},
+    leveling_helper::{LevelingAction, LevelingHelper},
+    orderboard::{Order, OrderBoard, OrderError, Purpose},
     reservable::Reservable,
 };
 use anyhow::{self, Result, bail};
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
 use derive_more::Deref;
 use itertools::{Either, Itertools};
 use log::{debug, error, info, warn};
@@ -32,14 +33,14 @@ use sdk::Quantity;
 use sdk::entities::{CharacterName, TaskCode};
 use sdk::models::{EquipSchema, InventorySlotSchema, MapContentSchema, MapLayer, UnequipSchema};
 use sdk::{
-    Client, Code, CollectionClient, HasConditions, HasDropTable, HasDrops, ItemContainer, ItemList,
-    ItemsClient, Level, LimitedContainer, MapsClient, MonstersClient, NpcsClient, SlotLimited,
-    SpaceLimited, TasksClient,
+    Client, Code, CollectionClient, CraftPlan, CraftPlanner, HasConditions, HasDropTable, HasDrops,
+    ItemContainer, ItemList, ItemsClient, Level, LimitedContainer, MapsClient, MonstersClient,
+    NpcsClient, Route, RouteStep, SlotLimited, SpaceLimited, TasksClient,
     bank::Bank,
     character::{CharacterClient, Inventory, error::RestError},
     consts::{
         BANK_MIN_FREE_SLOT, CRAFT_TIME, GOLD, GOLDEN_EGG, GOLDEN_SHRIMP, MAX_LEVEL,
-        TASK_CANCEL_PRICE, TASK_EXCHANGE_PRICE, TASKS_COIN,
+        TASK_CANCEL_PRICE, TASK_EXCHANGE_PRICE, TASK_TIME, TASKS_COIN,
     },
     entities::{Character, Item, Map, Monster, NpcItem, PendingItem, RawMap, Resource},
     gear::{Gear, Slot},
@@ -57,7 +58,7 @@ use std::{
     cmp::min,
     option::Option,
     sync::{
-        Arc, Mutex,
+        Arc, Mutex, RwLock,
         mpsc::{SendError, channel},
     },
     thread::sleep,
@@ -69,6 +70,10 @@ use std::{
 use strum::IntoEnumIterator;
 
 const KILL_CONFIDENCE: f64 = 0.95;
+/// Number of characters a boss fight leader can bring along.
+const MAX_BOSS_PARTICIPANTS: usize = 2;
+
+type RoundTripKey = ((MapLayer, i32, i32), Vec<(MapLayer, i32, i32)>);
 
 #[derive(Clone, Deref)]
 #[deref(forward)]
@@ -87,6 +92,14 @@ pub struct CharacterControllerInner {
     npcs: NpcsClient,
     order_board: OrderBoard,
     gear_finder: GearFinder,
+    /// What the gear of the character was last resolved for, shared with
+    /// the other characters when allocating the account equipment. Cleared
+    /// when the character has nothing to do.
+    purpose: RwLock<Option<GearPurpose>>,
+    /// Round trip times computed by [`CharacterController::round_trip_time`],
+    /// keyed by the starting position and the positions of the destinations.
+    /// Cleared at each step since the accessible maps may have changed.
+    round_trips: RwLock<HashMap<RoundTripKey, Option<u32>>>,
     leveling_helper: LevelingHelper,
     commands_sendr: Arc<Sender<CharacterCommand>>,
     commands_recvr: Arc<Mutex<Receiver<CharacterCommand>>>,
@@ -118,6 +131,8 @@ impl CharacterController {
                 npcs: client.npcs.clone(),
                 order_board: order_board.clone(),
                 gear_finder,
+                purpose: RwLock::default(),
+                round_trips: RwLock::default(),
                 leveling_helper,
                 commands_sendr: Arc::new(tx),
                 commands_recvr: Arc::new(Mutex::new(rx)),
@@ -147,90 +162,114 @@ impl CharacterController {
         info!("{}: started !", self.name());
         loop {
             if self.config().is_idle() {
+                self.release_gear();
                 sleep(Duration::from_secs(5));
                 continue;
             }
-            self.maps.refresh_from_events();
-            sleep(
-                self.client
-                    .remaining_cooldown()
-                    .saturating_sub(Duration::from_secs(5)),
-            );
-            if self.order_food().is_ok() {
-                continue;
-            }
-            if self.claim_pending_items().is_ok() {
-                continue;
+            if !self.step() {
+                warn!("{}: nothing to do, sleeping for 5 seconds...", self.name());
+                sleep(Duration::from_secs(5));
             }
-            if let Ok(c) = self.commands_recvr.lock().unwrap().try_recv()
-                && match c {
-                    CharacterCommand::Craft { item, quantity } => {
-                        self.craft(&item, quantity).is_ok()
-                    }
-                    CharacterCommand::Kill { monster } => self.kill_monster(&monster).is_ok(),
-                    CharacterCommand::Gather { resource } => {
-                        self.gather_resource(&resource).is_ok()
-                    }
-                    CharacterCommand::Recycle { item, quantity } => {
-                        self.recycle_item(&item, quantity).is_ok()
-                    }
-                    CharacterCommand::Delete { item, quantity } => {
-                        self.delete_item(&item, quantity).is_ok()
-                    }
-                    CharacterCommand::BuyItem { item, quantity } => {
-                        self.buy_item(&item, quantity).is_ok()
-                    }
-                    CharacterCommand::SellItem { item, quantity } => {
-                        self.sell_item(&item, quantity).is_ok()
-                    }
-                    CharacterCommand::DepositItem { item, quantity } => {
-                        self.deposit_item(&item, quantity).is_ok()
-                    }
-                    CharacterCommand::WithdrawItem { item, quantity } => {
-                        self.withdraw_item(&item, quantity).is_ok()
-                    }
-                    CharacterCommand::DepositGold { quantity } => {
-                        self.deposit_gold(quantity).is_ok()
-                    }
-                    CharacterCommand::WithdrawGold { quantity } => {
-                        self.withdraw_gold(quantity).is_ok()
-                    }
+        }
+    }
+
+    /// Waits for the cooldown then performs the next action of the loop:
+    /// food orders, pending items, commands, bank cleanup, goals and finally
+    /// tasks. Returns `false` when there was nothing to do.
+    #[must_use]
+    pub fn step(&self) -> bool {
+        let acted = self.act();
+        if !acted {
+            self.release_gear();
+        }
+        acted
+    }
+
+    fn act(&self) -> bool {
+        self.maps.refresh_from_events();
+        self.round_trips.write().unwrap().clear();
+        sleep(
+            self.client
+                .remaining_cooldown()
+                .saturating_sub(Duration::from_secs(5)),
+        );
+        if self.order_food().is_ok() {
+            return true;
+        }
+        if self.claim_pending_items().is_ok() {
+            return true;
+        }
+        if let Ok(c) = self.commands_recvr.lock().unwrap().try_recv()
+            && match c {
+                CharacterCommand::Move { layer, x, y } => {
+                    self.r#move(&Either::Left((layer, x, y))).is_ok()
                 }
-            {
-                continue;
-            }
-            if self.cleanup_bank().is_ok() {
-                continue;
-            }
-            if self.handle_goals() {
-                continue;
+                CharacterCommand::Craft { item, quantity } => self.craft(&item, quantity).is_ok(),
+                CharacterCommand::Kill { monster } => self.kill_monster(&monster).is_ok(),
+                CharacterCommand::KillBoss {
+                    monster,
+                    participants,
+                } => self
+                    .kill_boss(&monster, &participants)
+                    .map_err(|e| error!("{}: failed to kill boss: {e}", self.name()))
+                    .is_ok(),
+                CharacterCommand::JoinBossFight { fight } => self
+                    .join_boss_fight(&fight)
+                    .map_err(|e| error!("{}: failed to join boss fight: {e}", self.name()))
+                    .is_ok(),
+                CharacterCommand::Gather { resource } => self.gather_resource(&resource).is_ok(),
+                CharacterCommand::Recycle { item, quantity } => {
+                    self.recycle_item(&item, quantity).is_ok()
+                }
+                CharacterCommand::Delete { item, quantity } => {
+                    self.delete_item(&item, quantity).is_ok()
+                }
+                CharacterCommand::BuyItem { item, quantity } => {
+                    self.buy_item(&item, quantity).is_ok()
+                }
+                CharacterCommand::SellItem { item, quantity } => {
+                    self.sell_item(&item, quantity).is_ok()
+                }
+                CharacterCommand::DepositItem { item, quantity } => {
+                    self.deposit_item(&item, quantity).is_ok()
+                }
+                CharacterCommand::WithdrawItem { item, quantity } => {
+                    self.withdraw_item(&item, quantity).is_ok()
+                }
+                CharacterCommand::DepositGold { quantity } => self.deposit_gold(quantity).is_ok(),
+                CharacterCommand::WithdrawGold { quantity } => self.withdraw_gold(quantity).is_ok(),
             }
-            // TODO: improve fallback
-            match self.progress_task() {
-                Ok(_) => continue,
-                Err(TaskProgressionError::TaskTradeCommandError(
-                    TaskTradeCommandError::MissingItems { item, quantity },
-                )) => {
-                    if self
-                        .order_board
-                        .add(
-                            &item,
-                            quantity,
-                            Some(&self.name()),
-                            Purpose::Task {
-                                char: self.name().clone(),
-                            },
-                        )
-                        .is_ok()
-                    {
-                        continue;
-                    }
+        {
+            return true;
+        }
+        if self.cleanup_bank().is_ok() {
+            return true;
+        }
+        if self.handle_goals() {
+            return true;
+        }
+        // TODO: improve fallback
+        match self.progress_task() {
+            Ok(_) => return true,
+            Err(TaskProgressionError::TaskTradeCommandError(
+                TaskTradeCommandError::MissingItems { item, quantity },
+            )) => {
+                if self
+                    .order_board
+                    .add(
+                        &item,
+                        quantity,
+                        Some(&self.name()),
+                        Purpose::Task { char: self.name() },
+                    )
+                    .is_ok()
+                {
+                    return true;
                 }
-                Err(e) => error!("{}: failed to progress task as fallback: {e}", self.name()),
             }
-            warn!("{}: nothing to do, sleeping for 5 seconds...", self.name());
-            sleep(Duration::from_secs(5));
+            Err(e) => error!("{}: failed to progress task as fallback: {e}", self.name()),
         }
+        false
     }
 
     pub fn send_cmd(&self, cmd: CharacterCommand) -> Result<(), SendError<CharacterCommand>> {
@@ -271,9 +310,23 @@ impl CharacterController {
         if skill.is_combat() {
             return Ok(self.level_combat()?);
         }
-        match self.level_skill_by_crafting(skill) {
+        let options = self.leveling_helper.leveling_options(skill, self);
+        let craft = options.iter().find_map(|o| match &o.action {
+            LevelingAction::Craft { item, quantity } => Some((item, *quantity)),
+            _ => None,
+        });
+        let resource = options.iter().find_map(|o| match &o.action {
+            LevelingAction::Gather(resource) => Some(resource),
+            _ => None,
+        });
+        if options.first().is_some_and(|o| o.action.is_gather())
+            && self.level_skill_by_gathering(resource).is_ok()
+        {
+            return Ok(());
+        }
+        match self.level_skill_by_crafting(skill, craft, resource.is_some()) {
             Ok(()) => Ok(()),
-            Err(_) if skill.is_gathering() => Ok(self.level_skill_by_gathering(skill)?),
+            Err(_) if skill.is_gathering() => Ok(self.level_skill_by_gathering(resource)?),
             Err(e) => Err(e.into()),
         }
     }
@@ -293,27 +346,26 @@ impl CharacterController {
         Ok(())
     }
 
-    fn level_skill_by_crafting(&self, skill: Skill) -> Result<(), CraftSkillLevelingError> {
-        let Some(item) = self
-            .leveling_helper
-            .best_craft(self.skill_level(skill), skill, self)
-        else {
+    /// Crafts the `craft` chosen to level `skill`. Its missing materials are
+    /// ordered for crafting skills, and for alchemy when no resource to gather
+    /// levels it (`has_resource`).
+    fn level_skill_by_crafting(
+        &self,
+        skill: Skill,
+        craft: Option<(&Item, u32)>,
+        has_resource: bool,
+    ) -> Result<(), CraftSkillLevelingError> {
+        let Some((item, quantity)) = craft else {
             return Err(CraftSkillLevelingError::ItemNotFound);
         };
-        let quantity = self.max_craftable_items(item.code());
         match self.craft(item.code(), quantity) {
             Ok(_) => Ok(()),
-            Err(CraftCommandError::InsufficientMaterials(missing_mats))
-                if !skill.is_gathering()
-                    || skill.is_alchemy()
-                        && self
-                            .leveling_helper
-                            .best_resource(self.skill_level(skill), skill)
-                            .is_none() =>
+            Err(CraftCommandError::InsufficientMaterials(_missing_mats))
+                if !skill.is_gathering() || skill.is_alchemy() && !has_resource =>
             {
-                Ok(self.order_board.add_multiple(
-                    &missing_mats,
-                    None,
+                Ok(self.order_craft_plan(
+                    item.code(),
+                    quantity,
                     &Purpose::Leveling {
                         char: self.name(),
                         skill,
@@ -324,14 +376,14 @@ impl CharacterController {
         }
     }
 
-    fn level_skill_by_gathering(&self, skill: Skill) -> Result<(), GatherCommandError> {
-        let Some(resource) = self
-            .leveling_helper
-            .best_resource(self.skill_level(skill), skill)
-        else {
+    fn level_skill_by_gathering(
+        &self,
+        resource: Option<&Resource>,
+    ) -> Result<(), GatherCommandError> {
+        let Some(resource) = resource else {
             return Err(GatherCommandError::MapNotFound);
         };
-        self.gather_resource(&resource)?;
+        self.gather_resource(resource)?;
         Ok(())
     }
 
@@ -466,17 +518,26 @@ impl CharacterController {
                 Ok(result.map(|craft| craft.amount_of(&order.item))?)
             }
             Err(CraftCommandError::InsufficientMaterials(_missing_mats)) => Ok(self
-                .order_board
-                .add_multiple(
-                    &self.missing_mats_for(&order.item, total_missing),
-                    None,
-                    &order.purpose,
-                )
+                .order_craft_plan(&order.item, total_missing, &order.purpose)
                 .map(|()| 0)?),
             Err(e) => Err(e.into()),
         }
     }
 
+    /// Orders everything missing to craft `quantity` of `item`, down to the
+    /// raw materials.
+    fn order_craft_plan(
+        &self,
+        item: &str,
+        quantity: u32,
+        purpose: &Purpose,
+    ) -> Result<(), OrderError> {
+        let Some(plan) = self.craft_plan(item, quantity) else {
+            return Err(OrderError::UnknownItem);
+        };
+        self.order_board.add_plan(&plan, None, purpose)
+    }
+
     fn progress_task_reward_order(
         &self,
         order: &Order,
@@ -566,10 +627,28 @@ impl CharacterController {
                 .flat_map(|c| c.drops.clone())
                 .collect_vec()),
             Err(KillMonsterCommandError::GearTooWeak { monster_code }) => {
-                warn!(
-                    "{}: no gear powerfull enough to kill {monster_code}",
-                    self.name(),
-                );
+                let roadmap = self.order_board.roadmap_orders(&self.name(), &monster_code);
+                if let Some(ordered) = roadmap.iter().map(|o| o.creation).min() {
+                    let deadline =
+                        TimeDelta::minutes(self.bot_config.gear_roadmap_deadline().into());
+                    if Utc::now() - ordered < deadline {
+                        return Err(TaskProgressionError::WaitingForGear { monster_code });
+                    }
+                    warn!(
+                        "{}: gear roadmap to kill {monster_code} not completed in time",
+                        self.name(),
+                    );
+                    for order in &roadmap {
+                        self.order_board.remove(order);
+                    }
+                } else if self.order_gear_roadmap_to(&monster) {
+                    return Ok(vec![]);
+                } else {
+                    warn!(
+                        "{}: no gear powerfull enough to kill {monster_code}",
+                        self.name(),
+                    );
+                }
                 self.cancel_task()?;
                 Ok(vec![])
             }
@@ -694,7 +773,10 @@ impl CharacterController {
             //TODO: should not return this
             return Ok(CharacterFightSchema::default());
         }
-        let mut gear = self.can_kill(monster)?;
+        let mut gear = match self.allocated_gear() {
+            Some(gear) if self.can_kill_with(monster, &gear) => gear,
+            _ => self.can_kill(monster)?,
+        };
         self.equip_gear(&mut gear)?;
         if !self.inventory.has_room_for_drops_from(monster) {
             //TODO: don't deposit food
@@ -715,6 +797,110 @@ impl CharacterController {
         Ok(self.client.fight(None)?)
     }
 
+    /// Resolves the gear of the group against the given boss, gathers the one
+    /// or two `participants` on the boss map closest to the leader then starts
+    /// the fight once everyone is ready.
+    fn kill_boss(
+        &self,
+        monster: &Monster,
+        participants: &[CharacterName],
+    ) -> Result<CharacterFightSchema, BossFightCommandError> {
+        if !monster.is_boss() {
+            return Err(BossFightCommandError::NotABoss(monster.code().to_owned()));
+        }
+        if !(1..=MAX_BOSS_PARTICIPANTS).contains(&participants.len()) {
+            return Err(BossFightCommandError::InvalidGroupSize);
+        }
+        let names = participants.iter().map(ToString::to_string).collect_vec();
+        self.can_fight(monster)?;
+        let mut members = vec![self.group_member()];
+        for name in participants {
+            let char = self
+                .account
+                .get_character_by_name(name)
+                .ok_or_else(|| BossFightCommandError::CharacterNotFound(name.to_string()))?;
+            members.push(char.group_member());
+        }
+        let gear_too_weak = || BossFightCommandError::GearTooWeak {
+            monster_code: monster.code().to_owned(),
+        };
+        let loadouts = self
+            .gear_finder
+            .best_for_group(monster.clone(), members)
+            .with_shared_items(self.bank.available_for(&self.name()))
+            .with_excluded_items(self.bot_config.excluded_items())
+            .with_filter(Filter::available_only())
+            .with_confidence(KILL_CONFIDENCE)
+            .resolve()
+            .ok_or_else(gear_too_weak)?;
+        let Some((map, _)) = self
+            .client
+            .closest_among(&self.maps.with_content_code(monster.code()))
+        else {
+            return Err(KillMonsterCommandError::MapNotFound.into());
+        };
+        let fight = GroupFight::new(monster.clone(), map.position(), loadouts);
+        for name in participants {
+            if let Some(char) = self.account.get_character_by_name(name)
+                && let Err(e) = char.send_cmd(CharacterCommand::JoinBossFight {
+                    fight: fight.clone(),
+                })
+            {
+                warn!("{}: failed to invite {name}: {e}", self.name());
+            }
+        }
+        let result = self.prepare_for_boss_fight(&fight).and_then(|()| {
+            if !fight.wait_for_participants(GROUP_FIGHT_TIMEOUT) {
+                return Err(BossFightCommandError::ParticipantsNotReady);
+            }
+            Ok(self.client.fight(Some(&names))?)
+        });
+        fight.end();
+        result
+    }
+
+    /// Gets ready for the boss fight led by another character, then waits on
+    /// the boss map until the fight is over.
+    fn join_boss_fight(&self, fight: &GroupFight) -> Result<(), BossFightCommandError> {
+        if let Err(e) = self.prepare_for_boss_fight(fight) {
+            fight.abandon();
+            return Err(e);
+        }
+        fight.ready(&self.name());
+        fight.wait_for_end(GROUP_FIGHT_TIMEOUT);
+        Ok(())
+    }
+
+    fn prepare_for_boss_fight(&self, fight: &GroupFight) -> Result<(), BossFightCommandError> {
+        let mut gear = fight
+            .loadout_of(&self.name())
+            .map(|l| l.gear)
+            .ok_or_else(|| BossFightCommandError::CharacterNotFound(self.name().to_string()))?;
+        self.equip_gear(&mut gear)?;
+        if !self.inventory.has_room_for_drops_from(fight.monster()) {
+            self.deposit_all()?;
+        }
+        if let Err(e) = self.withdraw_food() {
+            error!("{}: failed to withdraw food: {e}", self.name());
+        }
+        self.eat_food_from_inventory();
+        self.rest()?;
+        self.r#move(&Either::Left(fight.position()))?;
+        Ok(())
+    }
+
+    /// Returns the character as a member of a group fight, owning the items
+    /// it wears and the ones available in its inventory.
+    fn group_member(&self) -> GroupMember {
+        let mut items: HashMap<String, u32> = self.gear().into();
+        for (code, quantity) in self.inventory.available_items() {
+            *items.entry(code).or_default() += quantity;
+        }
+        GroupMember::new(self.name())
+            .with_skill_levels(self.skill_levels())
+            .with_available_items(items)
+    }
+
     fn rest(&self) -> Result<u32, RestError> {
         if self.hp() < self.max_hp() {
             Ok(self.client.rest()?)
@@ -760,20 +946,18 @@ impl CharacterController {
         Ok(())
     }
 
+    /// Equips the gear allocated to the character for the given `purpose`
+    /// among the equipment of the account.
     fn equip_gear_for(&self, purpose: GearPurpose) -> Result<(), EquipGearCommandError> {
-        let mut available = self
-            .gear_finder
-            .best_for(purpose)
-            .with_excluded_items(self.bot_config.excluded_items())
-            .with_available_items(self.available_items())
-            .with_skill_levels(self.skill_levels())
-            .with_filter(Filter::available_only())
-            .resolve()
-            .unwrap_or_default();
-        self.equip_gear(&mut available)
+        self.set_purpose(purpose);
+        let mut allocated = self.allocated_gear().unwrap_or_default();
+        self.equip_gear(&mut allocated)
     }
 
+    /// Orders the items of the best gear for the given `purpose` that are
+    /// missing from the gear allocated to the character.
     fn order_best_gear_for(&self, purpose: GearPurpose) -> bool {
+        self.set_purpose(purpose.clone());
         if !self.bot_config.order_gear() {
             return false;
         }
@@ -796,7 +980,80 @@ impl CharacterController {
         {
             return false;
         }
-        self.order_gear(&mut gear)
+        let allocated = self.allocated_gear().map(Into::into).unwrap_or_default();
+        self.order_gear(&mut gear, &allocated)
+    }
+
+    /// Plans the cheapest way to a gear killing the `monster` with
+    /// `KILL_CONFIDENCE` and orders the items of each step. Returns `false` if
+    /// gear ordering is disabled or if no gear within reach kills the monster.
+    fn order_gear_roadmap_to(&self, monster: &Monster) -> bool {
+        if !self.bot_config.order_gear() {
+            return false;
+        }
+        let account = self.account.clone();
+        let costs = self.account.clone();
+        let Some(roadmap) = self
+            .gear_finder
+            .roadmap_to(monster.clone())
+            .with_excluded_items(self.bot_config.excluded_items())
+            .with_available_items(self.available_items())
+            .with_skill_levels(self.skill_levels())
+            .with_can_craft(move |code| account.can_craft(code))
+            .with_cost(move |code| costs.time_to_get(code))
+            .with_confidence(KILL_CONFIDENCE)
+            .plan()
+        else {
+            return false;
+        };
+        if roadmap.steps.is_empty() {
+            return false;
+        }
+        info!(
+            "{}: gear roadmap to kill '{}': {roadmap}",
+            self.name(),
+            monster.code()
+        );
+        matches!(
+            self.order_board
+                .add_roadmap(&roadmap, &self.name(), monster.code()),
+            Ok(()) | Err(OrderError::AlreadyExists)
+        )
+    }
+
+    /// Sets what the gear of the character is resolved for.
+    pub fn set_purpose(&self, purpose: GearPurpose) {
+        *self.purpose.write().unwrap() = Some(purpose);
+    }
+
+    /// Releases the gear allocated to the character, e.g. when it has nothing
+    /// to do.
+    fn release_gear(&self) {
+        *self.purpose.write().unwrap() = None;
+    }
+
+    /// Returns what the gear of the character should be resolved for, if it
+    /// is doing something requiring one.
+    pub(crate) fn gear_request(&self) -> Option<GearRequest> {
+        let purpose = self.purpose.read().unwrap().clone()?;
+        let mut owned: HashMap<String, u32> = self.gear().into();
+        let reserved = self.bank.reserved_to(&self.name());
+        for (code, quantity) in self.inventory.available_items().into_iter().chain(reserved) {
+            if self.items.get(&code).is_some_and(|i| i.is_equipable()) {
+                *owned.entry(code).or_default() += quantity;
+            }
+        }
+        Some(
+            GearRequest::new(self.name(), purpose)
+                .with_skill_levels(self.skill_levels())
+                .with_owned_items(owned),
+        )
+    }
+
+    /// Returns the gear allocated to the character by
+    /// [`AccountController::allocated_gear`].
+    fn allocated_gear(&self) -> Option<Gear> {
+        self.account.allocated_gear(&self.name(), &self.gear_finder)
     }
 
     pub fn can_fight(&self, monster: &Monster) -> Result<(), KillMonsterCommandError> {
@@ -838,7 +1095,7 @@ impl CharacterController {
                 .with_gear(gear.clone()),
             monster.clone(),
         );
-        sim.win_rate(1000) >= KILL_CONFIDENCE
+        self.is_confident_kill(&sim)
     }
 
     fn can_kill_now(&self, monster: &Monster) -> bool {
@@ -851,7 +1108,20 @@ impl CharacterController {
                 .with_missing_hp(self.missing_hp()),
             monster.clone(),
         );
-        sim.win_rate(1000) >= KILL_CONFIDENCE
+        self.is_confident_kill(&sim)
+    }
+
+    /// Logs the exact win rate of the fight before comparing it against
+    /// `KILL_CONFIDENCE`.
+    fn is_confident_kill(&self, sim: &FightSimulation) -> bool {
+        let odds = sim.odds();
+        debug!(
+            "{}: win rate against '{}': {:.2}%",
+            self.name(),
+            sim.monster().code(),
+            odds.win_rate * 100.0
+        );
+        odds.win_rate >= KILL_CONFIDENCE
     }
 
     /// Crafts the given `quantity` of the given item `code` if the required
@@ -1446,16 +1716,7 @@ impl CharacterController {
         if current_map.content_type_is(r#type) {
             return Ok(current_map);
         }
-        let maps = self
-            .maps
-            .of_type(r#type)
-            .into_iter()
-            .filter(|m| self.client.can_move(m.x(), m.y()).is_ok())
-            .collect_vec();
-        let Some(map) = current_map.closest_among(&maps) else {
-            return Err(MoveCommandError::MapNotFound);
-        };
-        self.r#move(&Either::Left((map.x(), map.y())))
+        self.move_to_closest_among(&self.maps.of_type(r#type))
     }
 
     fn move_to_closest_map_with_content_code(
@@ -1466,16 +1727,7 @@ impl CharacterController {
         if current_map.content_code_is(code) {
             return Ok(current_map);
         }
-        let maps = self
-            .maps
-            .with_content_code(code)
-            .into_iter()
-            .filter(|m| self.client.can_move(m.x(), m.y()).is_ok())
-            .collect_vec();
-        let Some(map) = current_map.closest_among(&maps) else {
-            return Err(MoveCommandError::MapNotFound);
-        };
-        self.r#move(&Either::Left((map.x(), map.y())))
+        self.move_to_closest_among(&self.maps.with_content_code(code))
     }
 
     fn move_to_closest_map_with_content(
@@ -1486,38 +1738,54 @@ impl CharacterController {
         if current_map.content_is(content) {
             return Ok(current_map);
         }
-        let maps = self
-            .maps
-            .with_content(content)
-            .into_iter()
-            .filter(|m| self.client.can_move(m.x(), m.y()).is_ok())
-            .collect_vec();
-        let Some(map) = current_map.closest_among(&maps) else {
+        self.move_to_closest_among(&self.maps.with_content(content))
+    }
+
+    fn move_to_closest_among(&self, maps: &[RawMap]) -> Result<RawMap, MoveCommandError> {
+        let Some((_, route)) = self.client.closest_among(maps) else {
             return Err(MoveCommandError::MapNotFound);
         };
-        self.r#move(&Either::Left((map.x(), map.y())))
+        self.follow(&route)
+    }
+
+    fn r#move(
+        &self,
+        destination: &Either<(MapLayer, i32, i32), i32>,
+    ) -> Result<RawMap, MoveCommandError> {
+        let position = match *destination {
+            Either::Left(position) => position,
+            Either::Right(id) => self
+                .maps
+                .get_by_id(id)
+                .ok_or(MoveCommandError::MapNotFound)?
+                .position(),
+        };
+        if self.position() == position {
+            return Ok(self.current_map());
+        }
+        let Some(route) = self.client.route_to(position) else {
+            return Err(MoveCommandError::NoRoute);
+        };
+        self.follow(&route)
     }
 
-    fn r#move(&self, destination: &Either<(i32, i32), i32>) -> Result<RawMap, MoveCommandError> {
-        match *destination {
-            Either::Left((x, y)) => {
-                let position = self.position();
-                if position == (position.0, x, y) {
-                    return Ok(self.current_map());
+    /// Performs the steps of `route`, unless the character does not carry the
+    /// gold and items its transitions cost altogether.
+    fn follow(&self, route: &Route) -> Result<RawMap, MoveCommandError> {
+        if !route.is_affordable(self.gold(), |code| self.inventory.total_of(code)) {
+            return Err(MoveCommandError::UnaffordableRoute);
+        }
+        for step in &route.steps {
+            match *step {
+                RouteStep::Move { x, y } => {
+                    self.client.r#move(x, y)?;
                 }
-                Ok(self.client.r#move(x, y)?)
-            }
-            Either::Right(id) => {
-                let current_map = self.current_map();
-                if current_map.id() == id {
-                    return Ok(current_map);
+                RouteStep::Transition { .. } => {
+                    self.client.transition()?;
                 }
-                let Some(map) = self.maps.get_by_id(id) else {
-                    return Err(MoveCommandError::MapNotFound);
-                };
-                Ok(self.client.r#move(map.x(), map.y())?)
             }
         }
+        Ok(self.current_map())
     }
 
     fn eat_food_from_inventory(&self) {
@@ -1730,41 +1998,52 @@ impl CharacterController {
                 || self.sell_item(item.code(), surplus).is_ok())
     }
 
-    fn order_gear(&self, gear: &mut Gear) -> bool {
+    fn order_gear(&self, gear: &mut Gear, allocated: &HashMap<String, u32>) -> bool {
         gear.align_to(&self.gear());
         let mut ordered = false;
         Slot::iter().for_each(|slot| {
             if let Some(item) = gear.item_in(slot)
                 && !slot.is_ring()
             {
-                ordered = self.order_if_needed(item, slot.max_quantity());
+                ordered = self.order_if_needed(item, slot.max_quantity(), allocated);
             }
         });
         if let Some(ref ring1) = gear.ring1
             && gear.ring1 == gear.ring2
         {
-            ordered = self.order_if_needed(ring1, 2);
+            ordered = self.order_if_needed(ring1, 2, allocated);
         } else {
             if let Some(ref ring1) = gear.ring1 {
-                ordered = self.order_if_needed(ring1, 1);
+                ordered = self.order_if_needed(ring1, 1, allocated);
             }
             if let Some(ref ring2) = gear.ring2 {
-                ordered = self.order_if_needed(ring2, 1);
+                ordered = self.order_if_needed(ring2, 1, allocated);
             }
         }
         ordered
     }
 
-    fn order_if_needed(&self, item: &Item, quantity: u32) -> bool {
+    /// Orders the `quantity` of `item` not covered by the `allocated` items,
+    /// unless the account would then hold more of it than its characters need.
+    fn order_if_needed(
+        &self,
+        item: &Item,
+        quantity: u32,
+        allocated: &HashMap<String, u32>,
+    ) -> bool {
+        let missing_quantity =
+            quantity.saturating_sub(allocated.get(item.code()).copied().unwrap_or(0));
+        if missing_quantity == 0 {
+            return false;
+        }
         let total = self.account.total_of(item.code());
         let max = if item.r#type().is_ring() { 10 } else { 5 };
         if item.is_equipable()
             && !item.r#type().is_utility()
-            && (total + self.order_board.quantity_ordered(item.code()) + quantity) > max
+            && (total + self.order_board.quantity_ordered(item.code()) + missing_quantity) > max
         {
             return false;
         }
-        let missing_quantity = quantity.saturating_sub(self.has_available(item.code()));
         self.order_board
             .add(
                 item.code(),
@@ -1794,56 +2073,130 @@ impl CharacterController {
         bail!("no item pending")
     }
 
-    /// Reserves the given `quantity` of the `item` if needed and available.
+    /// Returns the best source of `item` along with the estimated time in
+    /// seconds to get one of it from there, travel included.
     #[must_use]
     pub fn time_to_get(&self, item: &str) -> Option<(ItemSource, u32)> {
-        self.best_source_of(item)
-            .iter()
-            .filter_map(|s| {
-                (match s {
-                    ItemSource::Resource(r) => self.time_to_gather(r),
-                    ItemSource::Monster(m) => self
-                        .time_to_kill(m)
-                        .map(|time| (time as f32 / m.expected_quantity_of(item)) as u32),
-                    ItemSource::Craft => self.can_craft(item).is_ok().then_some(CRAFT_TIME),
-                    ItemSource::TaskReward | ItemSource::Task => Some(2000),
-                    ItemSource::Npc(_) => Some(60),
-                })
-                .map(|t| (s.clone(), t))
-            })
-            .min_by_key(|(_, t)| *t)
+        let source = self.best_source_of(item)?;
+        let time = self.time_to_get_from(&source, item)?;
+        Some((source, time))
+    }
+
+    /// Returns the estimated time in seconds to get one `item` from `source`,
+    /// including its share of the round trip to the source.
+    fn time_to_get_from(&self, source: &ItemSource, item: &str) -> Option<u32> {
+        match source {
+            ItemSource::Resource(r) => self
+                .time_to_gather(r)
+                .map(|time| (time as f32 / r.expected_quantity_of(item)) as u32),
+            ItemSource::Monster(m) => self
+                .time_to_kill(m)
+                .map(|time| (time as f32 / m.expected_quantity_of(item)) as u32),
+            ItemSource::Craft => {
+                self.can_craft(item).ok()?;
+                let skill = self.items.get(item)?.skill_to_craft()?;
+                let trip = self.round_trip_time(&self.maps.with_content_code(skill.as_ref()))?;
+                Some(CRAFT_TIME + trip / self.max_craftable_items(item).max(1))
+            }
+            ItemSource::TaskReward | ItemSource::Task => Some(
+                TASK_TIME
+                    + self.round_trip_time(&self.maps.of_type(MapContentType::TasksMaster))?,
+            ),
+            ItemSource::Npc(npc) => {
+                let trip = self.round_trip_time(&self.maps.with_content_code(npc.code()))?;
+                Some((trip / self.inventory.max_items().max(1)).max(1))
+            }
+        }
     }
 
+    /// Returns the estimated time in seconds to kill `monster` once: the
+    /// fight and rest cooldowns and the share of the round trip to the monster.
     #[must_use]
     pub fn time_to_kill(&self, monster: &Monster) -> Option<u32> {
         let gear = self.can_kill(monster).ok()?;
-        let fight = FightSimulation::new(
+        let odds = FightSimulation::new(
             Participant::new(self.name())
                 .with_level(self.level())
                 .with_gear(gear),
             monster.clone(),
         )
-        .run();
-        Some(fight.cd + (fight.hp_lost / 5 + i32::from(fight.hp_lost % 5 > 0)) as u32)
+        .odds();
+        let travel = self.travel_time_per_action(
+            &self.maps.with_content_code(monster.code()),
+            monster.expected_quantity(),
+        )?;
+        Some(
+            odds.expected_cd.round() as u32
+                + time_to_rest(odds.expected_hp_lost.round() as u32)
+                + travel,
+        )
     }
 
+    /// Returns the estimated time in seconds to gather `resource` once: the
+    /// gathering cooldown and the share of the round trip to the resource.
     #[must_use]
     pub fn time_to_gather(&self, resource: &Resource) -> Option<u32> {
         self.can_gather(resource).ok()?;
         let reduction = self
-            .gear_finder
-            .best_for(GearPurpose::Gathering(resource.clone()))
+            .available_gear_for(GearPurpose::Gathering(resource.clone()))
+            .map_or(0, |gear| gear.skill_cooldown_reduction(resource.skill()));
+        let travel = self.travel_time_per_action(
+            &self.maps.with_content_code(resource.code()),
+            resource.expected_quantity(),
+        )?;
+        Some(compute_gathering_cd(resource.level(), reduction) + travel)
+    }
+
+    /// Returns the best gear for `purpose` among the items available to the
+    /// character.
+    #[must_use]
+    pub fn available_gear_for(&self, purpose: GearPurpose) -> Option<Gear> {
+        self.gear_finder
+            .best_for(purpose)
             .with_excluded_items(self.bot_config.excluded_items())
             .with_available_items(self.available_items())
             .with_skill_levels(self.skill_levels())
             .with_filter(Filter::available_only())
             .resolve()
-            .and_then(|gear| {
-                gear.weapon
-                    .map(|w| w.skill_cooldown_reduction(resource.skill()))
-            })
-            .unwrap_or(0);
-        Some(compute_gathering_cd(resource.level(), reduction))
+    }
+
+    /// Returns the time in seconds to walk from the current map to the closest
+    /// of `maps` and from there back to the closest bank.
+    ///
+    /// Routes are planned once per step for a given position and set of maps.
+    #[must_use]
+    pub fn round_trip_time(&self, maps: &[RawMap]) -> Option<u32> {
+        let key = (
+            self.position(),
+            maps.iter().map(Map::position).collect_vec(),
+        );
+        if let Some(time) = self.round_trips.read().unwrap().get(&key) {
+            return *time;
+        }
+        let time = self.plan_round_trip(maps);
+        self.round_trips.write().unwrap().insert(key, time);
+        time
+    }
+
+    fn plan_round_trip(&self, maps: &[RawMap]) -> Option<u32> {
+        let (map, there) = self.client.closest_among(maps)?;
+        let (_, back) = self.maps.closest_from_among(
+            map.position(),
+            &self.maps.of_type(MapContentType::Bank),
+            |e| self.client.meets_conditions_for(e),
+        )?;
+        Some(there.time() + back.time())
+    }
+
+    /// Returns the share of the round trip to the closest of `maps` taken by
+    /// each action performed there, a trip lasting until the `drops` of the
+    /// actions fill the inventory.
+    fn travel_time_per_action(&self, maps: &[RawMap], drops: f32) -> Option<u32> {
+        Some(travel_share(
+            self.round_trip_time(maps)?,
+            self.inventory.max_items(),
+            drops,
+        ))
     }
 
     /// Calculates the maximum number of items that can be crafted in one go based on
@@ -1864,6 +2217,26 @@ impl CharacterController {
     //     )
     // }
 
+    /// Plans the crafting of `quantity` of `item` from the items available to
+    /// the character in the bank and in its inventory.
+    #[must_use]
+    pub fn craft_plan(&self, item: &str, quantity: u32) -> Option<CraftPlan> {
+        CraftPlanner::new(self.items.clone())
+            .with_stock(self.bank_and_inventory_items())
+            .plan(item, quantity)
+    }
+
+    /// Returns the items available to the character in the bank and in its
+    /// inventory, equipped items excluded.
+    #[must_use]
+    pub fn bank_and_inventory_items(&self) -> HashMap<String, u32> {
+        let mut items = self.bank.available_for(&self.name());
+        for (code, quantity) in self.inventory.available_items() {
+            *items.entry(code).or_default() += quantity;
+        }
+        items
+    }
+
     #[must_use]
     pub fn gold_available(&self) -> u32 {
         self.gold() + self.bank.gold()
@@ -1929,11 +2302,16 @@ impl CharacterController {
         if sources.iter().all(|s| s.is_resource() || s.is_monster()) {
             return sources
                 .iter()
-                .max_by_key(|s| {
-                    OrderedFloat(match s {
-                        ItemSource::Resource(resource) => resource.probability_of(code),
-                        ItemSource::Monster(monster) => monster.probability_of(code),
-                        _ => 0.0,
+                .filter_map(|s| Some((s, self.time_to_get_from(s, code)?)))
+                .min_by_key(|(_, time)| *time)
+                .map(|(s, _)| s)
+                .or_else(|| {
+                    sources.iter().max_by_key(|s| {
+                        OrderedFloat(match s {
+                            ItemSource::Resource(resource) => resource.probability_of(code),
+                            ItemSource::Monster(monster) => monster.probability_of(code),
+                            _ => 0.0,
+                        })
                     })
                 })
                 .cloned();
@@ -2092,3 +2470,25 @@ impl Level for CharacterController {
         self.client.level()
     }
 }
+
+/// Returns the share of a `round_trip` taken by each action, a trip lasting
+/// until the `drops` of the actions fill an inventory of `max_items`.
+fn travel_share(round_trip: u32, max_items: u32, drops: f32) -> u32 {
+    let actions = (max_items as f32 / drops).max(1.0);
+    (round_trip as f32 / actions) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_ITEMS: u32 = 100;
+
+    #[test]
+    fn travel_is_shared_by_the_actions_filling_the_inventory() {
+        assert_eq!(travel_share(1000, MAX_ITEMS, 1.0), 10);
+        assert_eq!(travel_share(1000, MAX_ITEMS, 2.0), 20);
+        assert_eq!(travel_share(1000, MAX_ITEMS, 200.0), 1000);
+        assert_eq!(travel_share(1000, MAX_ITEMS, 0.0), 0);
+    }
+}
@@ -4,7 +4,8 @@ use sdk::{
         BankExpansionError, BuyNpcError, CraftError, DeleteError, DepositError, EquipError,
         FightError, GatherError, GoldDepositError, GoldWithdrawError, MoveError, RecycleError,
         RestError, SellNpcError, TaskAcceptationError, TaskCancellationError, TaskCompletionError,
-        TaskTradeError, TasksCoinExchangeError, UnequipError, UseError, WithdrawError,
+        TaskTradeError, TasksCoinExchangeError, TransitionError, UnequipError, UseError,
+        WithdrawError,
     },
     models::SimpleItemSchema,
     skill::Skill,
@@ -33,6 +34,32 @@ pub enum KillMonsterCommandError {
     ClientError(#[from] FightError),
 }
 
+#[derive(Debug, Error)]
+pub enum BossFightCommandError {
+    #[error("'{0}' is not a boss")]
+    NotABoss(String),
+    #[error("a group fight needs one or two participants")]
+    InvalidGroupSize,
+    #[error("character '{0}' not found")]
+    CharacterNotFound(String),
+    #[error("failed to check fight: {0}")]
+    KillMonsterCommandError(#[from] KillMonsterCommandError),
+    #[error("no group gear powerful enough available to kill '{monster_code}'")]
+    GearTooWeak { monster_code: String },
+    #[error("failed to equip gear: {0}")]
+    EquipGearCommandError(#[from] EquipGearCommandError),
+    #[error("failed to deposit before fighting: {0}")]
+    DepositItemCommandError(#[from] DepositItemCommandError),
+    #[error("failed to move: {0}")]
+    MoveCommandError(#[from] MoveCommandError),
+    #[error("failed to rest: {0}")]
+    RestError(#[from] RestError),
+    #[error("participants did not get ready in time")]
+    ParticipantsNotReady,
+    #[error("failed to request fight: {0}")]
+    ClientError(#[from] FightError),
+}
+
 #[derive(Debug, Error)]
 pub enum GatherCommandError {
     #[error("'{0}' skill is disabled")]
@@ -220,8 +247,14 @@ pub enum GoldDepositCommandError {
 pub enum MoveCommandError {
     #[error("failed to find target map")]
     MapNotFound,
+    #[error("no route to target map")]
+    NoRoute,
+    #[error("route to target map costs more than carried")]
+    UnaffordableRoute,
     #[error("failed to request movement: {0}")]
     MoveError(#[from] MoveError),
+    #[error("failed to request transition: {0}")]
+    TransitionError(#[from] TransitionError),
 }
 
 #[derive(Debug, Error)]
@@ -404,6 +437,8 @@ pub enum TaskProgressionError {
     KillMonsterCommandError(#[from] KillMonsterCommandError),
     #[error("order error missing items: {0}")]
     OrderError(#[from] OrderError),
+    #[error("waiting for the gear ordered to kill '{monster_code}'")]
+    WaitingForGear { monster_code: String },
 }
 
 #[derive(Debug, Error)]
@@ -0,0 +1,372 @@
+use crate::gear_finder::{Filter, GearPurpose, GearResolver, memo::GearMemo};
+use itertools::Itertools;
+use log::debug;
+use ordered_float::OrderedFloat;
+use sdk::{
+    CollectionClient, ItemsClient, Level,
+    entities::CharacterName,
+    gear::Gear,
+    simulator::{
+        FightParams, FightSimulation, HasEffects, Participant, compute_gathering_cd, time_to_rest,
+    },
+    skill::Skill,
+    wisdom_multiplier,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+/// What a character is currently doing, along with what its gear can be
+/// resolved from.
+#[derive(Clone, PartialEq)]
+pub struct GearRequest {
+    name: CharacterName,
+    purpose: GearPurpose,
+    skill_levels: HashMap<Skill, u32>,
+    /// Items only the character can wear, such as the ones it holds.
+    owned_items: HashMap<String, u32>,
+}
+
+impl GearRequest {
+    #[must_use]
+    pub fn new(name: CharacterName, purpose: GearPurpose) -> Self {
+        Self {
+            name,
+            purpose,
+            skill_levels: HashMap::new(),
+            owned_items: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_skill_levels(mut self, levels: HashMap<Skill, u32>) -> Self {
+        self.skill_levels = levels;
+        self
+    }
+
+    #[must_use]
+    pub fn with_owned_items(mut self, items: HashMap<String, u32>) -> Self {
+        self.owned_items = items;
+        self
+    }
+
+    #[must_use]
+    pub const fn name(&self) -> &CharacterName {
+        &self.name
+    }
+
+    fn level(&self, skill: Skill) -> u32 {
+        *self.skill_levels.get(&skill).unwrap_or(&1)
+    }
+
+    /// Returns the quantity of the item `code` the character needs from the
+    /// shared pool to wear `quantity` of it.
+    fn missing(&self, code: &str, quantity: u32) -> u32 {
+        quantity.saturating_sub(self.owned_items.get(code).copied().unwrap_or(0))
+    }
+}
+
+/// Shares the equipment of the account between the characters: the shared
+/// pool, such as the bank, and the items each one owns.
+pub struct GearAllocator {
+    items: ItemsClient,
+    memo: Arc<GearMemo>,
+    requests: Vec<GearRequest>,
+    pool: HashMap<String, u32>,
+    excluded_items: Vec<String>,
+}
+
+impl GearAllocator {
+    pub(super) const fn new(
+        items: ItemsClient,
+        memo: Arc<GearMemo>,
+        requests: Vec<GearRequest>,
+        pool: HashMap<String, u32>,
+    ) -> Self {
+        Self {
+            items,
+            memo,
+            requests,
+            pool,
+            excluded_items: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn with_excluded_items(mut self, items: Vec<String>) -> Self {
+        self.excluded_items = items;
+        self
+    }
+
+    /// Assigns a gear to each request, picked from its owned items and the
+    /// items of the pool.
+    ///
+    /// Every pending request resolves its best gear from its owned items and
+    /// what is left in the pool. As long as an item is wanted from the pool by
+    /// more characters than the pool holds, the character whose throughput
+    /// drops the most without the contested items gets its gear first and the
+    /// items it takes from the pool leave it. The others are then resolved
+    /// again from what remains. Should none of the gears take a contested
+    /// item, the pending requests are served one after the other from what
+    /// remains. Requests for which no gear could be resolved are left out.
+    #[must_use]
+    pub fn resolve(self) -> HashMap<CharacterName, Gear> {
+        let mut pool = self.pool.clone();
+        let mut pending = self.requests.iter().collect_vec();
+        let mut allocation = HashMap::new();
+        while !pending.is_empty() {
+            let gears = pending
+                .iter()
+                .map(|r| self.resolve_for(r, &pool))
+                .collect_vec();
+            let contested = self.contested_items(&pending, &gears, &pool);
+            if contested.is_empty() {
+                allocation.extend(
+                    pending
+                        .iter()
+                        .zip(gears)
+                        .filter_map(|(r, g)| g.map(|g| (r.name.clone(), g))),
+                );
+                break;
+            }
+            let without_contested = pool
+                .iter()
+                .map(|(code, quantity)| {
+                    let quantity = if contested.contains(code) {
+                        0
+                    } else {
+                        *quantity
+                    };
+                    (code.clone(), quantity)
+                })
+                .collect::<HashMap<_, _>>();
+            let Some((i, gear)) = gears
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, g)| g.map(|g| (i, g)))
+                .filter(|(_, g)| self.equipment_of(g).any(|(c, _)| contested.contains(&c)))
+                .max_by_key(|(i, g)| {
+                    let request = pending[*i];
+                    let fallback = self.resolve_for(request, &without_contested);
+                    OrderedFloat(regret(request, g, fallback.as_ref()))
+                })
+            else {
+                for request in pending {
+                    if let Some(gear) = self.resolve_for(request, &pool) {
+                        self.take(&mut pool, request, &gear);
+                        allocation.insert(request.name.clone(), gear);
+                    }
+                }
+                break;
+            };
+            let request = pending.remove(i);
+            debug!(
+                "{}: allocated contested items {}",
+                request.name,
+                contested.iter().join(", ")
+            );
+            self.take(&mut pool, request, &gear);
+            allocation.insert(request.name.clone(), gear);
+        }
+        allocation
+    }
+
+    /// Removes from the `pool` the items of the `gear` the `request` does not
+    /// own.
+    fn take(&self, pool: &mut HashMap<String, u32>, request: &GearRequest, gear: &Gear) {
+        for (code, quantity) in self.equipment_of(gear) {
+            if let Some(left) = pool.get_mut(&code) {
+                *left = left.saturating_sub(request.missing(&code, quantity));
+            }
+        }
+    }
+
+    fn resolve_for(&self, request: &GearRequest, pool: &HashMap<String, u32>) -> Option<Gear> {
+        let mut available = pool.clone();
+        for (code, quantity) in &request.owned_items {
+            *available.entry(code.clone()).or_default() += quantity;
+        }
+        let mut resolver = GearResolver::new(self.items.clone(), request.purpose.clone())
+            .with_skill_levels(request.skill_levels.clone())
+            .with_available_items(available)
+            .with_excluded_items(self.excluded_items.clone())
+            .with_filter(Filter::available_only());
+        resolver.memo = Some(self.memo.clone());
+        resolver.resolve()
+    }
+
+    /// Returns the codes of the items wanted from the pool in a greater
+    /// quantity than it holds.
+    fn contested_items(
+        &self,
+        requests: &[&GearRequest],
+        gears: &[Option<Gear>],
+        pool: &HashMap<String, u32>,
+    ) -> HashSet<String> {
+        requests
+            .iter()
+            .zip(gears)
+            .filter_map(|(r, g)| Some((r, g.as_ref()?)))
+            .flat_map(|(r, g)| {
+                self.equipment_of(g)
+                    .map(|(code, quantity)| {
+                        let missing = r.missing(&code, quantity);
+                        (code, missing)
+                    })
+                    .collect_vec()
+            })
+            .filter(|(_, missing)| *missing > 0)
+            .into_grouping_map()
+            .sum()
+            .into_iter()
+            .filter(|(code, wanted)| wanted > pool.get(code).unwrap_or(&0))
+            .map(|(code, _)| code)
+            .collect()
+    }
+
+    /// Returns the items of the `gear` with their quantity, utilities aside
+    /// since they are consumed rather than shared.
+    fn equipment_of(&self, gear: &Gear) -> impl Iterator<Item = (String, u32)> {
+        HashMap::<String, u32>::from(gear.clone())
+            .into_iter()
+            .filter(|(code, _)| {
+                self.items
+                    .get(code.as_str())
+                    .is_some_and(|i| !i.r#type().is_utility())
+            })
+    }
+}
+
+/// Share of the throughput the `request` loses when wearing `fallback`
+/// instead of `gear`.
+fn regret(request: &GearRequest, gear: &Gear, fallback: Option<&Gear>) -> f64 {
+    let best = throughput(request, Some(gear));
+    if best <= 0.0 {
+        return 0.0;
+    }
+    1.0 - throughput(request, fallback) / best
+}
+
+/// Estimated rate at which the purpose of the `request` progresses with
+/// the given gear: fights won, drops or crafting XP per second of
+/// cooldown.
+fn throughput(request: &GearRequest, gear: Option<&Gear>) -> f64 {
+    let default = Gear::default();
+    let gear = gear.unwrap_or(&default);
+    match &request.purpose {
+        GearPurpose::Combat(monster) => {
+            let fight = FightSimulation::new(
+                Participant::new(request.name.clone())
+                    .with_level(request.level(Skill::Combat))
+                    .with_gear(gear.clone()),
+                monster.clone(),
+            )
+            .with_params(FightParams::averaged())
+            .run();
+            if !fight.is_winning() {
+                return 0.0;
+            }
+            1.0 / f64::from(fight.cd + time_to_rest(fight.hp_lost as u32)).max(1.0)
+        }
+        GearPurpose::Gathering(resource) => {
+            let cd = compute_gathering_cd(
+                resource.level(),
+                gear.skill_cooldown_reduction(resource.skill()),
+            );
+            (1.0 + f64::from(gear.prospecting()) / 1000.0) / f64::from(cd.max(1))
+        }
+        GearPurpose::Crafting(_) => wisdom_multiplier(gear.wisdom()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gear_finder::GearFinder;
+    use sdk::test_utils::{ITEMS, item, monster};
+
+    fn request(name: &str, monster_code: &str, level: u32) -> GearRequest {
+        GearRequest::new(name.into(), GearPurpose::Combat(monster(monster_code)))
+            .with_skill_levels(HashMap::from([(Skill::Combat, level)]))
+    }
+
+    #[test]
+    fn allocations_never_exceed_pool() {
+        let pool = HashMap::from([
+            ("copper_dagger".to_owned(), 1),
+            ("copper_helmet".to_owned(), 1),
+        ]);
+        let allocation = GearFinder::new(ITEMS.clone())
+            .allocate(
+                vec![
+                    request("char1", "chicken", 5),
+                    request("char2", "chicken", 5),
+                ],
+                pool.clone(),
+            )
+            .resolve();
+
+        for (code, quantity) in pool {
+            let allocated = allocation
+                .values()
+                .map(|g| {
+                    HashMap::<String, u32>::from(g.clone())
+                        .get(&code)
+                        .copied()
+                        .unwrap_or(0)
+                })
+                .sum::<u32>();
+            assert!(allocated <= quantity, "{code} allocated {allocated} times");
+        }
+    }
+
+    #[test]
+    fn contested_item_goes_to_character_losing_most_without_it() {
+        let pool = HashMap::from([
+            ("copper_dagger".to_owned(), 1),
+            ("wooden_stick".to_owned(), 1),
+        ]);
+        let allocation = GearFinder::new(ITEMS.clone())
+            .allocate(
+                vec![
+                    request("char1", "chicken", 3),
+                    request("char2", "yellow_slime", 3),
+                ],
+                pool,
+            )
+            .resolve();
+
+        assert_eq!(
+            allocation[&"char1".into()].weapon,
+            Some(item("wooden_stick"))
+        );
+        assert_eq!(
+            allocation[&"char2".into()].weapon,
+            Some(item("copper_dagger"))
+        );
+    }
+
+    #[test]
+    fn owned_items_stay_with_their_owner() {
+        let allocation = GearFinder::new(ITEMS.clone())
+            .allocate(
+                vec![
+                    request("char1", "chicken", 5),
+                    request("char2", "chicken", 5)
+                        .with_owned_items(HashMap::from([("copper_dagger".to_owned(), 1)])),
+                ],
+                HashMap::from([("wooden_stick".to_owned(), 1)]),
+            )
+            .resolve();
+
+        assert_eq!(
+            allocation[&"char1".into()].weapon,
+            Some(item("wooden_stick"))
+        );
+        assert_eq!(
+            allocation[&"char2".into()].weapon,
+            Some(item("copper_dagger"))
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use itertools::Itertools;
+use sdk::{
+    Code,
+    gear::{Gear, Slot},
+    items::Type,
+    simulator::{FightReport, HasEffects, time_to_rest},
+};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+/// Number of pruned gears listed when displaying a `GearExplanation`.
+const PRUNED_SHOWN: usize = 10;
+
+/// Filters removing catalog items from the pool of a `GearResolver`, in the
+/// order they are applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum PoolFilter {
+    Excluded,
+    Npc,
+    Task,
+    Monster,
+    Craftable,
+}
+
+/// Criteria ranking the gears winning a fight, from the most important to the
+/// least.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Display)]
+pub enum RankCriterion {
+    #[strum(serialize = "cd+rest")]
+    CooldownAndRest,
+    #[strum(serialize = "monster_hp")]
+    MonsterHp,
+    #[strum(serialize = "hp")]
+    Hp,
+    #[strum(serialize = "prospecting")]
+    Prospecting,
+    #[strum(serialize = "wisdom")]
+    Wisdom,
+    #[strum(serialize = "slot count")]
+    SlotCount,
+}
+
+impl RankCriterion {
+    /// Returns `Ordering::Less` if `a` ranks before `b` on this criterion.
+    pub(super) fn compare(self, a: &(FightReport, Gear), b: &(FightReport, Gear)) -> Ordering {
+        let ((fa, ga), (fb, gb)) = (a, b);
+        match self {
+            Self::CooldownAndRest => cost(fa).cmp(&cost(fb)),
+            Self::MonsterHp => fa.monster_hp.cmp(&fb.monster_hp),
+            Self::Hp => fb.hp.cmp(&fa.hp),
+            Self::Prospecting => gb.prospecting().cmp(&ga.prospecting()),
+            Self::Wisdom => gb.wisdom().cmp(&ga.wisdom()),
+            Self::SlotCount => slot_count(gb).cmp(&slot_count(ga)),
+        }
+    }
+
+    /// Returns the first criterion on which `winner` ranks before `runner_up`.
+    pub(super) fn deciding(
+        winner: &(FightReport, Gear),
+        runner_up: &(FightReport, Gear),
+    ) -> Option<Self> {
+        Self::iter().find(|c| c.compare(winner, runner_up).is_lt())
+    }
+}
+
+fn cost(fight: &FightReport) -> u32 {
+    fight.cd + time_to_rest(fight.hp_lost as u32)
+}
+
+fn items(gear: &Gear) -> String {
+    Slot::iter()
+        .filter_map(|s| gear.item_in(s).map(Code::code))
+        .join(", ")
+}
+
+fn slot_count(gear: &Gear) -> usize {
+    Slot::iter().filter(|s| gear.item_in(*s).is_some()).count()
+}
+
+/// Catalog items left after a `PoolFilter` was applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolStage {
+    pub filter: PoolFilter,
+    pub remaining: usize,
+    pub removed: Vec<String>,
+}
+
+/// Candidates considered for each slot along with a weapon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeaponCandidates {
+    pub weapon: String,
+    pub slots: Vec<(Type, Vec<String>)>,
+}
+
+/// How a `GearResolver` came to its result, see `GearResolver::explain`.
+#[derive(Debug, Clone, Default)]
+pub struct GearExplanation {
+    /// Equipable catalog items before filtering.
+    pub catalog: usize,
+    pub stages: Vec<PoolStage>,
+    /// Items the gears were built from, owned ones included.
+    pub pool: usize,
+    pub candidates: Vec<WeaponCandidates>,
+    /// Number of gears built from the candidates.
+    pub generated: usize,
+    /// Number of gears left once the dominated ones were pruned.
+    pub kept: usize,
+    /// Pruned gears along with a gear dominating them.
+    pub pruned: Vec<(Gear, Gear)>,
+    /// Number of gears winning the fight.
+    pub winning: usize,
+    /// Best winning gears with their fight, best first.
+    pub loadouts: Vec<(FightReport, Gear)>,
+    /// Criterion on which the best gear beat the second one, `None` if they
+    /// were tied on all of them.
+    pub decided_by: Option<RankCriterion>,
+    pub gear: Option<Gear>,
+}
+
+impl Display for GearExplanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "item pool:")?;
+        writeln!(f, "  equipable: {}", self.catalog)?;
+        for stage in &self.stages {
+            write!(f, "  {}: {}", stage.filter, stage.remaining)?;
+            if !stage.removed.is_empty() {
+                write!(f, " (removed {})", stage.removed.join(", "))?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(
+            f,
+            "  with owned items and skill requirements: {}",
+            self.pool
+        )?;
+        for candidates in &self.candidates {
+            writeln!(f, "candidates with {}:", candidates.weapon)?;
+            for (r#type, items) in &candidates.slots {
+                writeln!(f, "  {type}: {}", items.join(", "))?;
+            }
+        }
+        if !self.candidates.is_empty() {
+            writeln!(
+                f,
+                "gears: {} generated, {} kept after pruning",
+                self.generated, self.kept
+            )?;
+            for (gear, by) in self.pruned.iter().take(PRUNED_SHOWN) {
+                writeln!(f, "  {} dominated by {}", items(gear), items(by))?;
+            }
+            if self.pruned.len() > PRUNED_SHOWN {
+                writeln!(f, "  and {} more", self.pruned.len() - PRUNED_SHOWN)?;
+            }
+            writeln!(f, "winning gears: {}", self.winning)?;
+            for (i, (fight, gear)) in self.loadouts.iter().enumerate() {
+                writeln!(
+                    f,
+                    "  {}. cd+rest: {}s, monster_hp: {}, hp: {}, turns: {} | {}",
+                    i + 1,
+                    cost(fight),
+                    fight.monster_hp,
+                    fight.hp,
+                    fight.turns,
+                    items(gear)
+                )?;
+            }
+            match (self.winning, self.decided_by) {
+                (0, _) => writeln!(f, "no gear wins the fight")?,
+                (1, _) => writeln!(f, "decided by: only winning gear")?,
+                (_, Some(criterion)) => writeln!(f, "decided by: {criterion}")?,
+                (_, None) => writeln!(f, "decided by: tied on every criterion")?,
+            }
+        }
+        match &self.gear {
+            Some(gear) => write!(f, "{gear}"),
+            None => writeln!(f, "no gear found"),
+        }
+    }
+}
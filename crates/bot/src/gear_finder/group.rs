@@ -0,0 +1,423 @@
+use crate::gear_finder::{Filter, GearPurpose, GearResolver};
+use itertools::Itertools;
+use log::debug;
+use ordered_float::OrderedFloat;
+use sdk::{
+    Code, ItemsClient,
+    entities::{CharacterName, Monster},
+    gear::Gear,
+    simulator::{FightOdds, FightParams, FightSimulation, HasEffects, Participant, time_to_rest},
+    skill::Skill,
+};
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+/// Maximum number of characters taking part in a fight.
+pub const MAX_GROUP_SIZE: usize = 3;
+
+/// Part played by a character in a group fight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum GroupRole {
+    /// Draws the monster attacks with threat.
+    Tank,
+    /// Keeps itself up with healing.
+    Healer,
+    Damage,
+}
+
+/// Character taking part in a group fight, along with what its gear can be
+/// resolved from.
+///
+/// Its available items are the ones it owns, the items shared by the group
+/// being given to [`GroupGearResolver::with_shared_items`].
+#[derive(Debug, Clone)]
+pub struct GroupMember {
+    name: CharacterName,
+    skill_levels: HashMap<Skill, u32>,
+    available_items: HashMap<String, u32>,
+}
+
+impl GroupMember {
+    #[must_use]
+    pub fn new(name: CharacterName) -> Self {
+        Self {
+            name,
+            skill_levels: HashMap::new(),
+            available_items: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_skill_levels(mut self, levels: HashMap<Skill, u32>) -> Self {
+        self.skill_levels = levels;
+        self
+    }
+
+    #[must_use]
+    pub fn with_available_items(mut self, items: HashMap<String, u32>) -> Self {
+        self.available_items = items;
+        self
+    }
+
+    #[must_use]
+    pub const fn name(&self) -> &CharacterName {
+        &self.name
+    }
+
+    fn level(&self) -> u32 {
+        *self.skill_levels.get(&Skill::Combat).unwrap_or(&1)
+    }
+}
+
+/// Gear chosen for a member of the group and the role it plays with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupLoadout {
+    pub name: CharacterName,
+    pub role: GroupRole,
+    pub gear: Gear,
+}
+
+pub struct GroupGearResolver {
+    items: ItemsClient,
+    monster: Monster,
+    members: Vec<GroupMember>,
+    /// Items any member can take, such as the ones in the bank.
+    shared_items: HashMap<String, u32>,
+    filter: Filter,
+    excluded_items: Vec<String>,
+    confidence: f64,
+}
+
+impl GroupGearResolver {
+    pub(super) fn new(items: ItemsClient, monster: Monster, members: Vec<GroupMember>) -> Self {
+        Self {
+            items,
+            monster,
+            members,
+            shared_items: HashMap::new(),
+            filter: Filter::available_only(),
+            excluded_items: vec![],
+            confidence: 1.0,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    #[must_use]
+    pub fn with_excluded_items(mut self, items: Vec<String>) -> Self {
+        self.excluded_items = items;
+        self
+    }
+
+    /// Win rate the group must reach with its loadouts, 1 by default.
+    #[must_use]
+    pub const fn with_confidence(mut self, confidence: f64) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Sets the items shared by the members on top of their own, each copy
+    /// going to a single member.
+    #[must_use]
+    pub fn with_shared_items(mut self, items: HashMap<String, u32>) -> Self {
+        self.shared_items = items;
+        self
+    }
+
+    /// Resolves a loadout for each member, in the order they were given.
+    ///
+    /// Each member proposes its best gear for every `GroupRole`, then every
+    /// combination of proposals is fought against the monster so that threat
+    /// and healing are weighted by how the group actually fares, every hit
+    /// dealing its average damage. The combination with the highest win rate,
+    /// then the lowest cooldown for the initiator, is kept if its odds with
+    /// critical strikes reach the confidence. When only available items are
+    /// allowed, combinations taking more shared items than there are are left
+    /// out. Returns `None` if the kept combination does not reach the
+    /// confidence or if the group cannot fight the monster together.
+    #[must_use]
+    pub fn resolve(self) -> Option<Vec<GroupLoadout>> {
+        if self.members.is_empty()
+            || self.members.len() > MAX_GROUP_SIZE
+            || (self.members.len() > 1 && !self.monster.is_boss())
+        {
+            return None;
+        }
+        let candidates = self
+            .members
+            .iter()
+            .map(|m| self.candidates_for(m))
+            .collect_vec();
+        let (_, gears) = candidates
+            .into_iter()
+            .multi_cartesian_product()
+            .filter(|gears| !self.filter.is_available_only() || self.shares_enough_for(gears))
+            .map(|gears| (self.odds_with(&gears, FightParams::averaged()), gears))
+            .filter(|(odds, _)| odds.win_rate > 0.0)
+            .max_set_by_key(|(odds, _)| OrderedFloat(odds.win_rate))
+            .into_iter()
+            .min_by_key(|(odds, _)| {
+                OrderedFloat(
+                    odds.expected_cd
+                        + f64::from(time_to_rest(odds.expected_hp_lost.round() as u32)),
+                )
+            })?;
+        let odds = self.odds_with(&gears, FightParams::default());
+        debug!(
+            "group gear against '{}': {:.2}% win rate",
+            self.monster.code(),
+            odds.win_rate * 100.0
+        );
+        if odds.win_rate < self.confidence {
+            return None;
+        }
+        let roles = assign_roles(&gears);
+        Some(
+            self.members
+                .iter()
+                .zip(gears)
+                .zip(roles)
+                .map(|((member, gear), role)| GroupLoadout {
+                    name: member.name.clone(),
+                    role,
+                    gear,
+                })
+                .collect_vec(),
+        )
+    }
+
+    /// Returns the distinct gears the `member` would wear for each role, from
+    /// its own items along with the shared ones and, in case another member
+    /// takes the shared ones, from its own items only.
+    fn candidates_for(&self, member: &GroupMember) -> Vec<Gear> {
+        let mut with_shared = self.shared_items.clone();
+        for (code, quantity) in &member.available_items {
+            *with_shared.entry(code.clone()).or_default() += quantity;
+        }
+        [with_shared, member.available_items.clone()]
+            .into_iter()
+            .cartesian_product(GroupRole::iter().collect_vec())
+            .filter_map(|(available_items, role)| {
+                let mut resolver = GearResolver::new(
+                    self.items.clone(),
+                    GearPurpose::Combat(self.monster.clone()),
+                )
+                .with_skill_levels(member.skill_levels.clone())
+                .with_available_items(available_items)
+                .with_excluded_items(self.excluded_items.clone())
+                .with_filter(self.filter);
+                resolver.role = Some(role);
+                resolver.item_pool = resolver.create_item_pool();
+                resolver.best_for_role(&self.monster, role)
+            })
+            .unique_by(ToString::to_string)
+            .collect_vec()
+    }
+
+    /// Checks the shared items are enough for every member to wear its gear
+    /// of `gears` once its own items are used up.
+    fn shares_enough_for(&self, gears: &[Gear]) -> bool {
+        let mut shared = self.shared_items.clone();
+        for (member, gear) in self.members.iter().zip(gears) {
+            let worn: HashMap<String, u32> = gear.clone().into();
+            for (code, quantity) in worn {
+                let owned = member.available_items.get(&code).copied().unwrap_or(0);
+                let missing = quantity.saturating_sub(owned);
+                if missing == 0 {
+                    continue;
+                }
+                let left = shared.entry(code).or_default();
+                if *left < missing {
+                    return false;
+                }
+                *left -= missing;
+            }
+        }
+        true
+    }
+
+    fn odds_with(&self, gears: &[Gear], params: FightParams) -> FightOdds {
+        let mut participants = self
+            .members
+            .iter()
+            .zip(gears)
+            .map(|(member, gear)| {
+                Participant::new(member.name.clone())
+                    .with_level(member.level())
+                    .with_gear(gear.clone())
+            })
+            .collect_vec();
+        let initiator = participants.remove(0);
+        FightSimulation::new(initiator, self.monster.clone())
+            .with_participants(participants)
+            .with_params(params)
+            .odds()
+    }
+}
+
+impl GearResolver {
+    /// Returns the gear that best fulfills the `role` against the `monster`,
+    /// breaking ties with health then damage.
+    pub(super) fn best_for_role(&self, monster: &Monster, role: GroupRole) -> Option<Gear> {
+        let gears = self.gen_combat_gears(monster).collect_vec();
+        let gears = match role {
+            GroupRole::Tank => gears.into_iter().max_set_by_key(HasEffects::threat),
+            GroupRole::Healer => gears
+                .into_iter()
+                .max_set_by_key(HasEffects::healing)
+                .into_iter()
+                .max_set_by_key(HasEffects::restore),
+            GroupRole::Damage => gears
+                .into_iter()
+                .max_set_by_key(|g| OrderedFloat(g.average_dmg_against(monster))),
+        };
+        gears
+            .into_iter()
+            .max_set_by_key(HasEffects::health)
+            .into_iter()
+            .max_by_key(|g| OrderedFloat(g.average_dmg_against(monster)))
+    }
+}
+
+/// Gives the tank role to the member drawing the most threat and the healer
+/// role to the one healing the most, everyone else deals damage.
+fn assign_roles(gears: &[Gear]) -> Vec<GroupRole> {
+    let mut roles = vec![GroupRole::Damage; gears.len()];
+    if gears.len() < 2 {
+        return roles;
+    }
+    let tank = (0..gears.len())
+        .filter(|&i| gears[i].threat() > 0)
+        .max_by_key(|&i| (gears[i].threat(), gears[i].health()));
+    if let Some(tank) = tank {
+        roles[tank] = GroupRole::Tank;
+    }
+    if let Some(healer) = (0..gears.len())
+        .filter(|&i| Some(i) != tank && gears[i].healing() > 0)
+        .max_by_key(|&i| gears[i].healing())
+    {
+        roles[healer] = GroupRole::Healer;
+    }
+    roles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdk::test_utils::{ITEMS, item, monster};
+
+    fn member(name: &str, level: u32) -> GroupMember {
+        GroupMember::new(name.into()).with_skill_levels(HashMap::from([(Skill::Combat, level)]))
+    }
+
+    fn resolver(members: Vec<GroupMember>) -> GroupGearResolver {
+        GroupGearResolver::new(ITEMS.clone(), monster("king_slime"), members)
+            .with_filter(Filter::default())
+            .with_confidence(0.95)
+    }
+
+    fn trio(level: u32) -> Vec<GroupMember> {
+        vec![
+            member("char1", level),
+            member("char2", level),
+            member("char3", level),
+        ]
+    }
+
+    #[test]
+    fn group_beats_boss_a_lone_character_cannot() {
+        let loadouts = resolver(trio(20)).resolve().unwrap();
+
+        assert!(resolver(vec![member("char1", 20)]).resolve().is_none());
+        assert_eq!(
+            loadouts.iter().map(|l| l.name.to_string()).collect_vec(),
+            ["char1", "char2", "char3"]
+        );
+    }
+
+    #[test]
+    fn group_unlikely_to_win_is_rejected() {
+        // The group wins when every hit deals its average damage, but rarely
+        // once critical strikes are accounted for.
+        eprintln!(
+            "DBG {:?}",
+            resolver(trio(15)).with_confidence(0.0).resolve().is_some()
+        );
+        eprintln!(
+            "DBG {:?}",
+            resolver(trio(15))
+                .with_confidence(0.0001)
+                .resolve()
+                .is_some()
+        );
+        assert!(resolver(trio(15)).resolve().is_none());
+    }
+
+    #[test]
+    fn shared_items_go_to_a_single_member() {
+        let owned = |name: &str| {
+            member(name, 40).with_available_items(HashMap::from([
+                ("copper_dagger".into(), 1),
+                ("white_knight_shield".into(), 1),
+                ("batwing_helmet".into(), 1),
+                ("gold_platebody".into(), 1),
+                ("enchanter_pants".into(), 1),
+                ("leather_boots".into(), 1),
+                ("mithril_ring".into(), 2),
+                ("ancestral_talisman".into(), 1),
+            ]))
+        };
+        let loadouts = GroupGearResolver::new(
+            ITEMS.clone(),
+            monster("king_slime"),
+            vec![owned("char1"), owned("char2"), owned("char3")],
+        )
+        .with_shared_items(HashMap::from([("gold_sword".into(), 1)]))
+        .with_confidence(0.95)
+        .resolve()
+        .unwrap();
+
+        assert_eq!(
+            loadouts
+                .iter()
+                .filter(|l| l
+                    .gear
+                    .weapon
+                    .as_ref()
+                    .is_some_and(|w| w.code() == "gold_sword"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn non_boss_cannot_be_fought_in_group() {
+        let resolver = GroupGearResolver::new(
+            ITEMS.clone(),
+            monster("chicken"),
+            vec![member("char1", 10), member("char2", 10)],
+        );
+
+        assert!(resolver.resolve().is_none());
+    }
+
+    #[test]
+    fn roles_follow_threat_and_healing() {
+        let roles = assign_roles(&[
+            Gear::default().with_weapon(item("iron_sword")),
+            Gear::default().with_rune(item("healing_rune")),
+            Gear::default().with_boots(item("iron_boots")),
+        ]);
+
+        assert_eq!(
+            roles,
+            [GroupRole::Damage, GroupRole::Healer, GroupRole::Tank]
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use crate::gear_finder::Filter;
+use lru::LruCache;
+use sdk::{gear::Gear, skill::Skill};
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+/// Number of resolutions kept, the least recently used being evicted first so
+/// that entries of items no longer available are dropped over time.
+const MAX_ENTRIES: NonZeroUsize = NonZeroUsize::new(10_000).unwrap();
+
+/// Gears already resolved by the resolvers of a `GearFinder`.
+///
+/// Entries are keyed by everything a resolution depends on, so that a change
+/// of the available items or skill levels is a miss instead of a stale hit.
+pub(super) struct GearMemo {
+    entries: Mutex<LruCache<MemoKey, Entry>>,
+}
+
+/// Resolution of a key, locked while it is being resolved. `None` until
+/// resolved.
+type Entry = Arc<Mutex<Option<Option<Gear>>>>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct MemoKey {
+    /// Kind and code of the entity the gear is resolved for.
+    pub purpose: (&'static str, String),
+    /// Codes of the items the gear is picked from.
+    pub pool: Vec<String>,
+    /// Equipable items available to the character, sorted by code.
+    pub available: Vec<(String, u32)>,
+    /// Skill levels of the character, sorted by skill.
+    pub skill_levels: Vec<(Skill, u32)>,
+    pub filter: Filter,
+}
+
+impl GearMemo {
+    /// Returns the gear memoized for `key`, resolving it with `resolve` if
+    /// missing. Concurrent calls for the same key wait for a single
+    /// resolution instead of running their own.
+    pub(super) fn get_or_resolve(
+        &self,
+        key: MemoKey,
+        resolve: impl FnOnce() -> Option<Gear>,
+    ) -> Option<Gear> {
+        let entry = self
+            .entries
+            .lock()
+            .unwrap()
+            .get_or_insert(key, Arc::default)
+            .clone();
+        let mut gear = entry.lock().unwrap();
+        gear.get_or_insert_with(resolve).clone()
+    }
+
+    pub(super) fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    #[cfg(test)]
+    pub(super) fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+}
+
+impl Default for GearMemo {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(MAX_ENTRIES)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    fn key() -> MemoKey {
+        MemoKey {
+            purpose: ("combat", "chicken".to_owned()),
+            pool: vec![],
+            available: vec![],
+            skill_levels: vec![],
+            filter: Filter::default(),
+        }
+    }
+
+    #[test]
+    fn changed_items_miss_the_memo() {
+        let memo = GearMemo::default();
+        let _ = memo.get_or_resolve(key(), || Some(Gear::default()));
+        assert_eq!(memo.get_or_resolve(key(), || None), Some(Gear::default()));
+
+        let changed = MemoKey {
+            available: vec![("copper_dagger".to_owned(), 1)],
+            ..key()
+        };
+        assert_eq!(memo.get_or_resolve(changed, || None), None);
+        assert_eq!(memo.len(), 2);
+    }
+
+    #[test]
+    fn full_memo_evicts_the_least_recently_used() {
+        let memo = GearMemo::default();
+        let _ = memo.get_or_resolve(key(), || Some(Gear::default()));
+        for quantity in 1..=MAX_ENTRIES.get() as u32 {
+            let _ = memo.get_or_resolve(key(), || None);
+            let key = MemoKey {
+                available: vec![("copper_dagger".to_owned(), quantity)],
+                ..key()
+            };
+            let _ = memo.get_or_resolve(key, || None);
+        }
+
+        assert_eq!(memo.len(), MAX_ENTRIES.get());
+        assert_eq!(memo.get_or_resolve(key(), || None), Some(Gear::default()));
+    }
+
+    #[test]
+    fn concurrent_misses_resolve_once() {
+        let memo = GearMemo::default();
+        let resolutions = AtomicUsize::new(0);
+        (0..8).into_par_iter().for_each(|_| {
+            let _ = memo.get_or_resolve(key(), || {
+                resolutions.fetch_add(1, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(50));
+                None
+            });
+        });
+
+        assert_eq!(resolutions.load(Ordering::SeqCst), 1);
+    }
+}
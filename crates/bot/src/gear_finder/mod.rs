@@ -1,6 +1,9 @@
 use itertools::Itertools;
 use log::warn;
+use memo::{GearMemo, MemoKey};
 use ordered_float::OrderedFloat;
+use pruning::{explain_pruning, prune_dominated};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use sdk::{
     CanProvideXp, Code, CollectionClient, ItemsClient, Level, MAX_LEVEL,
     entities::{Item, Monster, Resource},
@@ -9,27 +12,40 @@ use sdk::{
         ItemSource,
         Type::{self, Rune},
     },
-    simulator::{FightParams, FightSimulation, HasEffects, Participant, time_to_rest},
+    simulator::{FightParams, FightReport, FightSimulation, HasEffects, Participant},
     skill::Skill,
     yields_xp,
 };
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
+pub use allocation::{GearAllocator, GearRequest};
 pub use artifact_set::ArtifactSet;
 pub use component::{GearComponent, ItemSlot};
+pub use explanation::{GearExplanation, PoolFilter, PoolStage, RankCriterion, WeaponCandidates};
 pub use filter::Filter;
+pub use group::{GroupGearResolver, GroupLoadout, GroupMember, GroupRole};
 pub use ring_set::RingSet;
+pub use roadmap::{GearRoadmap, RoadmapPlanner, RoadmapStep};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIs;
 pub use utility_set::UtilitySet;
 
+mod allocation;
 mod artifact_set;
 mod component;
+mod explanation;
 mod filter;
+mod group;
+mod memo;
+mod pruning;
 mod ring_set;
+mod roadmap;
 mod utility_set;
 
-#[derive(Clone, EnumIs)]
+#[derive(Clone, PartialEq, EnumIs)]
 pub enum GearPurpose {
     Combat(Monster),
     Crafting(Item),
@@ -39,17 +55,53 @@ pub enum GearPurpose {
 #[derive(Clone)]
 pub struct GearFinder {
     items: ItemsClient,
+    memo: Arc<GearMemo>,
 }
 
 impl GearFinder {
     #[must_use]
-    pub const fn new(items: ItemsClient) -> Self {
-        Self { items }
+    pub fn new(items: ItemsClient) -> Self {
+        Self {
+            items,
+            memo: Arc::default(),
+        }
     }
 
     #[must_use]
     pub fn best_for(&self, purpose: GearPurpose) -> GearResolver {
-        GearResolver::new(self.items.clone(), purpose)
+        let mut resolver = GearResolver::new(self.items.clone(), purpose);
+        resolver.memo = Some(self.memo.clone());
+        resolver
+    }
+
+    /// Forgets every gear resolved so far.
+    pub fn clear_memo(&self) {
+        self.memo.clear();
+    }
+
+    /// Resolves the loadouts of a group of up to three `members` fighting the
+    /// given boss together.
+    #[must_use]
+    pub fn best_for_group(&self, monster: Monster, members: Vec<GroupMember>) -> GroupGearResolver {
+        GroupGearResolver::new(self.items.clone(), monster, members)
+    }
+
+    /// Shares the items of the `pool` between the characters of the `requests`,
+    /// each one also having its own items, see [`GearAllocator::resolve`].
+    #[must_use]
+    pub fn allocate(
+        &self,
+        requests: Vec<GearRequest>,
+        pool: HashMap<String, u32>,
+    ) -> GearAllocator {
+        GearAllocator::new(self.items.clone(), self.memo.clone(), requests, pool)
+    }
+
+    /// Plans how to get a gear killing the given `monster`, see
+    /// [`RoadmapPlanner::plan`].
+    #[must_use]
+    pub fn roadmap_to(&self, monster: Monster) -> RoadmapPlanner {
+        RoadmapPlanner::new(self.items.clone(), monster)
     }
 }
 
@@ -64,6 +116,8 @@ pub struct GearResolver {
     excluded_items: HashSet<String>,
     can_craft: Option<CanCraftFn>,
     item_pool: Vec<Item>,
+    role: Option<GroupRole>,
+    memo: Option<Arc<GearMemo>>,
 }
 
 impl GearResolver {
@@ -77,6 +131,8 @@ impl GearResolver {
             excluded_items: HashSet::new(),
             can_craft: None,
             item_pool: Vec::new(),
+            role: None,
+            memo: None,
         }
     }
 
@@ -149,6 +205,11 @@ impl GearResolver {
     }
 
     fn is_eligible(&self, item: &Item) -> bool {
+        !self.filter.is_available_only() && item.is_equipable() && self.rejected_by(item).is_none()
+    }
+
+    /// Returns the first catalog filter removing `item` from the pool.
+    fn rejected_by(&self, item: &Item) -> Option<PoolFilter> {
         let Filter::Catalog {
             force_craftable,
             from_task,
@@ -157,19 +218,16 @@ impl GearResolver {
             ..
         } = self.filter
         else {
-            return false;
+            return None;
         };
-        if !item.is_equipable() {
-            return false;
-        }
         if self.excluded_items.contains(item.code()) {
-            return false;
+            return Some(PoolFilter::Excluded);
         }
         if !from_npc && self.items.is_buyable(item.code()) {
-            return false;
+            return Some(PoolFilter::Npc);
         }
         if !from_task && item.is_crafted_from_task() {
-            return false;
+            return Some(PoolFilter::Task);
         }
         if !from_monster
             && self
@@ -178,16 +236,16 @@ impl GearResolver {
                 .iter()
                 .any(ItemSource::is_monster)
         {
-            return false;
+            return Some(PoolFilter::Monster);
         }
         if let Some(can_craft) = &self.can_craft
             && force_craftable
             && item.is_craftable()
             && !can_craft(item.code())
         {
-            return false;
+            return Some(PoolFilter::Craftable);
         }
-        true
+        None
     }
 
     /// Resolve the best gear based on the internal properties:
@@ -203,9 +261,19 @@ impl GearResolver {
     ///
     /// When resolving gears with both catalog and available items, items from `available_items`
     /// are prioritized in case of a tie, and catalog items are considered of infinite quantity
+    ///
+    /// Resolvers created from a [`GearFinder`] share their results, resolving the same purpose
+    /// from the same items and skill levels twice only resolves it once.
     #[must_use]
     pub fn resolve(mut self) -> Option<Gear> {
         self.item_pool = self.create_item_pool();
+        self.memo.as_ref().map_or_else(
+            || self.resolve_uncached(),
+            |memo| memo.get_or_resolve(self.memo_key(), || self.resolve_uncached()),
+        )
+    }
+
+    fn resolve_uncached(&self) -> Option<Gear> {
         match &self.purpose {
             GearPurpose::Combat(monster) => self.best_to_kill(monster),
             GearPurpose::Crafting(item) => self.best_to_craft(item),
@@ -213,14 +281,67 @@ impl GearResolver {
         }
     }
 
+    fn memo_key(&self) -> MemoKey {
+        let purpose = match &self.purpose {
+            GearPurpose::Combat(monster) => ("combat", monster.code().to_owned()),
+            GearPurpose::Crafting(item) => ("crafting", item.code().to_owned()),
+            GearPurpose::Gathering(resource) => ("gathering", resource.code().to_owned()),
+        };
+        MemoKey {
+            purpose,
+            pool: self.item_pool.iter().map(|i| i.code().to_owned()).collect(),
+            available: self
+                .available_items
+                .iter()
+                .filter(|(code, quantity)| {
+                    **quantity > 0
+                        && self
+                            .items
+                            .get(code.as_str())
+                            .is_some_and(|i| i.is_equipable())
+                })
+                .map(|(code, quantity)| (code.clone(), *quantity))
+                .sorted()
+                .collect(),
+            skill_levels: self
+                .skill_levels
+                .iter()
+                .map(|(skill, level)| (*skill, *level))
+                .sorted_by_key(|(skill, _)| skill.to_string())
+                .collect(),
+            filter: self.filter,
+        }
+    }
+
     /// Return the best gear to kill the given monster, if no gear allow the character to win the
     /// fight, returns None
     fn best_to_kill(&self, monster: &Monster) -> Option<Gear> {
-        self.gen_combat_gears(monster)
+        self.ranked_combat_gears(monster)
+            .into_iter()
+            .next()
+            .map(|(_, g)| g)
+    }
+
+    /// Returns the gears winning against the given monster along with their fight, ranked by
+    /// [`RankCriterion`]. Gears tied on every criterion are ranked by reverse generation order.
+    ///
+    /// Gears that are beaten by another one on every stat are pruned before the remaining ones
+    /// are simulated in parallel.
+    fn ranked_combat_gears(&self, monster: &Monster) -> Vec<(FightReport, Gear)> {
+        let gears = prune_dominated(monster, self.gen_combat_gears(monster).collect_vec());
+        self.rank_combat_gears(monster, gears)
+    }
+
+    /// Fights the `monster` with each of the `gears` and returns the winning
+    /// ones along with their fight, best first.
+    fn rank_combat_gears(&self, monster: &Monster, gears: Vec<Gear>) -> Vec<(FightReport, Gear)> {
+        let level = self.skill_level(Skill::Combat);
+        gears
+            .into_par_iter()
             .filter_map(|g| {
                 let sim = FightSimulation::new(
                     Participant::new("char1".into())
-                        .with_level(self.skill_level(Skill::Combat))
+                        .with_level(level)
                         .with_gear(g.clone()),
                     monster.clone(),
                 )
@@ -228,18 +349,114 @@ impl GearResolver {
                 let fight = sim.run();
                 fight.is_winning().then_some((fight, g))
             })
-            .min_set_by_key(|(f, _)| f.cd + time_to_rest(f.hp_lost as u32))
-            .into_iter()
-            .min_set_by_key(|(f, _)| f.monster_hp)
-            .into_iter()
-            .max_set_by_key(|(f, _)| f.hp)
+            .collect::<Vec<_>>()
             .into_iter()
-            .max_set_by_key(|(_, g)| g.prospecting())
-            .into_iter()
-            .max_set_by_key(|(_, g)| g.wisdom())
+            .enumerate()
+            .sorted_by(|(i, a), (j, b)| {
+                RankCriterion::iter()
+                    .map(|c| c.compare(a, b))
+                    .find(|o| o.is_ne())
+                    .unwrap_or_else(|| j.cmp(i))
+            })
+            .map(|(_, ranked)| ranked)
+            .collect_vec()
+    }
+
+    /// Resolves the gear like [`GearResolver::resolve`] while recording how the item pool was
+    /// built, the candidates of each slot, the gears pruned as dominated, the `top` best winning
+    /// gears and what decided between the two best ones.
+    #[must_use]
+    pub fn explain(mut self, top: usize) -> GearExplanation {
+        self.item_pool = self.create_item_pool();
+        let mut explanation = GearExplanation {
+            pool: self.item_pool.len(),
+            ..Default::default()
+        };
+        self.explain_pool(&mut explanation);
+        let GearPurpose::Combat(monster) = &self.purpose else {
+            explanation.gear = self.resolve_uncached();
+            return explanation;
+        };
+        explanation.candidates = self
+            .best_weapons(monster)
             .into_iter()
-            .max_by_key(|(_, g)| Slot::iter().filter(|s| g.item_in(*s).is_some()).count())
-            .map(|(_, g)| g)
+            .map(|weapon| self.combat_candidates(monster, weapon))
+            .collect();
+        let gears = self.gen_combat_gears(monster).collect_vec();
+        explanation.generated = gears.len();
+        let (gears, pruned) = explain_pruning(monster, gears);
+        explanation.kept = gears.len();
+        explanation.pruned = pruned;
+        let ranked = self.rank_combat_gears(monster, gears);
+        explanation.winning = ranked.len();
+        explanation.decided_by = ranked
+            .get(1)
+            .and_then(|runner_up| RankCriterion::deciding(&ranked[0], runner_up));
+        explanation.gear = ranked.first().map(|(_, g)| g.clone());
+        explanation.loadouts = ranked.into_iter().take(top).collect();
+        explanation
+    }
+
+    fn explain_pool(&self, explanation: &mut GearExplanation) {
+        if self.filter.is_available_only() {
+            return;
+        }
+        let equipable = self.items.iter().filter(Item::is_equipable).collect_vec();
+        explanation.catalog = equipable.len();
+        let rejections = equipable
+            .iter()
+            .filter_map(|i| self.rejected_by(i).map(|f| (f, i.code().to_owned())))
+            .into_group_map();
+        let mut remaining = equipable.len();
+        explanation.stages = PoolFilter::iter()
+            .map(|filter| {
+                let removed = rejections
+                    .get(&filter)
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .sorted()
+                    .collect_vec();
+                remaining -= removed.len();
+                PoolStage {
+                    filter,
+                    remaining,
+                    removed,
+                }
+            })
+            .collect();
+    }
+
+    fn combat_candidates(&self, monster: &Monster, weapon: &Item) -> WeaponCandidates {
+        let codes = |items: Vec<Item>| items.iter().map(|i| i.code().to_owned()).collect_vec();
+        let mut slots = [
+            Type::Helmet,
+            Type::Shield,
+            Type::BodyArmor,
+            Type::LegArmor,
+            Type::Boots,
+            Type::Amulet,
+            Type::Ring,
+            Type::Artifact,
+        ]
+        .into_iter()
+        .map(|r#type| {
+            (
+                r#type,
+                codes(self.best_combat_armors(monster, weapon, r#type)),
+            )
+        })
+        .collect_vec();
+        if self.filter.utilities_allowed() {
+            slots.push((
+                Type::Utility,
+                codes(self.best_combat_utilities(monster, weapon)),
+            ));
+        }
+        WeaponCandidates {
+            weapon: weapon.code().to_owned(),
+            slots,
+        }
     }
 
     /// Return the best gear to craft the given Item, if the character would not get XP from the
@@ -365,6 +582,16 @@ impl GearResolver {
         {
             bests.push(best);
         }
+        let role_criteria = match self.role {
+            Some(GroupRole::Tank) => Some(GearCriteria::Threat),
+            Some(GroupRole::Healer) => Some(GearCriteria::Healing),
+            Some(GroupRole::Damage) | None => None,
+        };
+        if let Some(criteria) = role_criteria
+            && let Some(best) = self.best_by_among(criteria, &armors)
+        {
+            bests.push(best);
+        }
         bests.into_iter().sorted().dedup().cloned().collect()
     }
 
@@ -396,11 +623,19 @@ impl GearResolver {
     }
 
     fn best_combat_runes(&self) -> Vec<GearComponent> {
-        self.item_pool
-            .iter()
-            .filter(|i| i.type_is(Rune))
-            .max_set_by_key(HasEffects::burn)
+        let runes = self.item_pool.iter().filter(|i| i.type_is(Rune));
+        let mut bests = runes.clone().max_set_by_key(HasEffects::burn);
+        if self.role == Some(GroupRole::Healer) {
+            bests.extend(
+                runes
+                    .filter(|i| i.healing() > 0)
+                    .max_set_by_key(HasEffects::healing),
+            );
+        }
+        bests
             .into_iter()
+            .sorted()
+            .dedup()
             .map(Into::into)
             .collect_vec()
     }
@@ -550,6 +785,8 @@ impl GearResolver {
             GearCriteria::Wisdom => i.wisdom() > 0,
             GearCriteria::Health => i.health() > 0,
             GearCriteria::Restore => i.restore() > 0,
+            GearCriteria::Threat => i.threat() > 0,
+            GearCriteria::Healing => i.healing() > 0,
         });
         let armors = match criteria {
             GearCriteria::DamageBoost { weapon, monster } => armors.max_set_by_key(|i| {
@@ -562,6 +799,8 @@ impl GearResolver {
             GearCriteria::Wisdom => armors.max_set_by_key(HasEffects::wisdom),
             GearCriteria::Health => armors.max_set_by_key(HasEffects::health),
             GearCriteria::Restore => armors.max_set_by_key(HasEffects::restore),
+            GearCriteria::Threat => armors.max_set_by_key(HasEffects::threat),
+            GearCriteria::Healing => armors.max_set_by_key(HasEffects::healing),
         };
         armors
             .into_iter()
@@ -610,6 +849,8 @@ enum GearCriteria<'a> {
     },
     Health,
     Restore,
+    Threat,
+    Healing,
     Prospecting,
     Wisdom,
 }
@@ -659,6 +900,68 @@ mod tests {
     use super::*;
     use sdk::test_utils::{ITEMS, item, monster, resource};
 
+    #[test]
+    fn finder_memoizes_resolutions() {
+        let finder = GearFinder::new(ITEMS.clone());
+        let resolve = |level| {
+            finder
+                .best_for(GearPurpose::Combat(monster("chicken")))
+                .with_skill_levels(HashMap::from([(Skill::Combat, level)]))
+                .with_available_items(HashMap::from([
+                    ("copper_dagger".into(), 1),
+                    ("copper_ore".into(), 10),
+                ]))
+                .with_filter(Filter::available_only())
+                .resolve()
+        };
+
+        let gear = resolve(1);
+        assert_eq!(resolve(1), gear);
+        assert_eq!(finder.memo.len(), 1);
+        let _ = resolve(2);
+        assert_eq!(finder.memo.len(), 2);
+        finder.clear_memo();
+        assert_eq!(finder.memo.len(), 0);
+    }
+
+    #[test]
+    fn explanation_matches_resolution() {
+        let resolver = || {
+            GearResolver::new(ITEMS.clone(), GearPurpose::Combat(monster("blue_slime")))
+                .with_skill_levels(HashMap::from([(Skill::Combat, 10)]))
+                .with_excluded_items(vec!["iron_shield".into()])
+        };
+        let explanation = resolver().explain(3);
+
+        assert_eq!(explanation.gear, resolver().resolve());
+        assert_eq!(explanation.loadouts.len(), 3);
+        assert_eq!(
+            explanation.loadouts.first().map(|(_, g)| g),
+            explanation.gear.as_ref()
+        );
+        assert!(explanation.decided_by.is_some());
+        assert_eq!(explanation.stages[0].filter, PoolFilter::Excluded);
+        assert_eq!(explanation.stages[0].removed, ["iron_shield"]);
+        assert!(
+            explanation
+                .stages
+                .iter()
+                .tuple_windows()
+                .all(|(a, b)| a.remaining - b.removed.len() == b.remaining)
+        );
+        assert!(!explanation.candidates.is_empty());
+        assert_eq!(
+            explanation.generated,
+            explanation.kept + explanation.pruned.len()
+        );
+        assert!(explanation.winning <= explanation.kept);
+        assert!(!explanation.pruned.is_empty());
+        assert!(explanation.pruned.iter().all(|(gear, by)| {
+            gear.average_dmg_against(&monster("blue_slime"))
+                <= by.average_dmg_against(&monster("blue_slime"))
+        }));
+    }
+
     #[test]
     fn resolver_best_weapons_against() {
         let gear = GearResolver::new(ITEMS.clone(), GearPurpose::Combat(monster("vampire")))
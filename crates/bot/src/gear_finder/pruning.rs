@@ -0,0 +1,149 @@
+use itertools::Itertools;
+use sdk::{
+    entities::Monster,
+    gear::{Gear, Slot},
+    simulator::HasEffects,
+};
+use strum::IntoEnumIterator;
+
+/// Drops the gears that cannot win against the `monster` over another one of
+/// the list.
+///
+/// Gears are walked by decreasing damage so that each one only needs to be
+/// checked against the gears kept so far: a gear is dominated when a kept one
+/// deals at least as much damage, takes at most as much, and is at least as good
+/// on every other stat the fight or the ranking of the gears depends on.
+pub(super) fn prune_dominated(monster: &Monster, gears: Vec<Gear>) -> Vec<Gear> {
+    explain_pruning(monster, gears).0
+}
+
+/// Prunes the gears like [`prune_dominated`], also returning each dropped gear
+/// along with the gear dominating it.
+pub(super) fn explain_pruning(
+    monster: &Monster,
+    gears: Vec<Gear>,
+) -> (Vec<Gear>, Vec<(Gear, Gear)>) {
+    let profiles = gears.iter().map(|g| Profile::new(g, monster)).collect_vec();
+    let mut kept: Vec<usize> = vec![];
+    let mut pruned: Vec<(usize, usize)> = vec![];
+    for i in (0..gears.len()).sorted_by(|&a, &b| profiles[b].dealt.total_cmp(&profiles[a].dealt)) {
+        if let Some(&j) = kept.iter().find(|&&j| profiles[j].dominates(&profiles[i])) {
+            pruned.push((i, j));
+            continue;
+        }
+        // Only gears dealing the same damage can be dominated by a later one.
+        kept.retain(|&j| {
+            let dominated = profiles[i].dominates(&profiles[j]);
+            if dominated {
+                pruned.push((j, i));
+            }
+            !dominated
+        });
+        kept.push(i);
+    }
+    let pruned = pruned
+        .into_iter()
+        .sorted_unstable()
+        .map(|(i, j)| (gears[i].clone(), gears[j].clone()))
+        .collect_vec();
+    kept.sort_unstable();
+    let mut kept = kept.into_iter().peekable();
+    let kept = gears
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, g)| kept.next_if_eq(&i).map(|_| g))
+        .collect_vec();
+    (kept, pruned)
+}
+
+struct Profile {
+    dealt: f32,
+    taken: f32,
+    /// Stats for which higher is better.
+    stats: [i32; 15],
+}
+
+impl Profile {
+    fn new(gear: &Gear, monster: &Monster) -> Self {
+        Self {
+            dealt: gear.average_dmg_against(monster),
+            taken: monster.average_dmg_against(gear),
+            stats: [
+                gear.health(),
+                gear.haste(),
+                gear.initiative(),
+                gear.critical_strike(),
+                gear.lifesteal(),
+                gear.burn(),
+                gear.poison(),
+                gear.restore(),
+                gear.healing(),
+                gear.reconstitution(),
+                gear.corrupted(),
+                gear.prospecting(),
+                gear.wisdom(),
+                gear.inventory_space(),
+                Slot::iter().filter(|s| gear.item_in(*s).is_some()).count() as i32,
+            ],
+        }
+    }
+
+    fn dominates(&self, other: &Self) -> bool {
+        let at_least = self.dealt >= other.dealt
+            && self.taken <= other.taken
+            && self.stats.iter().zip(other.stats).all(|(a, b)| *a >= b);
+        let better = self.dealt > other.dealt
+            || self.taken < other.taken
+            || self.stats.iter().zip(other.stats).any(|(a, b)| *a > b);
+        at_least && better
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdk::test_utils::{item, monster};
+
+    #[test]
+    fn keeps_only_undominated_gears() {
+        let dagger = Gear::default().with_weapon(item("copper_dagger"));
+        let armored = Gear::default()
+            .with_weapon(item("copper_dagger"))
+            .with_helmet(item("copper_helmet"));
+        let naked = Gear::default();
+
+        let kept = prune_dominated(&monster("chicken"), vec![dagger, armored.clone(), naked]);
+
+        assert_eq!(kept, [armored]);
+    }
+
+    #[test]
+    fn pruned_gears_are_reported_with_their_dominating_gear() {
+        let dagger = Gear::default().with_weapon(item("copper_dagger"));
+        let armored = Gear::default()
+            .with_weapon(item("copper_dagger"))
+            .with_helmet(item("copper_helmet"));
+        let naked = Gear::default();
+
+        let (kept, pruned) = explain_pruning(
+            &monster("chicken"),
+            vec![dagger.clone(), armored.clone(), naked.clone()],
+        );
+
+        assert_eq!(kept, std::slice::from_ref(&armored));
+        assert_eq!(pruned.len(), 2);
+        assert_eq!(pruned[0], (dagger, armored));
+        assert_eq!(pruned[1].0, naked);
+        assert!(pruned.iter().all(|(gear, by)| gear != by));
+    }
+
+    #[test]
+    fn keeps_tradeoffs() {
+        let gears = vec![
+            Gear::default().with_weapon(item("copper_dagger")),
+            Gear::default().with_helmet(item("copper_helmet")),
+        ];
+
+        assert_eq!(prune_dominated(&monster("chicken"), gears.clone()), gears);
+    }
+}
@@ -0,0 +1,338 @@
+use crate::gear_finder::{CanCraftFn, Filter, GearPurpose, GearResolver, GroupRole};
+use itertools::Itertools;
+use sdk::{
+    Code, ItemsClient,
+    entities::Monster,
+    gear::{Gear, Slot},
+    items::ItemSource,
+    simulator::{FightSimulation, Participant},
+    skill::Skill,
+};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+use strum::IntoEnumIterator;
+
+type CostFn = Box<dyn Fn(&str) -> Option<u32>>;
+
+/// Items to obtain on the way to a gear killing a monster.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoadmapStep {
+    pub item: String,
+    pub quantity: u32,
+    /// Where the item is expected to come from, crafting first.
+    pub source: Option<ItemSource>,
+    /// Estimated time in seconds to obtain the items.
+    pub cost: u32,
+    /// Win rate against the monster once this step and the previous ones
+    /// are done.
+    pub win_rate: f64,
+}
+
+impl RoadmapStep {
+    const fn action(&self) -> &'static str {
+        match self.source {
+            Some(ItemSource::Craft) => "craft",
+            Some(ItemSource::Npc(_)) => "buy",
+            Some(ItemSource::Monster(_)) => "farm",
+            Some(ItemSource::Resource(_)) => "gather",
+            Some(ItemSource::Task | ItemSource::TaskReward) | None => "get",
+        }
+    }
+}
+
+impl Display for RoadmapStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} '{}'x{}", self.action(), self.item, self.quantity)
+    }
+}
+
+/// Steps leading from the gear available to a character to a gear killing a
+/// monster, cheapest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GearRoadmap {
+    pub steps: Vec<RoadmapStep>,
+    pub gear: Gear,
+}
+
+impl GearRoadmap {
+    /// Estimated time in seconds to complete every step.
+    #[must_use]
+    pub fn cost(&self) -> u32 {
+        self.steps.iter().map(|s| s.cost).sum()
+    }
+}
+
+impl Display for GearRoadmap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.steps.is_empty() {
+            writeln!(f, "nothing to obtain")?;
+        } else {
+            writeln!(
+                f,
+                "{} (~{}s)",
+                self.steps.iter().join(" then "),
+                self.cost()
+            )?;
+        }
+        for (i, step) in self.steps.iter().enumerate() {
+            writeln!(
+                f,
+                "  {}. {step}: ~{}s, {:.2}% win rate",
+                i + 1,
+                step.cost,
+                step.win_rate * 100.0
+            )?;
+        }
+        write!(f, "{}", self.gear)
+    }
+}
+
+/// Plans the cheapest way for a character to get a gear killing a monster it
+/// cannot beat with the items available to it.
+pub struct RoadmapPlanner {
+    items: ItemsClient,
+    monster: Monster,
+    skill_levels: HashMap<Skill, u32>,
+    available_items: HashMap<String, u32>,
+    excluded_items: Vec<String>,
+    confidence: f64,
+    cost: Option<CostFn>,
+    can_craft: Option<CanCraftFn>,
+}
+
+impl RoadmapPlanner {
+    pub(super) fn new(items: ItemsClient, monster: Monster) -> Self {
+        Self {
+            items,
+            monster,
+            skill_levels: HashMap::new(),
+            available_items: HashMap::new(),
+            excluded_items: vec![],
+            confidence: 1.0,
+            cost: None,
+            can_craft: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_skill_levels(mut self, levels: HashMap<Skill, u32>) -> Self {
+        self.skill_levels = levels;
+        self
+    }
+
+    #[must_use]
+    pub fn with_available_items(mut self, items: HashMap<String, u32>) -> Self {
+        self.available_items = items;
+        self
+    }
+
+    #[must_use]
+    pub fn with_excluded_items(mut self, items: Vec<String>) -> Self {
+        self.excluded_items = items;
+        self
+    }
+
+    /// Win rate the gear at the end of the roadmap must reach, 1 by default.
+    #[must_use]
+    pub const fn with_confidence(mut self, confidence: f64) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Time in seconds to obtain one of an item, `None` if it cannot be
+    /// obtained. Without it every missing item costs one second.
+    #[must_use]
+    pub fn with_cost(mut self, f: impl Fn(&str) -> Option<u32> + 'static) -> Self {
+        self.cost = Some(Box::new(f));
+        self
+    }
+
+    #[must_use]
+    pub fn with_can_craft(mut self, f: impl Fn(&str) -> bool + 'static) -> Self {
+        self.can_craft = Some(Box::new(f));
+        self
+    }
+
+    /// Returns the cheapest roadmap to a gear reaching the confidence against
+    /// the monster, `None` if no gear within reach does.
+    ///
+    /// Gears winning the fight are built from the catalog and the available
+    /// items, then walked from the cheapest to obtain until one reaches the
+    /// confidence. Each of its pieces still to obtain is then swapped back,
+    /// most expensive first, for the one the character would wear today as long
+    /// as the confidence holds. The pieces left make the steps of the roadmap,
+    /// the cheapest first.
+    #[must_use]
+    pub fn plan(mut self) -> Option<GearRoadmap> {
+        let current = self
+            .resolver(Filter::available_only(), None)
+            .best_for_role(&self.monster, GroupRole::Damage)
+            .unwrap_or_default();
+        let can_craft = self.can_craft.take();
+        let (_, gear) = self
+            .resolver(Filter::default(), can_craft)
+            .ranked_combat_gears(&self.monster)
+            .into_iter()
+            .filter_map(|(_, g)| Some((self.cost_of(&g)?, g)))
+            .sorted_by_key(|(cost, _)| *cost)
+            .find(|(_, g)| self.win_rate(g) >= self.confidence)?;
+        let gear = self.trim(gear, &current);
+        let mut steps = self
+            .missing_from(&gear)
+            .into_iter()
+            .filter_map(|(item, quantity)| {
+                Some(RoadmapStep {
+                    cost: self.unit_cost(&item)? * quantity,
+                    source: self.source_of(&item),
+                    item,
+                    quantity,
+                    win_rate: 0.0,
+                })
+            })
+            .sorted_by(|a, b| a.cost.cmp(&b.cost).then_with(|| a.item.cmp(&b.item)))
+            .collect_vec();
+        for i in 0..steps.len() {
+            let obtained = steps[..=i].iter().map(|s| s.item.as_str()).collect_vec();
+            let partial =
+                Slot::iter().fold(gear.clone(), |partial, slot| match gear.item_in(slot) {
+                    Some(item) if !obtained.contains(&item.code()) && !self.owns(item.code()) => {
+                        partial
+                            .with_item_in(slot, current.item_in(slot).cloned())
+                            .or_else(|| partial.with_item_in(slot, None))
+                            .unwrap_or(partial)
+                    }
+                    _ => partial,
+                });
+            steps[i].win_rate = self.win_rate(&partial);
+        }
+        Some(GearRoadmap { steps, gear })
+    }
+
+    fn resolver(&self, filter: Filter, can_craft: Option<CanCraftFn>) -> GearResolver {
+        let mut resolver = GearResolver::new(
+            self.items.clone(),
+            GearPurpose::Combat(self.monster.clone()),
+        )
+        .with_skill_levels(self.skill_levels.clone())
+        .with_available_items(self.available_items.clone())
+        .with_excluded_items(self.excluded_items.clone())
+        .with_filter(filter);
+        resolver.can_craft = can_craft;
+        resolver.item_pool = resolver.create_item_pool();
+        resolver
+    }
+
+    /// Swaps the pieces of `gear` still to obtain for the ones of `current`,
+    /// most expensive first, as long as the confidence holds.
+    fn trim(&self, gear: Gear, current: &Gear) -> Gear {
+        Slot::iter()
+            .filter_map(|slot| {
+                let item = gear.item_in(slot)?;
+                (!self.owns(item.code()) && current.item_in(slot) != Some(item))
+                    .then(|| (slot, self.unit_cost(item.code())))
+            })
+            .sorted_by_key(|(_, cost)| *cost)
+            .rev()
+            .fold(gear, |gear, (slot, _)| {
+                match gear.with_item_in(slot, current.item_in(slot).cloned()) {
+                    Some(cheaper)
+                        if self.cost_of(&cheaper) < self.cost_of(&gear)
+                            && self.win_rate(&cheaper) >= self.confidence =>
+                    {
+                        cheaper
+                    }
+                    _ => gear,
+                }
+            })
+    }
+
+    /// Returns the items of `gear` missing from the available ones along with
+    /// the quantity missing.
+    fn missing_from(&self, gear: &Gear) -> Vec<(String, u32)> {
+        HashMap::<String, u32>::from(gear.clone())
+            .into_iter()
+            .filter_map(|(code, quantity)| {
+                let missing =
+                    quantity.saturating_sub(*self.available_items.get(&code).unwrap_or(&0));
+                (missing > 0).then_some((code, missing))
+            })
+            .sorted()
+            .collect_vec()
+    }
+
+    /// Time in seconds to obtain the items of `gear` missing from the available
+    /// ones, `None` if one of them cannot be obtained.
+    fn cost_of(&self, gear: &Gear) -> Option<u32> {
+        self.missing_from(gear)
+            .iter()
+            .map(|(code, quantity)| Some(self.unit_cost(code)? * quantity))
+            .sum()
+    }
+
+    fn unit_cost(&self, code: &str) -> Option<u32> {
+        self.cost.as_ref().map_or(Some(1), |cost| cost(code))
+    }
+
+    fn source_of(&self, code: &str) -> Option<ItemSource> {
+        let sources = self.items.sources_of(code);
+        sources
+            .iter()
+            .find(|s| s.is_craft())
+            .or_else(|| sources.first())
+            .cloned()
+    }
+
+    fn owns(&self, code: &str) -> bool {
+        self.available_items.get(code).is_some_and(|q| *q > 0)
+    }
+
+    fn win_rate(&self, gear: &Gear) -> f64 {
+        FightSimulation::new(
+            Participant::new("char1".into())
+                .with_level(*self.skill_levels.get(&Skill::Combat).unwrap_or(&1))
+                .with_gear(gear.clone()),
+            self.monster.clone(),
+        )
+        .odds()
+        .win_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gear_finder::GearFinder;
+    use sdk::test_utils::{ITEMS, monster};
+
+    fn planner(monster_code: &str, level: u32) -> RoadmapPlanner {
+        GearFinder::new(ITEMS.clone())
+            .roadmap_to(monster(monster_code))
+            .with_skill_levels(HashMap::from([(Skill::Combat, level)]))
+            .with_confidence(0.95)
+    }
+
+    #[test]
+    fn roadmap_reaches_confidence() {
+        let roadmap = planner("cow", 8)
+            .with_cost(|code| Some(code.len() as u32))
+            .plan()
+            .unwrap();
+
+        assert!(!roadmap.steps.is_empty());
+        assert!(roadmap.steps.last().unwrap().win_rate >= 0.95);
+        assert!(roadmap.steps.is_sorted_by_key(|s| s.cost));
+    }
+
+    #[test]
+    fn roadmap_keeps_owned_items() {
+        let roadmap = planner("yellow_slime", 5)
+            .with_available_items(HashMap::from([("copper_dagger".to_owned(), 1)]))
+            .plan()
+            .unwrap();
+
+        assert!(roadmap.steps.iter().all(|s| s.item != "copper_dagger"));
+        assert!(roadmap.cost() < planner("yellow_slime", 5).plan().unwrap().cost());
+    }
+}
@@ -0,0 +1,193 @@
+use crate::gear_finder::GroupLoadout;
+use sdk::{
+    entities::{CharacterName, Monster},
+    models::MapLayer,
+};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+/// How long the characters of a group fight wait for each other.
+pub const GROUP_FIGHT_TIMEOUT: Duration = Duration::from_mins(5);
+
+/// Rendezvous between the leader of a boss fight and its participants.
+///
+/// The leader creates it with the loadout of every member and the map the fight
+/// takes place on, then sends it to the participants. Each participant gets
+/// ready on the map and waits there until the leader ends the fight.
+#[derive(Clone)]
+pub struct GroupFight(Arc<GroupFightInner>);
+
+struct GroupFightInner {
+    monster: Monster,
+    position: (MapLayer, i32, i32),
+    /// Behind a lock since gears are not `Sync`.
+    loadouts: Mutex<Vec<GroupLoadout>>,
+    state: Mutex<GroupFightState>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct GroupFightState {
+    ready: HashSet<CharacterName>,
+    abandoned: bool,
+    over: bool,
+}
+
+impl GroupFight {
+    /// The first loadout is the one of the leader.
+    #[must_use]
+    pub fn new(
+        monster: Monster,
+        position: (MapLayer, i32, i32),
+        loadouts: Vec<GroupLoadout>,
+    ) -> Self {
+        Self(Arc::new(GroupFightInner {
+            monster,
+            position,
+            loadouts: Mutex::new(loadouts),
+            state: Mutex::default(),
+            changed: Condvar::new(),
+        }))
+    }
+
+    #[must_use]
+    pub fn monster(&self) -> &Monster {
+        &self.0.monster
+    }
+
+    #[must_use]
+    pub fn position(&self) -> (MapLayer, i32, i32) {
+        self.0.position
+    }
+
+    #[must_use]
+    pub fn loadout_of(&self, name: &CharacterName) -> Option<GroupLoadout> {
+        self.0
+            .loadouts
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|l| &l.name == name)
+            .cloned()
+    }
+
+    /// Names of the members joining the leader.
+    #[must_use]
+    pub fn participants(&self) -> Vec<CharacterName> {
+        self.0
+            .loadouts
+            .lock()
+            .unwrap()
+            .iter()
+            .skip(1)
+            .map(|l| l.name.clone())
+            .collect()
+    }
+
+    /// Marks the participant `name` as ready to fight.
+    pub fn ready(&self, name: &CharacterName) {
+        self.update(|state| {
+            state.ready.insert(name.clone());
+        });
+    }
+
+    /// Marks the fight as abandoned by a participant that could not get ready.
+    pub fn abandon(&self) {
+        self.update(|state| state.abandoned = true);
+    }
+
+    /// Marks the fight as over, releasing the participants.
+    pub fn end(&self) {
+        self.update(|state| state.over = true);
+    }
+
+    /// Waits until every participant is ready. Returns `false` if one of
+    /// them abandoned or if `timeout` elapsed first.
+    #[must_use]
+    pub fn wait_for_participants(&self, timeout: Duration) -> bool {
+        let participants = self.participants();
+        self.wait_until(timeout, |state| {
+            state.abandoned || participants.iter().all(|p| state.ready.contains(p))
+        })
+        .is_some_and(|state| !state.abandoned)
+    }
+
+    /// Waits until the leader ends the fight or `timeout` elapsed.
+    pub fn wait_for_end(&self, timeout: Duration) {
+        let _ = self.wait_until(timeout, |state| state.over);
+    }
+
+    fn update(&self, f: impl FnOnce(&mut GroupFightState)) {
+        f(&mut self.0.state.lock().unwrap());
+        self.0.changed.notify_all();
+    }
+
+    fn wait_until(
+        &self,
+        timeout: Duration,
+        condition: impl Fn(&GroupFightState) -> bool,
+    ) -> Option<MutexGuard<'_, GroupFightState>> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.0.state.lock().unwrap();
+        while !condition(&state) {
+            let remaining = deadline.checked_duration_since(Instant::now())?;
+            state = self.0.changed.wait_timeout(state, remaining).unwrap().0;
+        }
+        Some(state)
+    }
+}
+
+impl PartialEq for GroupFight {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gear_finder::GroupRole;
+    use sdk::{gear::Gear, test_utils::monster};
+    use std::thread;
+
+    fn fight() -> GroupFight {
+        let loadout = |name: &str| GroupLoadout {
+            name: name.into(),
+            role: GroupRole::Damage,
+            gear: Gear::default(),
+        };
+        GroupFight::new(
+            monster("king_slime"),
+            (MapLayer::Overworld, 0, 0),
+            vec![loadout("leader"), loadout("char2"), loadout("char3")],
+        )
+    }
+
+    #[test]
+    fn leader_waits_for_every_participant() {
+        let fight = fight();
+        let participant = fight.clone();
+        let handle = thread::spawn(move || {
+            participant.ready(&"char2".into());
+            participant.ready(&"char3".into());
+            participant.wait_for_end(GROUP_FIGHT_TIMEOUT);
+        });
+
+        assert!(fight.wait_for_participants(GROUP_FIGHT_TIMEOUT));
+        fight.end();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn leader_gives_up_when_a_participant_is_missing() {
+        let fight = fight();
+        fight.ready(&"char2".into());
+
+        assert!(!fight.wait_for_participants(Duration::from_millis(10)));
+        fight.abandon();
+        assert!(!fight.wait_for_participants(GROUP_FIGHT_TIMEOUT));
+    }
+}
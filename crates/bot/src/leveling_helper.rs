@@ -1,13 +1,116 @@
-use crate::{account::AccountController, bank::BankController, character::CharacterController};
+use crate::{
+    account::AccountController, bank::BankController, character::CharacterController,
+    gear_finder::GearPurpose,
+};
 use itertools::Itertools;
+use ordered_float::OrderedFloat;
 use rayon::iter::{ParallelBridge, ParallelIterator};
 use sdk::{
     CanProvideXp, Code, CollectionClient, ItemsClient, Level, MapsClient, MonstersClient,
     ResourcesClient,
+    consts::CRAFT_TIME,
     entities::{Character, Item, Monster, Resource},
-    items::SubType,
+    level_xp_rank,
+    simulator::HasEffects,
     skill::Skill,
+    wisdom_multiplier,
 };
+use std::fmt::{self, Display, Formatter};
+use strum_macros::EnumIs;
+
+/// Way of earning XP in a skill.
+#[derive(Debug, Clone, PartialEq, EnumIs)]
+pub enum LevelingAction {
+    /// Crafts a batch of `quantity` items, as many as the inventory holds the
+    /// materials for.
+    Craft {
+        item: Item,
+        quantity: u32,
+    },
+    Gather(Resource),
+    Fight(Monster),
+}
+
+impl Display for LevelingAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Craft { item, quantity } => write!(f, "craft '{}'x{quantity}", item.code()),
+            Self::Gather(resource) => write!(f, "gather '{}'", resource.code()),
+            Self::Fight(monster) => write!(f, "fight '{}'", monster.code()),
+        }
+    }
+}
+
+/// A `LevelingAction` along with how it ranks in XP and the time it takes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelingOption {
+    pub action: LevelingAction,
+    /// Rank of the XP earned once the action is performed, from the level
+    /// difference only (see [`sdk::level_xp_rank`]) times the number of
+    /// entities and the wisdom multiplier. It is not an amount of XP and only
+    /// ranks the options against each other.
+    pub score: f64,
+    /// Estimated time in seconds the action takes, from getting the materials
+    /// to going back to the bank.
+    pub time: u32,
+}
+
+impl LevelingOption {
+    #[must_use]
+    pub fn score_per_sec(&self) -> f64 {
+        self.score / f64::from(self.time.max(1))
+    }
+
+    /// Scores crafting `quantity` of `item` at `level`, the `acquisition` of
+    /// the missing materials and the `trip` to the workshop and back included.
+    fn craft(
+        item: Item,
+        quantity: u32,
+        level: u32,
+        wisdom: i32,
+        acquisition: u32,
+        trip: u32,
+    ) -> Self {
+        Self {
+            score: level_xp_rank(level, item.level())
+                * f64::from(quantity)
+                * wisdom_multiplier(wisdom),
+            time: acquisition + trip + CRAFT_TIME * quantity,
+            action: LevelingAction::Craft { item, quantity },
+        }
+    }
+
+    /// Scores gathering `resource` at `level` once every `time` seconds.
+    fn gather(resource: Resource, level: u32, wisdom: i32, time: u32) -> Self {
+        Self {
+            score: level_xp_rank(level, resource.level()) * wisdom_multiplier(wisdom),
+            time,
+            action: LevelingAction::Gather(resource),
+        }
+    }
+
+    /// Scores killing `monster` at `level` once every `time` seconds.
+    fn fight(monster: Monster, level: u32, wisdom: i32, time: u32) -> Self {
+        Self {
+            score: level_xp_rank(level, monster.level()) * wisdom_multiplier(wisdom),
+            time,
+            action: LevelingAction::Fight(monster),
+        }
+    }
+}
+
+impl Display for LevelingOption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: score {:.2} in {}s ({:.4}/s)",
+            self.action,
+            self.score,
+            self.time,
+            self.score_per_sec()
+        )
+    }
+}
 
 #[derive(Clone)]
 pub struct LevelingHelper {
@@ -61,98 +164,103 @@ impl LevelingHelper {
             .max_set_by_key(Level::level)
     }
 
+    /// Returns every way for the `char` to earn XP in `skill`, the best
+    /// [`LevelingOption::score`] per second first.
+    #[must_use]
+    pub fn leveling_options(
+        &self,
+        skill: Skill,
+        char: &CharacterController,
+    ) -> Vec<LevelingOption> {
+        let level = char.skill_level(skill);
+        let mut options = self.best_crafts(level, skill, char);
+        if skill.is_combat() {
+            options.extend(self.fight_options(char));
+        } else if skill.is_gathering() {
+            options.extend(self.gather_options(level, skill, char));
+        }
+        sort_by_score_rate(options)
+    }
+
+    /// Returns the craft leveling `skill` with the best score per second for
+    /// the `char`.
     #[must_use]
     pub fn best_craft(&self, level: u32, skill: Skill, char: &CharacterController) -> Option<Item> {
-        self.best_crafts_hardcoded(level, skill)
+        self.best_crafts(level, skill, char)
             .into_iter()
-            .filter_map(|i| {
-                let mats = self
-                    .items
-                    .mats_for(i.code(), char.max_craftable_items(i.code()));
-                let mats_with_ttg = self
-                    .bank
-                    .missing_among(&mats, &char.name())
-                    .into_iter()
-                    .par_bridge()
-                    .map(|m| (m.clone(), self.account.time_to_get(&m.code)))
-                    .collect::<Vec<_>>();
-                mats_with_ttg.iter().all(|(_, ttg)| ttg.is_some()).then(|| {
-                    (
-                        i,
-                        mats_with_ttg
-                            .iter()
-                            .filter_map(|(m, ttg)| ttg.as_ref().map(|ttg| ttg * m.quantity))
-                            .sum::<u32>(),
-                    )
-                })
+            .find_map(|o| match o.action {
+                LevelingAction::Craft { item, .. } => Some(item),
+                _ => None,
             })
-            .min_by_key(|(_, ttg)| *ttg)
-            .map(|(i, _)| i)
     }
 
-    /// Returns the best items to level the given `skill` at the given `level`.
+    /// Scores the crafts providing XP in `skill` at `level` for the `char`, the
+    /// best score per second first.
+    ///
+    /// A craft is done by batch: the missing materials for as many items as the
+    /// inventory holds are obtained, brought to the workshop, crafted, then
+    /// the crafted items are brought back to the bank. Crafts whose materials
+    /// cannot be obtained are left out.
     #[must_use]
-    pub fn best_crafts_hardcoded(&self, level: u32, skill: Skill) -> Vec<Item> {
-        match skill {
-            Skill::Gearcrafting => {
-                if level >= 5 {
-                    return self.best_crafts(level, skill);
-                }
-                vec![self.items.get("wooden_shield")]
-            }
-            Skill::Weaponcrafting | Skill::Mining | Skill::Woodcutting | Skill::Alchemy => {
-                return self.best_crafts(level, skill);
-            }
-            Skill::Jewelrycrafting => {
-                if level >= 40 {
-                    return self.best_crafts(level, skill);
-                } else if level >= 30 {
-                    vec![self.items.get("gold_ring")]
-                } else if level >= 20 {
-                    vec![self.items.get("steel_ring")]
-                } else if level >= 15 {
-                    vec![self.items.get("life_ring")]
-                } else if level >= 10 {
-                    vec![self.items.get("iron_ring")]
-                } else {
-                    vec![self.items.get("copper_ring")]
-                }
-            }
-            Skill::Cooking => {
-                if level >= 40 {
-                    return self.best_crafts(level, skill);
-                } else if level >= 30 {
-                    vec![self.items.get("cooked_bass")]
-                } else if level >= 20 {
-                    vec![self.items.get("cooked_trout")]
-                } else if level >= 10 {
-                    vec![self.items.get("cooked_shrimp")]
-                } else {
-                    vec![self.items.get("cooked_gudgeon")]
+    pub fn best_crafts(
+        &self,
+        level: u32,
+        skill: Skill,
+        char: &CharacterController,
+    ) -> Vec<LevelingOption> {
+        let trip = char.round_trip_time(&self.maps.with_content_code(skill.as_ref()));
+        let options = self
+            .crafts_providing_exp(level, skill)
+            .par_bridge()
+            .filter_map(|item| {
+                let quantity = char.max_craftable_items(item.code());
+                if quantity == 0 {
+                    return None;
                 }
-            }
-            Skill::Fishing | Skill::Combat => vec![None],
-        }
-        .into_iter()
-        .flatten()
-        .collect_vec()
+                let mats = self.items.mats_for(item.code(), quantity);
+                let acquisition = self
+                    .bank
+                    .missing_among(&mats, &char.name())
+                    .iter()
+                    .map(|m| Some(self.account.time_to_get(&m.code)? * m.quantity))
+                    .sum::<Option<u32>>()?;
+                let wisdom = char
+                    .available_gear_for(GearPurpose::Crafting(item.clone()))
+                    .map_or(0, |g| g.wisdom());
+                Some(LevelingOption::craft(
+                    item,
+                    quantity,
+                    level,
+                    wisdom,
+                    acquisition,
+                    trip?,
+                ))
+            })
+            .collect::<Vec<_>>();
+        sort_by_score_rate(options)
     }
 
-    pub fn best_crafts(&self, level: u32, skill: Skill) -> Vec<Item> {
-        self.crafts_providing_exp(level, skill)
-            .filter(|i| {
-                !(["wooden_staff"].contains(&i.code())
-                    || i.subtype_is(SubType::PreciousStone)
-                    || self.items.require_task_reward(i.code())
-                    || i.is_crafted_with("obsidian")
-                    || i.is_crafted_with("diamond")
-                    || i.is_crafted_with("strange_ore")
-                    || i.is_crafted_with("magic_wood"))
+    /// Returns the monster with the best combat score per second for the
+    /// `char`.
+    #[must_use]
+    pub fn best_monster(&self, char: &CharacterController) -> Option<Monster> {
+        self.fight_options(char)
+            .into_iter()
+            .max_by_key(|o| OrderedFloat(o.score_per_sec()))
+            .and_then(|o| match o.action {
+                LevelingAction::Fight(monster) => Some(monster),
+                _ => None,
             })
-            .max_set_by_key(Level::level)
     }
 
-    pub fn best_resource(&self, level: u32, skill: Skill) -> Option<Resource> {
+    /// Scores the resources providing XP in `skill` at `level`, gathering
+    /// cooldown and share of the bank round trip included.
+    fn gather_options(
+        &self,
+        level: u32,
+        skill: Skill,
+        char: &CharacterController,
+    ) -> Vec<LevelingOption> {
         self.resources
             .iter()
             .filter(|r| {
@@ -160,17 +268,81 @@ impl LevelingHelper {
                     && r.provides_xp_at(level)
                     && !self.maps.with_content_code(r.code()).is_empty()
             })
-            .max_by_key(Level::level)
+            .filter_map(|resource| {
+                let time = char.time_to_gather(&resource)?;
+                let wisdom = char
+                    .available_gear_for(GearPurpose::Gathering(resource.clone()))
+                    .map_or(0, |g| g.wisdom());
+                Some(LevelingOption::gather(resource, level, wisdom, time))
+            })
+            .collect_vec()
     }
 
-    pub fn best_monster(&self, char: &CharacterController) -> Option<Monster> {
+    /// Scores the monsters the `char` can kill, fight and rest cooldowns and
+    /// share of the bank round trip included. Monsters without a map or
+    /// beyond the kill confidence of the `char` are left out by
+    /// [`CharacterController::time_to_kill`].
+    fn fight_options(&self, char: &CharacterController) -> Vec<LevelingOption> {
         self.monsters
             .iter()
-            .filter(|m| {
-                m.level() <= char.level()
-                    && !["imp", "death_knight"].contains(&m.code())
-                    && char.can_kill(m).is_ok()
+            .filter(|m| m.level() <= char.level() && m.provides_xp_at(char.level()))
+            .filter_map(|monster| {
+                let time = char.time_to_kill(&monster)?;
+                let wisdom = char.can_kill(&monster).map_or(0, |g| g.wisdom());
+                Some(LevelingOption::fight(monster, char.level(), wisdom, time))
             })
-            .max_by_key(Level::level)
+            .collect_vec()
+    }
+}
+
+fn sort_by_score_rate(options: Vec<LevelingOption>) -> Vec<LevelingOption> {
+    options
+        .into_iter()
+        .sorted_by_key(|o| OrderedFloat(-o.score_per_sec()))
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdk::test_utils::{item, monster, resource};
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "expected {b}, got {a}");
+    }
+
+    #[test]
+    fn craft_option_adds_up_the_batch() {
+        let dagger = item("copper_dagger");
+        let option = LevelingOption::craft(dagger.clone(), 4, dagger.level(), 100, 30, 20);
+
+        assert_eq!(option.time, 30 + 20 + CRAFT_TIME * 4);
+        assert_close(option.score, 4.0 * 1.1);
+    }
+
+    #[test]
+    fn gather_option_fades_with_the_level_difference() {
+        let rocks = resource("copper_rocks");
+        let same = LevelingOption::gather(rocks.clone(), rocks.level(), 0, 30);
+        let outleveled = LevelingOption::gather(rocks.clone(), rocks.level() + 5, 0, 30);
+
+        assert_eq!(same.time, 30);
+        assert_close(same.score, 1.0);
+        assert!(outleveled.score < same.score);
+        assert_close(
+            LevelingOption::gather(rocks.clone(), rocks.level(), 50, 30).score,
+            1.05,
+        );
+    }
+
+    #[test]
+    fn fight_option_is_ranked_by_score_per_second() {
+        let chicken = monster("chicken");
+        let level = chicken.level();
+        let slow = LevelingOption::fight(chicken.clone(), level, 0, 60);
+        let fast = LevelingOption::fight(chicken, level, 0, 20);
+
+        assert_close(fast.score_per_sec(), 1.0 / 20.0);
+        assert_eq!(sort_by_score_rate(vec![slow, fast.clone()])[0], fast);
     }
 }
@@ -1,16 +1,17 @@
 use crate::{
     account::AccountController, bank::BankController, bot_config::BotConfig,
-    gear_finder::GearFinder, leveling_helper::LevelingHelper, orchestrator::Orchestrator,
-    orderboard::OrderBoard,
+    gear_finder::GearFinder, group_fight::GroupFight, leveling_helper::LevelingHelper,
+    orchestrator::Orchestrator, orderboard::OrderBoard,
 };
 use chrono::{DateTime, Utc};
 use log::error;
 use sdk::{
-    Client,
+    Client, EventFilter, SdkEventKind,
     consts::{
         APPLE, APPLE_PIE, CARROT, COOKED_HELLHOUND_MEAT, FISH_SOUP, MAPLE_SYRUP, MUSHROOM_SOUP,
     },
-    entities::{Character, Monster, Resource},
+    entities::{Character, CharacterName, Monster, Resource},
+    models::MapLayer,
 };
 use std::{
     collections::VecDeque,
@@ -25,6 +26,7 @@ pub mod bot_config;
 pub mod character;
 pub mod error;
 pub mod gear_finder;
+pub mod group_fight;
 pub mod inventory;
 pub mod leveling_helper;
 pub mod orchestrator;
@@ -51,6 +53,9 @@ pub const FOOD_CONSUMPTION_BLACKLIST: [&str; 2] = [APPLE, CARROT];
 pub const MIN_COIN_THRESHOLD: u32 = 4;
 pub const MIN_FOOD_THRESHOLD: u32 = 6000;
 
+/// Events buffered for the orchestrator while it handles a deposit.
+const ORCHESTRATOR_EVENTS_CAPACITY: usize = 1024;
+
 pub struct Bot {
     pub config: BotConfig,
     pub client: Client,
@@ -62,9 +67,14 @@ pub struct Bot {
 }
 
 impl Bot {
+    /// Builds the bot with the config read from the config file.
     #[must_use]
     pub fn new(client: Client) -> Self {
-        let config = BotConfig::from_file();
+        Self::with_config(client, BotConfig::from_file())
+    }
+
+    #[must_use]
+    pub fn with_config(client: Client, config: BotConfig) -> Self {
         let bank = BankController::new(client.account.bank(), client.items.clone());
         let account = AccountController::new(
             config.clone(),
@@ -73,13 +83,12 @@ impl Bot {
             client.npcs.clone(),
             bank.clone(),
         );
-        let event_rx = client.event_bus().subscribe();
-        let mut orchestrator = Orchestrator::new(
-            event_rx,
-            account.clone(),
-            bank.clone(),
-            client.items.clone(),
+        let events = client.event_bus().subscribe_with(
+            EventFilter::all().kind(SdkEventKind::LeveledUp),
+            ORCHESTRATOR_EVENTS_CAPACITY,
         );
+        let mut orchestrator =
+            Orchestrator::new(events, account.clone(), bank.clone(), client.items.clone());
         // Spawn orchestrator thread immediately so it's ready before events fire
         if let Err(e) = Builder::new()
             .name("orchestrator".into())
@@ -105,7 +114,9 @@ impl Bot {
         }
     }
 
-    pub fn run(&self) {
+    /// Creates the character controllers without starting their loops, e.g.
+    /// to inspect the characters of an offline client.
+    pub fn init_characters(&self) {
         self.account.init_characters(
             &self.client,
             &self.account,
@@ -113,6 +124,10 @@ impl Bot {
             &self.gear_finder,
             &self.leveling_helper,
         );
+    }
+
+    pub fn run(&self) {
+        self.init_characters();
         for char in self.account.characters() {
             sleep(Duration::from_millis(250));
             if let Err(e) = Builder::new().name(char.name().to_string()).spawn(move || {
@@ -126,17 +141,60 @@ impl Bot {
 
 #[derive(Clone, PartialEq)]
 pub enum CharacterCommand {
-    Craft { item: String, quantity: u32 },
-    Kill { monster: Monster },
-    Gather { resource: Resource },
-    Recycle { item: String, quantity: u32 },
-    Delete { item: String, quantity: u32 },
-    BuyItem { item: String, quantity: u32 },
-    SellItem { item: String, quantity: u32 },
-    DepositItem { item: String, quantity: u32 },
-    WithdrawItem { item: String, quantity: u32 },
-    DepositGold { quantity: u32 },
-    WithdrawGold { quantity: u32 },
+    Move {
+        layer: MapLayer,
+        x: i32,
+        y: i32,
+    },
+    Craft {
+        item: String,
+        quantity: u32,
+    },
+    Kill {
+        monster: Monster,
+    },
+    /// Kills the given boss along with the given participants, the receiver
+    /// leading the fight.
+    KillBoss {
+        monster: Monster,
+        participants: Vec<CharacterName>,
+    },
+    JoinBossFight {
+        fight: GroupFight,
+    },
+    Gather {
+        resource: Resource,
+    },
+    Recycle {
+        item: String,
+        quantity: u32,
+    },
+    Delete {
+        item: String,
+        quantity: u32,
+    },
+    BuyItem {
+        item: String,
+        quantity: u32,
+    },
+    SellItem {
+        item: String,
+        quantity: u32,
+    },
+    DepositItem {
+        item: String,
+        quantity: u32,
+    },
+    WithdrawItem {
+        item: String,
+        quantity: u32,
+    },
+    DepositGold {
+        quantity: u32,
+    },
+    WithdrawGold {
+        quantity: u32,
+    },
 }
 
 #[derive(Clone)]
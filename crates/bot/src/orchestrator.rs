@@ -5,14 +5,14 @@ use crate::{
 use itertools::Itertools;
 use log::{debug, info, warn};
 use sdk::{
-    CanProvideXp, Code, ItemsClient, Level, SdkEvent, Skill,
+    BusMessage, CanProvideXp, Code, ItemsClient, Level, SdkEvent, Skill, Subscription,
     entities::{Character, Item},
     models::SimpleItemSchema,
 };
 use std::cmp::{Reverse, min};
 
 pub struct Orchestrator {
-    event_rx: bus::BusReader<SdkEvent>,
+    events: Subscription,
     account: AccountController,
     bank: BankController,
     items: ItemsClient,
@@ -21,13 +21,13 @@ pub struct Orchestrator {
 impl Orchestrator {
     #[must_use]
     pub const fn new(
-        event_rx: bus::BusReader<SdkEvent>,
+        events: Subscription,
         account: AccountController,
         bank: BankController,
         items: ItemsClient,
     ) -> Self {
         Self {
-            event_rx,
+            events,
             account,
             bank,
             items,
@@ -36,19 +36,29 @@ impl Orchestrator {
 
     pub fn run(&mut self) {
         info!("orchestrator started");
-        while let Ok(event) = self.event_rx.recv() {
-            match event {
-                SdkEvent::ItemDeposited { character, items } => {
-                    // self.handle_item_deposited(&character, &items);
+        while let Ok(message) = self.events.recv() {
+            let event = match message {
+                BusMessage::Event(event) => event,
+                BusMessage::Missed(n) => {
+                    warn!("orchestrator: missed {n} events");
+                    continue;
                 }
-                SdkEvent::ItemWithdrawn { .. }
-                | SdkEvent::GoldDeposited { .. }
-                | SdkEvent::GoldWithdrawn { .. } => {}
+            };
+            if let SdkEvent::LeveledUp {
+                character,
+                skill,
+                level,
+            } = event
+            {
+                info!("orchestrator: {character} reached {skill} level {level}");
             }
         }
         info!("orchestrator stopped");
     }
+}
 
+#[expect(dead_code, reason = "cooking deposited ingredients is disabled")]
+impl Orchestrator {
     fn handle_item_deposited(&self, character: &str, deposited_items: &[SimpleItemSchema]) {
         debug!("orchestrator: processing deposit from {character}");
         for deposited in deposited_items {
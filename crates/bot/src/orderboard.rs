@@ -1,9 +1,9 @@
-use crate::{account::AccountController, reservable::Reservable};
+use crate::{account::AccountController, gear_finder::GearRoadmap, reservable::Reservable};
 use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use log::{debug, error, info};
 use sdk::{
-    Code, CollectionClient, ItemsClient, Quantity, entities::CharacterName,
+    Code, CollectionClient, CraftPlan, ItemsClient, Quantity, entities::CharacterName,
     models::SimpleItemSchema, skill::Skill,
 };
 use std::{
@@ -115,6 +115,56 @@ impl OrderBoard {
         }
     }
 
+    /// Orders the items left to obtain to complete `plan`, except the planned
+    /// item itself.
+    pub fn add_plan(
+        &self,
+        plan: &CraftPlan,
+        owner: Option<&CharacterName>,
+        purpose: &Purpose,
+    ) -> Result<(), OrderError> {
+        let missing = plan
+            .missing()
+            .into_iter()
+            .filter(|m| m.code != plan.root.item)
+            .collect_vec();
+        self.add_multiple(&missing, owner, purpose)
+    }
+
+    /// Orders the items of each step of `roadmap` for `char` to kill the
+    /// `monster`, the first step being added last so that it is progressed
+    /// first.
+    pub fn add_roadmap(
+        &self,
+        roadmap: &GearRoadmap,
+        char: &CharacterName,
+        monster: &str,
+    ) -> Result<(), OrderError> {
+        let mut ordered = false;
+        for step in roadmap.steps.iter().rev() {
+            let purpose = Purpose::Roadmap {
+                char: char.clone(),
+                monster: monster.to_owned(),
+            };
+            if self.add(&step.item, step.quantity, None, purpose).is_ok() {
+                ordered = true;
+            }
+        }
+        if ordered {
+            Ok(())
+        } else {
+            Err(OrderError::AlreadyExists)
+        }
+    }
+
+    /// Returns the orders of the roadmap of `char` toward the `monster`.
+    #[must_use]
+    pub fn roadmap_orders(&self, char: &CharacterName, monster: &str) -> Vec<Arc<Order>> {
+        self.orders_filtered(|o| {
+            matches!(&o.purpose, Purpose::Roadmap { char: c, monster: m } if c == char && m == monster)
+        })
+    }
+
     pub fn add(
         &self,
         item: &str,
@@ -314,11 +364,26 @@ pub enum OrderError {
 
 #[derive(Debug, PartialEq, Eq, Clone, EnumIs, EnumIter)]
 pub enum Purpose {
-    Food { char: CharacterName },
+    Food {
+        char: CharacterName,
+    },
     Cli,
-    Gear { char: CharacterName, item: String },
-    Task { char: CharacterName },
-    Leveling { char: CharacterName, skill: Skill },
+    Gear {
+        char: CharacterName,
+        item: String,
+    },
+    /// Gear planned for `char` to kill the `monster` of its task.
+    Roadmap {
+        char: CharacterName,
+        monster: String,
+    },
+    Task {
+        char: CharacterName,
+    },
+    Leveling {
+        char: CharacterName,
+        skill: Skill,
+    },
 }
 
 impl Display for Purpose {
@@ -331,6 +396,7 @@ impl Display for Purpose {
                 Self::Leveling { char, skill } => format!("{skill} ({char})"),
                 Self::Food { char } => format!("food ({char})"),
                 Self::Gear { char, item } => format!("'{item}': ({char})"),
+                Self::Roadmap { char, monster } => format!("roadmap to '{monster}' ({char})"),
                 Self::Task { char } => format!("task ({char})"),
             }
         )
@@ -0,0 +1,81 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use thiserror::Error;
+
+/// Game errors, answered with the same status codes and body as the
+/// `ArtifactsMMO` server.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GameError {
+    #[error("{0} not found")]
+    NotFound(&'static str),
+    #[error("bank has not enough gold")]
+    BankGoldInsufficient,
+    #[error("character has not enough gold")]
+    CharacterGoldInsufficient,
+    #[error("wrong task")]
+    WrongTask,
+    #[error("task already completed or too many items traded")]
+    TaskAlreadyCompleted,
+    #[error("missing item or insufficient quantity")]
+    MissingItem,
+    #[error("no task in progress")]
+    NoTask,
+    #[error("task not completed")]
+    TaskNotCompleted,
+    #[error("task already in progress")]
+    TaskAlreadyInProgress,
+    #[error("character already on map")]
+    AlreadyOnMap,
+    #[error("invalid slot state")]
+    InvalidSlotState,
+    #[error("skill level insufficient")]
+    SkillLevelInsufficient,
+    #[error("inventory full")]
+    InventoryFull,
+    #[error("character not found")]
+    CharacterNotFound,
+    #[error("character in cooldown")]
+    Cooldown,
+    #[error("{0} not found on this map")]
+    NotOnMap(&'static str),
+    #[error("action not supported by the mock server")]
+    Unsupported,
+}
+
+impl GameError {
+    #[must_use]
+    pub const fn code(&self) -> u16 {
+        match self {
+            Self::NotFound(_) => 404,
+            Self::BankGoldInsufficient => 460,
+            Self::WrongTask => 474,
+            Self::TaskAlreadyCompleted => 475,
+            Self::MissingItem => 478,
+            Self::NoTask => 487,
+            Self::TaskNotCompleted => 488,
+            Self::TaskAlreadyInProgress => 489,
+            Self::AlreadyOnMap => 490,
+            Self::InvalidSlotState => 491,
+            Self::CharacterGoldInsufficient => 492,
+            Self::SkillLevelInsufficient => 493,
+            Self::InventoryFull => 497,
+            Self::CharacterNotFound => 498,
+            Self::Cooldown => 499,
+            Self::NotOnMap(_) => 598,
+            Self::Unsupported => 501,
+        }
+    }
+}
+
+impl IntoResponse for GameError {
+    fn into_response(self) -> Response {
+        let code = self.code();
+        let body = json!({ "error": { "code": code, "message": self.to_string() } });
+        let status = StatusCode::from_u16(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        (status, Json(body)).into_response()
+    }
+}
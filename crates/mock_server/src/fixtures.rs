@@ -0,0 +1,118 @@
+use openapi::models::{
+    DropRateSchema, EventSchema, ItemSchema, MapLayer, MapSchema, MonsterSchema, NpcItemSchema,
+    NpcSchema, ResourceSchema, TaskFullSchema,
+};
+use serde::{Deserialize, de::DeserializeOwned};
+use std::{collections::BTreeMap, fs, path::Path};
+use thiserror::Error;
+
+/// Game catalogs served by the mock server, loaded from the RON files written
+/// by the SDK cache (e.g. `crates/sdk/tests/fixtures`).
+#[derive(Debug, Default, Clone)]
+pub struct Fixtures {
+    pub items: Vec<ItemSchema>,
+    pub monsters: Vec<MonsterSchema>,
+    pub resources: Vec<ResourceSchema>,
+    pub maps: Vec<MapSchema>,
+    pub npcs: Vec<NpcSchema>,
+    pub npcs_items: Vec<NpcItemSchema>,
+    pub tasks: Vec<TaskFullSchema>,
+    pub tasks_rewards: Vec<DropRateSchema>,
+    pub events: Vec<EventSchema>,
+}
+
+#[derive(Debug, Error)]
+pub enum FixtureError {
+    #[error("failed to read `{file}`: {source}")]
+    Io {
+        file: String,
+        source: std::io::Error,
+    },
+    #[error("failed to parse `{file}`: {source}")]
+    Parse {
+        file: String,
+        source: Box<ron::error::SpannedError>,
+    },
+}
+
+/// Files written by the SDK cache wrap the data with metadata, fixtures are
+/// stored bare.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    data: T,
+}
+
+/// Entities are stored by the SDK as newtypes around the API schemas.
+#[derive(Deserialize)]
+struct Entity<T>(T);
+
+impl Fixtures {
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self, FixtureError> {
+        let dir = dir.as_ref();
+        let maps: BTreeMap<(MapLayer, i32, i32), Entity<Entity<MapSchema>>> = read(dir, "maps")?;
+        Ok(Self {
+            items: values(read(dir, "items")?),
+            monsters: values(read(dir, "monsters")?),
+            resources: values(read(dir, "resources")?),
+            maps: maps.into_values().map(|m| m.0.0).collect(),
+            npcs: values(read(dir, "npcs")?),
+            npcs_items: values(read(dir, "npcs_items")?),
+            tasks: values(read(dir, "tasks")?),
+            tasks_rewards: values(read(dir, "tasks_rewards")?),
+            events: values(read(dir, "events")?),
+        })
+    }
+
+    #[must_use]
+    pub fn item(&self, code: &str) -> Option<&ItemSchema> {
+        self.items.iter().find(|i| i.code == code)
+    }
+
+    #[must_use]
+    pub fn monster(&self, code: &str) -> Option<&MonsterSchema> {
+        self.monsters.iter().find(|m| m.code == code)
+    }
+
+    #[must_use]
+    pub fn resource(&self, code: &str) -> Option<&ResourceSchema> {
+        self.resources.iter().find(|r| r.code == code)
+    }
+
+    #[must_use]
+    pub fn task(&self, code: &str) -> Option<&TaskFullSchema> {
+        self.tasks.iter().find(|t| t.code == code)
+    }
+
+    #[must_use]
+    pub fn map(&self, layer: MapLayer, x: i32, y: i32) -> Option<&MapSchema> {
+        self.maps
+            .iter()
+            .find(|m| m.layer == layer && m.x == x && m.y == y)
+    }
+
+    #[must_use]
+    pub fn map_by_id(&self, map_id: i32) -> Option<&MapSchema> {
+        self.maps.iter().find(|m| m.map_id == map_id)
+    }
+}
+
+fn read<T: DeserializeOwned>(dir: &Path, name: &str) -> Result<T, FixtureError> {
+    let file = format!("{name}.ron");
+    let content = fs::read_to_string(dir.join(&file)).map_err(|source| FixtureError::Io {
+        file: file.clone(),
+        source,
+    })?;
+    ron::from_str::<Envelope<T>>(&content)
+        .map(|e| e.data)
+        .or_else(|_| ron::from_str(&content))
+        .map_err(|source| FixtureError::Parse {
+            file,
+            source: Box::new(source),
+        })
+}
+
+/// Returns the entities ordered by code so that the catalogs are served in a
+/// stable order.
+fn values<T>(entities: BTreeMap<String, Entity<T>>) -> Vec<T> {
+    entities.into_values().map(|e| e.0).collect()
+}
@@ -0,0 +1,255 @@
+//! Local `ArtifactsMMO` server implementing the subset of the API used by the
+//! `api` crate.
+//!
+//! Catalogs are served from the RON fixtures written by the SDK cache and
+//! character actions are applied to an in-memory [`GameState`], so that a
+//! client or a full bot can be exercised end to end without network access.
+
+use std::{
+    io,
+    net::{SocketAddr, TcpListener},
+    sync::{Arc, Mutex, MutexGuard},
+    thread::{self, JoinHandle},
+};
+use tokio::sync::oneshot;
+
+pub use error::GameError;
+pub use fixtures::Fixtures;
+pub use routes::router;
+pub use state::GameState;
+
+mod error;
+pub mod fixtures;
+mod routes;
+pub mod state;
+
+/// Mock server running on a background thread, stopped when dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<GameState>>,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl MockServer {
+    /// Starts serving `state` on a random local port.
+    pub fn start(state: GameState) -> io::Result<Self> {
+        Self::bind(state, SocketAddr::from(([127, 0, 0, 1], 0)))
+    }
+
+    /// Starts serving `state` on `addr`.
+    pub fn bind(state: GameState, addr: SocketAddr) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(state));
+        let app = router(state.clone());
+        let (shutdown, signal) = oneshot::channel::<()>();
+        let handle = thread::Builder::new()
+            .name("mock_server".to_owned())
+            .spawn(move || {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?
+                    .block_on(async move {
+                        let listener = tokio::net::TcpListener::from_std(listener)?;
+                        axum::serve(listener, app)
+                            .with_graceful_shutdown(async {
+                                let _ = signal.await;
+                            })
+                            .await
+                    })
+            })?;
+        Ok(Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        })
+    }
+
+    /// Base URL to give to API clients.
+    #[must_use]
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    #[must_use]
+    pub const fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Gives access to the game state, e.g. to inspect it after a run.
+    pub fn state(&self) -> MutexGuard<'_, GameState> {
+        self.state.lock().unwrap()
+    }
+
+    /// Blocks until the server stops.
+    pub fn wait(mut self) -> io::Result<()> {
+        self.handle
+            .take()
+            .map_or(Ok(()), |h| h.join().unwrap_or(Ok(())))
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use api::ArtifactApi;
+    use openapi::apis::Error;
+    use sdk::{
+        CollectionClient, ItemContainer,
+        character::error::{MoveError, RequestError},
+    };
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../sdk/tests/fixtures");
+
+    fn server(cooldown: i32) -> MockServer {
+        let fixtures = Fixtures::load(FIXTURES).unwrap();
+        let state = GameState::new(fixtures, "account")
+            .with_character("cook")
+            .with_cooldown(cooldown);
+        MockServer::start(state).unwrap()
+    }
+
+    #[test]
+    fn serves_catalogs_across_pages() {
+        let server = server(0);
+        let api = ArtifactApi::new(server.url(), String::new());
+
+        let items = api.items.get_all().unwrap();
+        assert!(items.len() > 100);
+        assert_eq!(items.len(), server.state().fixtures().items.len());
+        assert_eq!(
+            api.maps.get_all().unwrap().len(),
+            server.state().fixtures().maps.len()
+        );
+        assert_eq!(api.account.characters("account").unwrap().data.len(), 1);
+    }
+
+    #[test]
+    fn applies_character_actions() {
+        let server = server(0);
+        let api = ArtifactApi::new(server.url(), String::new());
+
+        let res = api.my_character.r#move("cook", 0, 1).unwrap();
+        assert_eq!((res.data.character.x, res.data.character.y), (0, 1));
+        let res = api.my_character.fight("cook", None).unwrap();
+        assert_eq!(res.data.fight.characters[0].drops[0].code, "raw_chicken");
+        assert!(
+            res.data.characters[0]
+                .inventory
+                .iter()
+                .flatten()
+                .any(|s| s.code == "raw_chicken")
+        );
+        assert!(matches!(
+            api.my_character.r#move("cook", 0, 1),
+            Err(Error::ResponseError(e)) if e.status.as_u16() == 490
+        ));
+        assert_eq!(server.state().character("cook").unwrap().y, 1);
+    }
+
+    #[test]
+    fn refuses_actions_during_cooldown() {
+        let server = server(30);
+        let api = ArtifactApi::new(server.url(), String::new());
+
+        api.my_character.r#move("cook", 0, 1).unwrap();
+        assert!(matches!(
+            api.my_character.rest("cook"),
+            Err(Error::ResponseError(e)) if e.status.as_u16() == 499
+        ));
+    }
+
+    #[test]
+    fn drives_sdk_client() {
+        let server = server(0);
+        let cache = std::env::temp_dir().join(format!("mock_server_{}", std::process::id()));
+        std::fs::create_dir_all(&cache).unwrap();
+        let client = sdk::ClientBuilder::new()
+            .url(server.url())
+            .cache_dir(cache.to_str().unwrap())
+            .build()
+            .unwrap();
+        let character = client.account.get_character("cook").unwrap();
+
+        character.r#move(0, 1).unwrap();
+        character.fight(None).unwrap();
+        let cached = Fixtures::load(&cache).unwrap();
+        std::fs::remove_dir_all(&cache).unwrap();
+        assert_eq!(cached.items.len(), server.state().fixtures().items.len());
+        assert_eq!(server.state().character("cook").unwrap().y, 1);
+        assert!(character.inventory().total_of("raw_chicken") > 0);
+    }
+
+    #[test]
+    fn serves_sdk_offline_client_from_its_cache() {
+        let server = server(0);
+        let cache =
+            std::env::temp_dir().join(format!("mock_server_offline_{}", std::process::id()));
+        let builder = sdk::ClientBuilder::new()
+            .url(server.url())
+            .cache_dir(cache.to_str().unwrap());
+        builder.clone().build().unwrap();
+        drop(server);
+
+        let client = builder.build_offline().unwrap();
+        std::fs::remove_dir_all(&cache).unwrap();
+        let character = client.account.get_character("cook").unwrap();
+        assert_eq!(client.account.name(), "account");
+        assert!(client.items.get("copper_dagger").is_some());
+        assert!(matches!(
+            character.r#move(0, 1),
+            Err(MoveError::UnhandledError(RequestError::Offline))
+        ));
+    }
+
+    #[test]
+    fn falls_back_to_the_cache_when_unreachable() {
+        let server = server(0);
+        let cache = std::env::temp_dir().join(format!("mock_server_outage_{}", std::process::id()));
+        sdk::ClientBuilder::new()
+            .url(server.url())
+            .cache_dir(cache.to_str().unwrap())
+            .build()
+            .unwrap();
+        drop(server);
+
+        let client = sdk::ClientBuilder::new()
+            .url("http://127.0.0.1:1")
+            .cache_dir(cache.to_str().unwrap())
+            .build();
+        std::fs::remove_dir_all(&cache).unwrap();
+        let client = client.unwrap();
+        assert_eq!(client.account.name(), "account");
+        assert!(client.account.get_character("cook").is_some());
+        assert_eq!(client.server.status().version, env!("CARGO_PKG_VERSION"));
+        assert!(client.items.get("copper_dagger").is_some());
+    }
+
+    #[test]
+    fn reports_unreachable_account() {
+        let cache = std::env::temp_dir().join(format!("mock_server_none_{}", std::process::id()));
+        let error = sdk::ClientBuilder::new()
+            .url("http://127.0.0.1:1")
+            .cache_dir(cache.to_str().unwrap())
+            .build()
+            .err()
+            .unwrap();
+
+        assert_eq!(error.subsystem, sdk::Subsystem::Account);
+    }
+}
@@ -0,0 +1,40 @@
+use anyhow::Result;
+use clap::Parser;
+use log::{LevelFilter, info};
+use mock_server::{Fixtures, GameState, MockServer};
+use std::net::SocketAddr;
+
+/// Serves a local `ArtifactsMMO` API backed by fixtures.
+#[derive(Parser)]
+struct Args {
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    addr: SocketAddr,
+    /// Directory of the RON fixtures written by the SDK cache.
+    #[arg(long, default_value = "crates/sdk/tests/fixtures")]
+    fixtures: String,
+    #[arg(long, default_value = "account")]
+    account: String,
+    /// Name of a character to create, can be repeated.
+    #[arg(long = "character", default_value = "character")]
+    characters: Vec<String>,
+    /// Cooldown in seconds applied after each action.
+    #[arg(long, default_value_t = 0)]
+    cooldown: i32,
+}
+
+fn main() -> Result<()> {
+    simple_logging::log_to_stderr(LevelFilter::Info);
+    let args = Args::parse();
+    let fixtures = Fixtures::load(&args.fixtures)?;
+    let state = args
+        .characters
+        .iter()
+        .fold(GameState::new(fixtures, &args.account), |state, name| {
+            state.with_character(name)
+        })
+        .with_cooldown(args.cooldown);
+    let server = MockServer::bind(state, args.addr)?;
+    info!("mock server listening on {}", server.url());
+    server.wait()?;
+    Ok(())
+}
@@ -0,0 +1,317 @@
+use crate::{error::GameError, state::GameState};
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    routing::{MethodRouter, get, post},
+};
+use chrono::Utc;
+use openapi::models::{
+    AccountStatus, CraftingSchema, DepositWithdrawGoldSchema, DestinationSchema, EquipSchema,
+    MyAccountDetails, SimpleItemSchema, StatusSchema, UnequipSchema,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+
+pub type SharedState = Arc<Mutex<GameState>>;
+
+type Response<T> = Result<Json<Data<T>>, GameError>;
+
+const PAGE_SIZE: usize = 50;
+
+/// Envelope of every successful response.
+#[derive(Serialize)]
+pub struct Data<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct PageQuery {
+    page: Option<usize>,
+    size: Option<usize>,
+}
+
+pub fn router(state: SharedState) -> Router {
+    Router::new()
+        .route("/", get(status))
+        .route("/items", paged(|s| json!(s.fixtures().items)))
+        .route("/monsters", paged(|s| json!(s.fixtures().monsters)))
+        .route("/resources", paged(|s| json!(s.fixtures().resources)))
+        .route("/maps", paged(|s| json!(s.fixtures().maps)))
+        .route("/maps/id/{map_id}", get(map))
+        .route("/npcs/details", paged(|s| json!(s.fixtures().npcs)))
+        .route("/npcs/items", paged(|s| json!(s.fixtures().npcs_items)))
+        .route("/tasks/list", paged(|s| json!(s.fixtures().tasks)))
+        .route(
+            "/tasks/rewards",
+            paged(|s| json!(s.fixtures().tasks_rewards)),
+        )
+        .route("/events", paged(|s| json!(s.fixtures().events)))
+        .route("/events/active", get(empty_page))
+        .route("/characters/{name}", get(character))
+        .route("/accounts/{account}/characters", get(characters))
+        .route("/accounts/{account}/achievements", get(empty_page))
+        .route("/my/details", get(details))
+        .route("/my/bank", get(bank))
+        .route("/my/bank/items", paged(|s| json!(s.bank_items())))
+        .route("/my/pending_items", get(empty_page))
+        .route("/my/logs", get(empty_page))
+        .route("/my/logs/{name}", get(empty_page))
+        .route("/grandexchange/orders", get(empty_page))
+        .route("/grandexchange/history/{code}", get(empty_page))
+        .route("/my/{name}/action/move", post(r#move))
+        .route("/my/{name}/action/rest", post(rest))
+        .route("/my/{name}/action/fight", post(fight))
+        .route("/my/{name}/action/gathering", post(gather))
+        .route("/my/{name}/action/crafting", post(craft))
+        .route("/my/{name}/action/bank/deposit/item", post(deposit_items))
+        .route("/my/{name}/action/bank/withdraw/item", post(withdraw_items))
+        .route("/my/{name}/action/bank/deposit/gold", post(deposit_gold))
+        .route("/my/{name}/action/bank/withdraw/gold", post(withdraw_gold))
+        .route("/my/{name}/action/equip", post(equip))
+        .route("/my/{name}/action/unequip", post(unequip))
+        .route("/my/{name}/action/task/new", post(accept_task))
+        .route("/my/{name}/action/task/trade", post(trade_task_item))
+        .route("/my/{name}/action/task/complete", post(complete_task))
+        .route("/my/{name}/action/task/cancel", post(cancel_task))
+        .route("/my/{name}/action/delete", post(delete_item))
+        .route("/my/{name}/action/{*action}", post(unsupported))
+        .with_state(state)
+}
+
+const fn data<T>(data: T) -> Json<Data<T>> {
+    Json(Data { data })
+}
+
+/// Serves the catalog returned by `f` one page at a time, like the server
+/// paginated endpoints.
+fn paged(f: fn(&GameState) -> Value) -> MethodRouter<SharedState> {
+    get(
+        move |State(state): State<SharedState>, Query(query): Query<PageQuery>| async move {
+            let catalog = f(&state.lock().unwrap());
+            let entities = match catalog {
+                Value::Array(entities) => entities,
+                _ => vec![],
+            };
+            Json(page(entities, &query))
+        },
+    )
+}
+
+async fn empty_page(Query(query): Query<PageQuery>) -> Json<Value> {
+    Json(page(vec![], &query))
+}
+
+fn page(entities: Vec<Value>, query: &PageQuery) -> Value {
+    let size = query.size.unwrap_or(PAGE_SIZE).max(1);
+    let page = query.page.unwrap_or(1).max(1);
+    let total = entities.len();
+    let data = entities
+        .into_iter()
+        .skip((page - 1) * size)
+        .take(size)
+        .collect::<Vec<_>>();
+    json!({
+        "data": data,
+        "total": total,
+        "page": page,
+        "size": size,
+        "pages": total.div_ceil(size).max(1),
+    })
+}
+
+async fn status(State(state): State<SharedState>) -> Json<Data<StatusSchema>> {
+    let characters = state.lock().unwrap().characters().len();
+    data(StatusSchema::new(
+        env!("CARGO_PKG_VERSION").to_owned(),
+        Utc::now().fixed_offset(),
+        50,
+        50,
+        i32::try_from(characters).unwrap_or(i32::MAX),
+        vec![],
+    ))
+}
+
+async fn map(State(state): State<SharedState>, Path(map_id): Path<i32>) -> Response<Value> {
+    let map = state
+        .lock()
+        .unwrap()
+        .fixtures()
+        .map_by_id(map_id)
+        .map(|m| json!(m))
+        .ok_or(GameError::NotFound("map"))?;
+    Ok(data(map))
+}
+
+async fn character(State(state): State<SharedState>, Path(name): Path<String>) -> Response<Value> {
+    let character = state
+        .lock()
+        .unwrap()
+        .character(&name)
+        .map(|c| json!(c))
+        .ok_or(GameError::CharacterNotFound)?;
+    Ok(data(character))
+}
+
+async fn characters(
+    State(state): State<SharedState>,
+    Path(account): Path<String>,
+) -> Response<Value> {
+    let state = state.lock().unwrap();
+    let characters = (state.account() == account).then(|| json!(state.characters()));
+    drop(state);
+    Ok(data(characters.ok_or(GameError::NotFound("account"))?))
+}
+
+async fn details(State(state): State<SharedState>) -> Json<Data<MyAccountDetails>> {
+    let account = state.lock().unwrap().account().to_owned();
+    data(MyAccountDetails::new(
+        account,
+        String::new(),
+        true,
+        AccountStatus::Standard,
+        vec![],
+        0,
+        0,
+        false,
+    ))
+}
+
+async fn bank(State(state): State<SharedState>) -> Json<Data<Value>> {
+    data(json!(state.lock().unwrap().bank()))
+}
+
+async fn r#move(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(destination): Json<DestinationSchema>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().r#move(&name, &destination)?))
+}
+
+async fn rest(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().rest(&name)?))
+}
+
+async fn fight(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().fight(&name)?))
+}
+
+async fn gather(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().gather(&name)?))
+}
+
+async fn craft(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(craft): Json<CraftingSchema>,
+) -> Response<impl Serialize> {
+    let quantity = craft.quantity.unwrap_or(1);
+    Ok(data(state.lock().unwrap().craft(
+        &name,
+        &craft.code,
+        quantity,
+    )?))
+}
+
+async fn deposit_items(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(items): Json<Vec<SimpleItemSchema>>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().deposit_items(&name, items)?))
+}
+
+async fn withdraw_items(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(items): Json<Vec<SimpleItemSchema>>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().withdraw_items(&name, items)?))
+}
+
+async fn deposit_gold(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(gold): Json<DepositWithdrawGoldSchema>,
+) -> Response<impl Serialize> {
+    Ok(data(
+        state.lock().unwrap().deposit_gold(&name, gold.quantity)?,
+    ))
+}
+
+async fn withdraw_gold(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(gold): Json<DepositWithdrawGoldSchema>,
+) -> Response<impl Serialize> {
+    Ok(data(
+        state.lock().unwrap().withdraw_gold(&name, gold.quantity)?,
+    ))
+}
+
+async fn equip(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(items): Json<Vec<EquipSchema>>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().equip(&name, &items)?))
+}
+
+async fn unequip(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(slots): Json<Vec<UnequipSchema>>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().unequip(&name, &slots)?))
+}
+
+async fn accept_task(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().accept_task(&name)?))
+}
+
+async fn trade_task_item(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(item): Json<SimpleItemSchema>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().trade_task_item(&name, &item)?))
+}
+
+async fn complete_task(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().complete_task(&name)?))
+}
+
+async fn cancel_task(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().cancel_task(&name)?))
+}
+
+async fn delete_item(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(item): Json<SimpleItemSchema>,
+) -> Response<impl Serialize> {
+    Ok(data(state.lock().unwrap().delete_item(&name, item)?))
+}
+
+async fn unsupported() -> GameError {
+    GameError::Unsupported
+}
@@ -0,0 +1,792 @@
+use crate::{error::GameError, fixtures::Fixtures};
+use chrono::{TimeDelta, Utc};
+use openapi::models::{
+    ActionType, BankGoldTransactionSchema, BankItemTransactionSchema, BankSchema,
+    CharacterFightDataSchema, CharacterFightSchema, CharacterMovementDataSchema,
+    CharacterMultiFightResultSchema, CharacterRestDataSchema, CharacterSchema, CooldownSchema,
+    DeleteItemSchema, DestinationSchema, DropRateSchema, DropSchema, EquipSchema,
+    EquipmentItemSchema, EquipmentTransactionSchema, FightResult, GoldSchema, InventorySlotSchema,
+    ItemSlot, MapContentSchema, MapContentType, MapLayer, RewardDataSchema, RewardsSchema,
+    SimpleItemSchema, SkillDataSchema, SkillInfoSchema, TaskCancelledSchema, TaskDataSchema,
+    TaskSchema, TaskTradeDataSchema, TaskTradeSchema, TaskType, UnequipSchema,
+};
+
+const INVENTORY_SLOTS: i32 = 20;
+const TASKS_COIN: &str = "tasks_coin";
+
+/// In-memory game state of a single account.
+///
+/// Actions are applied to a copy of the character and only stored when they
+/// succeed, so that a failing action leaves the state untouched. Fights are
+/// always won and fights and gatherings only reward their likeliest drop,
+/// without XP.
+#[derive(Debug)]
+pub struct GameState {
+    fixtures: Fixtures,
+    account: String,
+    characters: Vec<CharacterSchema>,
+    bank: BankSchema,
+    bank_items: Vec<SimpleItemSchema>,
+    cooldown: i32,
+}
+
+impl GameState {
+    #[must_use]
+    pub fn new(fixtures: Fixtures, account: &str) -> Self {
+        Self {
+            fixtures,
+            account: account.to_owned(),
+            characters: vec![],
+            bank: BankSchema::new(50, 0, 4500, 0),
+            bank_items: vec![],
+            cooldown: 0,
+        }
+    }
+
+    /// Adds a level 1 character at the origin of the overworld.
+    #[must_use]
+    pub fn with_character(mut self, name: &str) -> Self {
+        let map_id = self
+            .fixtures
+            .map(MapLayer::Overworld, 0, 0)
+            .map_or(0, |m| m.map_id);
+        self.characters.push(CharacterSchema {
+            name: name.to_owned(),
+            account: self.account.clone(),
+            level: 1,
+            max_xp: 150,
+            hp: 120,
+            max_hp: 120,
+            mining_level: 1,
+            woodcutting_level: 1,
+            fishing_level: 1,
+            weaponcrafting_level: 1,
+            gearcrafting_level: 1,
+            jewelrycrafting_level: 1,
+            cooking_level: 1,
+            alchemy_level: 1,
+            layer: MapLayer::Overworld,
+            map_id,
+            inventory_max_items: 100,
+            inventory: Some(
+                (1..=INVENTORY_SLOTS)
+                    .map(|slot| InventorySlotSchema::new(slot, String::new(), 0))
+                    .collect(),
+            ),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Applies `update` to the character `name`, which must have been added
+    /// first, e.g. to set its skill levels.
+    #[must_use]
+    pub fn with_character_update(
+        mut self,
+        name: &str,
+        update: impl FnOnce(&mut CharacterSchema),
+    ) -> Self {
+        if let Some(character) = self.characters.iter_mut().find(|c| c.name == name) {
+            update(character);
+        }
+        self
+    }
+
+    /// Gives the task `code` to the character `name`, which must have been
+    /// added first.
+    #[must_use]
+    pub fn with_task(mut self, name: &str, code: &str) -> Self {
+        let task = self.fixtures.task(code).cloned();
+        if let (Some(character), Some(task)) =
+            (self.characters.iter_mut().find(|c| c.name == name), task)
+        {
+            character.task = task.code;
+            character.task_type = task.r#type.to_string();
+            character.task_progress = 0;
+            character.task_total = task.min_quantity;
+        }
+        self
+    }
+
+    /// Sets the cooldown, in seconds, triggered by each action.
+    #[must_use]
+    pub const fn with_cooldown(mut self, seconds: i32) -> Self {
+        self.cooldown = seconds;
+        self
+    }
+
+    /// Stores `quantity` of the item `code` in the bank.
+    #[must_use]
+    pub fn with_bank_item(mut self, code: &str, quantity: u32) -> Self {
+        self.bank_items
+            .push(SimpleItemSchema::new(code.to_owned(), quantity));
+        self
+    }
+
+    #[must_use]
+    pub const fn fixtures(&self) -> &Fixtures {
+        &self.fixtures
+    }
+
+    #[must_use]
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    #[must_use]
+    pub fn characters(&self) -> &[CharacterSchema] {
+        &self.characters
+    }
+
+    #[must_use]
+    pub fn character(&self, name: &str) -> Option<&CharacterSchema> {
+        self.characters.iter().find(|c| c.name == name)
+    }
+
+    #[must_use]
+    pub const fn bank(&self) -> &BankSchema {
+        &self.bank
+    }
+
+    #[must_use]
+    pub fn bank_items(&self) -> &[SimpleItemSchema] {
+        &self.bank_items
+    }
+
+    pub fn r#move(
+        &mut self,
+        name: &str,
+        destination: &DestinationSchema,
+    ) -> Result<CharacterMovementDataSchema, GameError> {
+        let mut character = self.ready(name)?;
+        let map = match (destination.map_id, destination.x, destination.y) {
+            (Some(id), _, _) => self.fixtures.map_by_id(id),
+            (None, Some(x), Some(y)) => self.fixtures.map(character.layer, x, y),
+            _ => None,
+        }
+        .ok_or(GameError::NotFound("map"))?
+        .clone();
+        if map.map_id == character.map_id {
+            return Err(GameError::AlreadyOnMap);
+        }
+        character.x = map.x;
+        character.y = map.y;
+        character.layer = map.layer;
+        character.map_id = map.map_id;
+        let (cooldown, character) = self.commit(character, ActionType::Movement);
+        Ok(CharacterMovementDataSchema::new(
+            cooldown,
+            map,
+            vec![],
+            character,
+        ))
+    }
+
+    pub fn rest(&mut self, name: &str) -> Result<CharacterRestDataSchema, GameError> {
+        let mut character = self.ready(name)?;
+        let hp_restored = character.max_hp - character.hp;
+        character.hp = character.max_hp;
+        let (cooldown, character) = self.commit(character, ActionType::Rest);
+        Ok(CharacterRestDataSchema::new(
+            cooldown,
+            hp_restored,
+            character,
+        ))
+    }
+
+    pub fn fight(&mut self, name: &str) -> Result<CharacterFightDataSchema, GameError> {
+        let mut character = self.ready(name)?;
+        let monster = self
+            .content(&character, MapContentType::Monster)
+            .and_then(|c| self.fixtures.monster(&c.code))
+            .ok_or(GameError::NotOnMap("monster"))?
+            .clone();
+        let drops = likeliest(&monster.drops);
+        for drop in &drops {
+            add_item(&mut character, &drop.code, drop.quantity as u32)?;
+        }
+        character.gold += monster.min_gold;
+        if character.task_type == TaskType::Monsters.to_string() && character.task == monster.code {
+            character.task_progress = (character.task_progress + 1).min(character.task_total);
+        }
+        let result = CharacterMultiFightResultSchema::new(
+            character.name.clone(),
+            0,
+            monster.min_gold,
+            drops,
+            character.hp,
+        );
+        let (cooldown, character) = self.commit(character, ActionType::Fight);
+        Ok(CharacterFightDataSchema::new(
+            cooldown,
+            CharacterFightSchema::new(FightResult::Win, 1, monster.code, vec![], vec![result]),
+            vec![character],
+        ))
+    }
+
+    pub fn gather(&mut self, name: &str) -> Result<SkillDataSchema, GameError> {
+        let mut character = self.ready(name)?;
+        let resource = self
+            .content(&character, MapContentType::Resource)
+            .and_then(|c| self.fixtures.resource(&c.code))
+            .ok_or(GameError::NotOnMap("resource"))?
+            .clone();
+        if skill_level(&character, &resource.skill.to_string()) < resource.level {
+            return Err(GameError::SkillLevelInsufficient);
+        }
+        let drops = likeliest(&resource.drops);
+        for drop in &drops {
+            add_item(&mut character, &drop.code, drop.quantity as u32)?;
+        }
+        let (cooldown, character) = self.commit(character, ActionType::Gathering);
+        Ok(SkillDataSchema::new(
+            cooldown,
+            SkillInfoSchema::new(0, drops),
+            character,
+        ))
+    }
+
+    pub fn craft(
+        &mut self,
+        name: &str,
+        code: &str,
+        quantity: u32,
+    ) -> Result<SkillDataSchema, GameError> {
+        let mut character = self.ready(name)?;
+        let craft = self
+            .fixtures
+            .item(code)
+            .and_then(|i| i.craft.clone())
+            .ok_or(GameError::NotFound("craft"))?;
+        let skill = craft.skill.map(|s| s.to_string()).unwrap_or_default();
+        if self
+            .content(&character, MapContentType::Workshop)
+            .is_none_or(|c| c.code != skill)
+        {
+            return Err(GameError::NotOnMap("workshop"));
+        }
+        if skill_level(&character, &skill) < craft.level.unwrap_or_default() {
+            return Err(GameError::SkillLevelInsufficient);
+        }
+        for material in craft.items.iter().flatten() {
+            remove_item(&mut character, &material.code, material.quantity * quantity)?;
+        }
+        let crafted = craft.quantity.unwrap_or(1) as u32 * quantity;
+        add_item(&mut character, code, crafted)?;
+        let (cooldown, character) = self.commit(character, ActionType::Crafting);
+        Ok(SkillDataSchema::new(
+            cooldown,
+            SkillInfoSchema::new(0, vec![DropSchema::new(code.to_owned(), crafted as i32)]),
+            character,
+        ))
+    }
+
+    pub fn deposit_items(
+        &mut self,
+        name: &str,
+        items: Vec<SimpleItemSchema>,
+    ) -> Result<BankItemTransactionSchema, GameError> {
+        let mut character = self.ready(name)?;
+        self.ensure_on(&character, MapContentType::Bank, "bank")?;
+        let mut bank_items = self.bank_items.clone();
+        for item in &items {
+            remove_item(&mut character, &item.code, item.quantity)?;
+            match bank_items.iter_mut().find(|i| i.code == item.code) {
+                Some(stack) => stack.quantity += item.quantity,
+                None => bank_items.push(item.clone()),
+            }
+        }
+        self.bank_items = bank_items;
+        let (cooldown, character) = self.commit(character, ActionType::DepositItem);
+        Ok(BankItemTransactionSchema::new(
+            cooldown,
+            items,
+            self.bank_items.clone(),
+            character,
+        ))
+    }
+
+    pub fn withdraw_items(
+        &mut self,
+        name: &str,
+        items: Vec<SimpleItemSchema>,
+    ) -> Result<BankItemTransactionSchema, GameError> {
+        let mut character = self.ready(name)?;
+        self.ensure_on(&character, MapContentType::Bank, "bank")?;
+        let mut bank_items = self.bank_items.clone();
+        for item in &items {
+            let stack = bank_items
+                .iter_mut()
+                .find(|i| i.code == item.code && i.quantity >= item.quantity)
+                .ok_or(GameError::NotFound("item"))?;
+            stack.quantity -= item.quantity;
+            add_item(&mut character, &item.code, item.quantity)?;
+        }
+        bank_items.retain(|i| i.quantity > 0);
+        self.bank_items = bank_items;
+        let (cooldown, character) = self.commit(character, ActionType::WithdrawItem);
+        Ok(BankItemTransactionSchema::new(
+            cooldown,
+            items,
+            self.bank_items.clone(),
+            character,
+        ))
+    }
+
+    pub fn deposit_gold(
+        &mut self,
+        name: &str,
+        quantity: u32,
+    ) -> Result<BankGoldTransactionSchema, GameError> {
+        let mut character = self.ready(name)?;
+        self.ensure_on(&character, MapContentType::Bank, "bank")?;
+        if (character.gold as u32) < quantity {
+            return Err(GameError::CharacterGoldInsufficient);
+        }
+        character.gold -= quantity as i32;
+        self.bank.gold += quantity;
+        let (cooldown, character) = self.commit(character, ActionType::DepositGold);
+        Ok(BankGoldTransactionSchema::new(
+            cooldown,
+            GoldSchema::new(self.bank.gold),
+            character,
+        ))
+    }
+
+    pub fn withdraw_gold(
+        &mut self,
+        name: &str,
+        quantity: u32,
+    ) -> Result<BankGoldTransactionSchema, GameError> {
+        let mut character = self.ready(name)?;
+        self.ensure_on(&character, MapContentType::Bank, "bank")?;
+        if self.bank.gold < quantity {
+            return Err(GameError::BankGoldInsufficient);
+        }
+        character.gold += quantity as i32;
+        self.bank.gold -= quantity;
+        let (cooldown, character) = self.commit(character, ActionType::WithdrawGold);
+        Ok(BankGoldTransactionSchema::new(
+            cooldown,
+            GoldSchema::new(self.bank.gold),
+            character,
+        ))
+    }
+
+    pub fn equip(
+        &mut self,
+        name: &str,
+        items: &[EquipSchema],
+    ) -> Result<EquipmentTransactionSchema, GameError> {
+        let mut character = self.ready(name)?;
+        let mut equipped = vec![];
+        for equip in items {
+            let item = self
+                .fixtures
+                .item(&equip.code)
+                .ok_or(GameError::NotFound("item"))?
+                .clone();
+            let quantity = equip.quantity.unwrap_or(1);
+            if item.r#type != slot_type(equip.slot) || !slot(&mut character, equip.slot).is_empty()
+            {
+                return Err(GameError::InvalidSlotState);
+            }
+            remove_item(&mut character, &equip.code, quantity)?;
+            equip.code.clone_into(slot(&mut character, equip.slot));
+            set_utility_quantity(&mut character, equip.slot, quantity);
+            equipped.push(EquipmentItemSchema::new(equip.slot, item));
+        }
+        let (cooldown, character) = self.commit(character, ActionType::Equip);
+        Ok(EquipmentTransactionSchema::new(
+            cooldown, equipped, character,
+        ))
+    }
+
+    pub fn unequip(
+        &mut self,
+        name: &str,
+        slots: &[UnequipSchema],
+    ) -> Result<EquipmentTransactionSchema, GameError> {
+        let mut character = self.ready(name)?;
+        let mut unequipped = vec![];
+        for unequip in slots {
+            let code = std::mem::take(slot(&mut character, unequip.slot));
+            let item = self
+                .fixtures
+                .item(&code)
+                .ok_or(GameError::InvalidSlotState)?
+                .clone();
+            let quantity = unequip.quantity.unwrap_or(1);
+            let remaining = utility_quantity(&character, unequip.slot).saturating_sub(quantity);
+            if remaining > 0 {
+                code.clone_into(slot(&mut character, unequip.slot));
+            }
+            set_utility_quantity(&mut character, unequip.slot, remaining);
+            add_item(&mut character, &code, quantity)?;
+            unequipped.push(EquipmentItemSchema::new(unequip.slot, item));
+        }
+        let (cooldown, character) = self.commit(character, ActionType::Unequip);
+        Ok(EquipmentTransactionSchema::new(
+            cooldown, unequipped, character,
+        ))
+    }
+
+    pub fn accept_task(&mut self, name: &str) -> Result<TaskDataSchema, GameError> {
+        let mut character = self.ready(name)?;
+        let master = self
+            .content(&character, MapContentType::TasksMaster)
+            .ok_or(GameError::NotOnMap("tasks master"))?
+            .code
+            .clone();
+        if !character.task.is_empty() {
+            return Err(GameError::TaskAlreadyInProgress);
+        }
+        let task = self
+            .fixtures
+            .tasks
+            .iter()
+            .find(|t| t.r#type.to_string() == master && t.level <= character.level)
+            .ok_or(GameError::NotFound("task"))?
+            .clone();
+        character.task.clone_from(&task.code);
+        character.task_type = task.r#type.to_string();
+        character.task_progress = 0;
+        character.task_total = task.min_quantity;
+        let (cooldown, character) = self.commit(character, ActionType::Task);
+        Ok(TaskDataSchema::new(
+            cooldown,
+            TaskSchema::new(task.code, task.r#type, task.min_quantity, task.rewards),
+            character,
+        ))
+    }
+
+    pub fn trade_task_item(
+        &mut self,
+        name: &str,
+        item: &SimpleItemSchema,
+    ) -> Result<TaskTradeDataSchema, GameError> {
+        let mut character = self.ready(name)?;
+        self.ensure_on(&character, MapContentType::TasksMaster, "tasks master")?;
+        if character.task_type != TaskType::Items.to_string() || character.task != item.code {
+            return Err(GameError::WrongTask);
+        }
+        if character.task_progress + item.quantity as i32 > character.task_total {
+            return Err(GameError::TaskAlreadyCompleted);
+        }
+        remove_item(&mut character, &item.code, item.quantity)?;
+        character.task_progress += item.quantity as i32;
+        let (cooldown, character) = self.commit(character, ActionType::Task);
+        Ok(TaskTradeDataSchema::new(
+            cooldown,
+            TaskTradeSchema::new(item.code.clone(), item.quantity as i32),
+            character,
+        ))
+    }
+
+    pub fn complete_task(&mut self, name: &str) -> Result<RewardDataSchema, GameError> {
+        let mut character = self.ready(name)?;
+        self.ensure_on(&character, MapContentType::TasksMaster, "tasks master")?;
+        if character.task.is_empty() {
+            return Err(GameError::NoTask);
+        }
+        if character.task_progress < character.task_total {
+            return Err(GameError::TaskNotCompleted);
+        }
+        let rewards = self
+            .fixtures
+            .task(&character.task)
+            .map_or_else(|| RewardsSchema::new(vec![], 0), |t| t.rewards.clone());
+        for item in &rewards.items {
+            add_item(&mut character, &item.code, item.quantity)?;
+        }
+        character.gold += rewards.gold;
+        clear_task(&mut character);
+        let (cooldown, character) = self.commit(character, ActionType::Task);
+        Ok(RewardDataSchema::new(cooldown, rewards, character))
+    }
+
+    pub fn cancel_task(&mut self, name: &str) -> Result<TaskCancelledSchema, GameError> {
+        let mut character = self.ready(name)?;
+        self.ensure_on(&character, MapContentType::TasksMaster, "tasks master")?;
+        if character.task.is_empty() {
+            return Err(GameError::NoTask);
+        }
+        remove_item(&mut character, TASKS_COIN, 1)?;
+        clear_task(&mut character);
+        let (cooldown, character) = self.commit(character, ActionType::Task);
+        Ok(TaskCancelledSchema::new(cooldown, character))
+    }
+
+    pub fn delete_item(
+        &mut self,
+        name: &str,
+        item: SimpleItemSchema,
+    ) -> Result<DeleteItemSchema, GameError> {
+        let mut character = self.ready(name)?;
+        remove_item(&mut character, &item.code, item.quantity)?;
+        let (cooldown, character) = self.commit(character, ActionType::DeleteItem);
+        Ok(DeleteItemSchema::new(cooldown, item, character))
+    }
+
+    /// Returns a copy of the character `name` if it can act.
+    fn ready(&self, name: &str) -> Result<CharacterSchema, GameError> {
+        let character = self.character(name).ok_or(GameError::CharacterNotFound)?;
+        if character
+            .cooldown_expiration
+            .is_some_and(|e| e > Utc::now().fixed_offset())
+        {
+            return Err(GameError::Cooldown);
+        }
+        Ok(character.clone())
+    }
+
+    /// Stores the `character` after a successful action, starting its cooldown.
+    fn commit(
+        &mut self,
+        mut character: CharacterSchema,
+        reason: ActionType,
+    ) -> (CooldownSchema, CharacterSchema) {
+        let started_at = Utc::now().fixed_offset();
+        let expiration = started_at + TimeDelta::seconds(self.cooldown.into());
+        character.cooldown = self.cooldown;
+        character.cooldown_expiration = Some(expiration);
+        if let Some(stored) = self
+            .characters
+            .iter_mut()
+            .find(|c| c.name == character.name)
+        {
+            stored.clone_from(&character);
+        }
+        let cooldown =
+            CooldownSchema::new(self.cooldown, self.cooldown, started_at, expiration, reason);
+        (cooldown, character)
+    }
+
+    fn content(
+        &self,
+        character: &CharacterSchema,
+        r#type: MapContentType,
+    ) -> Option<&MapContentSchema> {
+        self.fixtures
+            .map_by_id(character.map_id)?
+            .interactions
+            .content
+            .as_ref()
+            .filter(|c| c.r#type == r#type)
+    }
+
+    fn ensure_on(
+        &self,
+        character: &CharacterSchema,
+        r#type: MapContentType,
+        label: &'static str,
+    ) -> Result<(), GameError> {
+        self.content(character, r#type)
+            .map(|_| ())
+            .ok_or(GameError::NotOnMap(label))
+    }
+}
+
+/// Returns the likeliest of the `drops`, at its minimum quantity.
+fn likeliest(drops: &[DropRateSchema]) -> Vec<DropSchema> {
+    drops
+        .iter()
+        .min_by_key(|d| d.rate)
+        .map(|d| DropSchema::new(d.code.clone(), d.min_quantity as i32))
+        .into_iter()
+        .collect()
+}
+
+fn skill_level(character: &CharacterSchema, skill: &str) -> i32 {
+    match skill {
+        "mining" => character.mining_level,
+        "woodcutting" => character.woodcutting_level,
+        "fishing" => character.fishing_level,
+        "weaponcrafting" => character.weaponcrafting_level,
+        "gearcrafting" => character.gearcrafting_level,
+        "jewelrycrafting" => character.jewelrycrafting_level,
+        "cooking" => character.cooking_level,
+        "alchemy" => character.alchemy_level,
+        _ => character.level,
+    }
+}
+
+fn add_item(character: &mut CharacterSchema, code: &str, quantity: u32) -> Result<(), GameError> {
+    let max_items = character.inventory_max_items as u32;
+    let inventory = character.inventory.get_or_insert_default();
+    let total = inventory.iter().map(|s| s.quantity as u32).sum::<u32>();
+    if total + quantity > max_items {
+        return Err(GameError::InventoryFull);
+    }
+    let slot = match inventory.iter().position(|s| s.code == code) {
+        Some(i) => i,
+        None => inventory
+            .iter()
+            .position(|s| s.code.is_empty())
+            .ok_or(GameError::InventoryFull)?,
+    };
+    code.clone_into(&mut inventory[slot].code);
+    inventory[slot].quantity += quantity as i32;
+    Ok(())
+}
+
+fn remove_item(
+    character: &mut CharacterSchema,
+    code: &str,
+    quantity: u32,
+) -> Result<(), GameError> {
+    let slot = character
+        .inventory
+        .iter_mut()
+        .flatten()
+        .find(|s| s.code == code && s.quantity as u32 >= quantity)
+        .ok_or(GameError::MissingItem)?;
+    slot.quantity -= quantity as i32;
+    if slot.quantity == 0 {
+        slot.code.clear();
+    }
+    Ok(())
+}
+
+fn clear_task(character: &mut CharacterSchema) {
+    character.task.clear();
+    character.task_type.clear();
+    character.task_progress = 0;
+    character.task_total = 0;
+}
+
+/// Returns the item type accepted by the `slot`.
+const fn slot_type(slot: ItemSlot) -> &'static str {
+    match slot {
+        ItemSlot::Weapon => "weapon",
+        ItemSlot::Shield => "shield",
+        ItemSlot::Helmet => "helmet",
+        ItemSlot::BodyArmor => "body_armor",
+        ItemSlot::LegArmor => "leg_armor",
+        ItemSlot::Boots => "boots",
+        ItemSlot::Ring1 | ItemSlot::Ring2 => "ring",
+        ItemSlot::Amulet => "amulet",
+        ItemSlot::Artifact1 | ItemSlot::Artifact2 | ItemSlot::Artifact3 => "artifact",
+        ItemSlot::Utility1 | ItemSlot::Utility2 => "utility",
+        ItemSlot::Bag => "bag",
+        ItemSlot::Rune => "rune",
+    }
+}
+
+const fn slot(character: &mut CharacterSchema, slot: ItemSlot) -> &mut String {
+    match slot {
+        ItemSlot::Weapon => &mut character.weapon_slot,
+        ItemSlot::Shield => &mut character.shield_slot,
+        ItemSlot::Helmet => &mut character.helmet_slot,
+        ItemSlot::BodyArmor => &mut character.body_armor_slot,
+        ItemSlot::LegArmor => &mut character.leg_armor_slot,
+        ItemSlot::Boots => &mut character.boots_slot,
+        ItemSlot::Ring1 => &mut character.ring1_slot,
+        ItemSlot::Ring2 => &mut character.ring2_slot,
+        ItemSlot::Amulet => &mut character.amulet_slot,
+        ItemSlot::Artifact1 => &mut character.artifact1_slot,
+        ItemSlot::Artifact2 => &mut character.artifact2_slot,
+        ItemSlot::Artifact3 => &mut character.artifact3_slot,
+        ItemSlot::Utility1 => &mut character.utility1_slot,
+        ItemSlot::Utility2 => &mut character.utility2_slot,
+        ItemSlot::Bag => &mut character.bag_slot,
+        ItemSlot::Rune => &mut character.rune_slot,
+    }
+}
+
+const fn utility_quantity(character: &CharacterSchema, slot: ItemSlot) -> u32 {
+    match slot {
+        ItemSlot::Utility1 => character.utility1_slot_quantity,
+        ItemSlot::Utility2 => character.utility2_slot_quantity,
+        _ => 1,
+    }
+}
+
+const fn set_utility_quantity(character: &mut CharacterSchema, slot: ItemSlot, quantity: u32) {
+    match slot {
+        ItemSlot::Utility1 => character.utility1_slot_quantity = quantity,
+        ItemSlot::Utility2 => character.utility2_slot_quantity = quantity,
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> GameState {
+        let fixtures = Fixtures::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../sdk/tests/fixtures"
+        ))
+        .unwrap();
+        GameState::new(fixtures, "account").with_character("char1")
+    }
+
+    fn quantity(state: &GameState, code: &str) -> i32 {
+        state
+            .character("char1")
+            .and_then(|c| c.inventory.as_ref())
+            .into_iter()
+            .flatten()
+            .filter(|s| s.code == code)
+            .map(|s| s.quantity)
+            .sum()
+    }
+
+    #[test]
+    fn failing_action_leaves_state_untouched() {
+        let mut state = state();
+        let before = state.character("char1").cloned();
+
+        assert_eq!(
+            state.fight("char1").unwrap_err(),
+            GameError::NotOnMap("monster")
+        );
+        assert_eq!(state.character("char1").cloned(), before);
+    }
+
+    #[test]
+    fn actions_are_refused_during_cooldown() {
+        let mut state = state().with_cooldown(60);
+
+        assert!(state.rest("char1").is_ok());
+        assert_eq!(state.rest("char1").unwrap_err(), GameError::Cooldown);
+    }
+
+    #[test]
+    fn gathered_items_can_be_deposited() {
+        let mut state = state();
+        let chicken = state
+            .fixtures()
+            .map(MapLayer::Overworld, 0, 1)
+            .unwrap()
+            .clone();
+        let bank = state
+            .fixtures()
+            .map(MapLayer::Overworld, 4, 1)
+            .unwrap()
+            .clone();
+        let destination = |x, y| DestinationSchema {
+            x: Some(x),
+            y: Some(y),
+            map_id: None,
+        };
+
+        state
+            .r#move("char1", &destination(chicken.x, chicken.y))
+            .unwrap();
+        let fight = state.fight("char1").unwrap();
+        let drops = fight.fight.characters[0].drops.clone();
+        assert!(!drops.is_empty());
+        state.r#move("char1", &destination(bank.x, bank.y)).unwrap();
+        let items = drops
+            .iter()
+            .map(|d| SimpleItemSchema::new(d.code.clone(), d.quantity as u32))
+            .collect::<Vec<_>>();
+        state.deposit_items("char1", items.clone()).unwrap();
+
+        assert_eq!(quantity(&state, &items[0].code), 0);
+        assert_eq!(state.bank_items(), items);
+    }
+}
@@ -0,0 +1,339 @@
+//! End-to-end tests of the bot character loop against the mock server.
+
+use bot::{
+    Bot, CharacterCommand, bot_config::BotConfig, gear_finder::GearPurpose,
+    leveling_helper::LevelingAction, orderboard::Purpose, reservable::Reservable,
+};
+use mock_server::{Fixtures, GameState, MockServer};
+use openapi::models::MapLayer;
+use sdk::{Code, CollectionClient, Skill, entities::Character, items::ItemSource};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../sdk/tests/fixtures");
+const TASK_MONSTER: &str = "green_slime";
+
+/// Starts a bot leveling combat and woodcutting against the `server`,
+/// caching the game data under a directory named after `tag`.
+fn bot(server: &MockServer, tag: &str) -> (sdk::Client, Bot) {
+    bot_with_config(
+        server,
+        tag,
+        r#"
+        [[characters]]
+        skills = ["combat", "woodcutting"]
+        "#,
+    )
+}
+
+/// Starts a bot configured from the `config` TOML against the `server`.
+fn bot_with_config(server: &MockServer, tag: &str, config: &str) -> (sdk::Client, Bot) {
+    let cache = std::env::temp_dir().join(format!("mock_server_{tag}_{}", std::process::id()));
+    let client = sdk::ClientBuilder::new()
+        .url(server.url())
+        .cache_dir(cache.to_str().unwrap())
+        .build()
+        .unwrap();
+    std::fs::remove_dir_all(&cache).unwrap();
+    let config = BotConfig::from_toml(config).unwrap();
+    let bot = Bot::with_config(client.clone(), config);
+    bot.init_characters();
+    (client, bot)
+}
+
+#[test]
+fn runs_bot_character_loop() {
+    let fixtures = Fixtures::load(FIXTURES).unwrap();
+    let state = GameState::new(fixtures, "account")
+        .with_character("cook")
+        .with_bank_item("copper_dagger", 1);
+    let server = MockServer::start(state).unwrap();
+    let (client, bot) = bot(&server, "loop");
+    let char = bot.account.get_character(0).unwrap();
+
+    for cmd in [
+        CharacterCommand::Gather {
+            resource: client.resources.get("ash_tree").unwrap(),
+        },
+        // Fighting without a task accepts one first.
+        CharacterCommand::Kill {
+            monster: client.monsters.get("chicken").unwrap(),
+        },
+        CharacterCommand::Kill {
+            monster: client.monsters.get("chicken").unwrap(),
+        },
+        CharacterCommand::DepositItem {
+            item: "ash_wood".to_owned(),
+            quantity: 1,
+        },
+    ] {
+        char.send_cmd(cmd).unwrap();
+        assert!(char.step());
+    }
+    let cook = server.state().character("cook").unwrap().clone();
+    assert!(
+        server
+            .state()
+            .bank_items()
+            .iter()
+            .any(|i| i.code == "ash_wood")
+    );
+    assert_eq!(cook.weapon_slot, "copper_dagger");
+    assert!(
+        cook.inventory
+            .iter()
+            .flatten()
+            .any(|s| s.code == "raw_chicken")
+    );
+}
+
+#[test]
+fn plans_round_trips_from_the_character_position() {
+    let state = GameState::new(Fixtures::load(FIXTURES).unwrap(), "account").with_character("cook");
+    let server = MockServer::start(state).unwrap();
+    let (client, bot) = bot(&server, "trips");
+    let char = bot.account.get_character(0).unwrap();
+    let chickens = client.maps.with_content_code("chicken");
+
+    let near = char.round_trip_time(&chickens).unwrap();
+    char.send_cmd(CharacterCommand::Move {
+        layer: MapLayer::Overworld,
+        x: 7,
+        y: 13,
+    })
+    .unwrap();
+    assert!(char.step());
+    let far = char.round_trip_time(&chickens).unwrap();
+    assert!(near < far, "{near}s from the origin, {far}s from afar");
+    assert_eq!(char.round_trip_time(&chickens), Some(far));
+}
+
+/// Starts a bot whose only character has a task against a monster too
+/// strong for it, with the coins to cancel it.
+fn bot_with_strong_task_monster(tag: &str, deadline: u32) -> (MockServer, Bot) {
+    let fixtures = Fixtures::load(FIXTURES).unwrap();
+    let state = GameState::new(fixtures, "account")
+        .with_character("cook")
+        .with_task("cook", TASK_MONSTER)
+        .with_bank_item("tasks_coin", 10);
+    let server = MockServer::start(state).unwrap();
+    let (_, bot) = bot_with_config(
+        &server,
+        tag,
+        &format!(
+            r#"
+            order_gear = true
+            gear_roadmap_deadline = {deadline}
+
+            [[characters]]
+            skills = ["combat"]
+            "#
+        ),
+    );
+    (server, bot)
+}
+
+fn order(bot: &Bot, purpose: Purpose) {
+    bot.order_board
+        .add("copper_helmet", 1, None, purpose)
+        .unwrap();
+}
+
+fn roadmap_to_task_monster() -> Purpose {
+    Purpose::Roadmap {
+        char: "cook".into(),
+        monster: TASK_MONSTER.to_owned(),
+    }
+}
+
+fn task(server: &MockServer) -> String {
+    server.state().character("cook").unwrap().task.clone()
+}
+
+#[test]
+fn task_waits_for_the_gear_roadmap_to_its_monster() {
+    let (server, bot) = bot_with_strong_task_monster("roadmap_wait", 120);
+    let char = bot.account.get_character(0).unwrap();
+    order(&bot, roadmap_to_task_monster());
+
+    assert!(!char.step());
+    assert_eq!(
+        bot.order_board
+            .roadmap_orders(&"cook".into(), TASK_MONSTER)
+            .len(),
+        1
+    );
+    assert_eq!(task(&server), TASK_MONSTER);
+}
+
+#[test]
+fn task_is_cancelled_once_its_gear_roadmap_is_late() {
+    let (server, bot) = bot_with_strong_task_monster("roadmap_late", 0);
+    let char = bot.account.get_character(0).unwrap();
+    order(&bot, roadmap_to_task_monster());
+
+    assert!(char.step());
+    assert!(bot.order_board.orders().is_empty());
+    assert!(task(&server).is_empty());
+}
+
+#[test]
+fn gear_ordered_for_anything_else_does_not_hold_the_task_back() {
+    let (server, bot) = bot_with_strong_task_monster("roadmap_other", 120);
+    let char = bot.account.get_character(0).unwrap();
+    let account = bot.account.clone();
+    let roadmap = bot
+        .gear_finder
+        .roadmap_to(bot.client.monsters.get(TASK_MONSTER).unwrap())
+        .with_available_items(char.available_items())
+        .with_skill_levels(char.skill_levels())
+        .with_cost(move |code| account.time_to_get(code))
+        .with_confidence(0.95)
+        .plan();
+    order(
+        &bot,
+        Purpose::Gear {
+            char: "cook".into(),
+            item: "copper_helmet".to_owned(),
+        },
+    );
+
+    // No roadmap can be ordered either, so the task is given up right away.
+    assert!(roadmap.is_none());
+    assert!(char.step());
+    assert!(
+        bot.order_board
+            .roadmap_orders(&"cook".into(), TASK_MONSTER)
+            .is_empty()
+    );
+    assert_eq!(bot.order_board.orders().len(), 1);
+    assert!(task(&server).is_empty());
+}
+
+/// Starts a bot mining with an inventory of `max_items` next to the iron
+/// rocks, the copper rocks being farther but faster to gather, and returns
+/// the source it picks to get topaz along with the round trips to both rocks.
+fn topaz_source(tag: &str, max_items: i32) -> (String, u32, u32) {
+    let state = GameState::new(Fixtures::load(FIXTURES).unwrap(), "account")
+        .with_character("cook")
+        .with_character_update("cook", |c| {
+            c.mining_level = 10;
+            c.inventory_max_items = max_items;
+        });
+    let server = MockServer::start(state).unwrap();
+    let (client, bot) = bot_with_config(
+        &server,
+        tag,
+        r#"
+        [[characters]]
+        skills = ["mining"]
+        "#,
+    );
+    let char = bot.account.get_character(0).unwrap();
+    char.send_cmd(CharacterCommand::Move {
+        layer: MapLayer::Overworld,
+        x: 1,
+        y: 7,
+    })
+    .unwrap();
+    assert!(char.step());
+    let trip = |code| {
+        char.round_trip_time(&client.maps.with_content_code(code))
+            .unwrap()
+    };
+    let Some(ItemSource::Resource(resource)) = char.best_source_of("topaz_stone") else {
+        panic!("topaz is gathered");
+    };
+    (
+        resource.code().to_owned(),
+        trip("iron_rocks"),
+        trip("copper_rocks"),
+    )
+}
+
+#[test]
+fn closer_source_wins_over_faster_distant_one() {
+    let (source, iron, copper) = topaz_source("closer_source", 1);
+
+    assert!(iron < copper);
+    assert_eq!(source, "iron_rocks");
+}
+
+#[test]
+fn faster_source_wins_when_trips_are_alike() {
+    let (source, iron, copper) = topaz_source("faster_source", 100);
+
+    assert!(iron < copper);
+    assert_eq!(source, "copper_rocks");
+}
+
+/// Starts a bot mining at level 10 and weaponcrafting at level 1.
+fn miner(tag: &str) -> (MockServer, Bot) {
+    let state = GameState::new(Fixtures::load(FIXTURES).unwrap(), "account")
+        .with_character("cook")
+        .with_character_update("cook", |c| c.mining_level = 10);
+    let server = MockServer::start(state).unwrap();
+    let (_, bot) = bot_with_config(
+        &server,
+        tag,
+        r#"
+        [[characters]]
+        skills = ["mining", "weaponcrafting"]
+        "#,
+    );
+    (server, bot)
+}
+
+#[test]
+fn higher_xp_resource_beats_a_closer_one() {
+    let (_server, bot) = miner("leveling_gather");
+    let char = bot.account.get_character(0).unwrap();
+    let options = bot.leveling_helper.leveling_options(Skill::Mining, &char);
+    let gather = |code: &str| {
+        options
+            .iter()
+            .find(|o| matches!(&o.action, LevelingAction::Gather(r) if r.code() == code))
+            .unwrap()
+    };
+
+    assert!(gather("copper_rocks").time < gather("iron_rocks").time);
+    assert_eq!(options[0], *gather("iron_rocks"));
+}
+
+#[test]
+fn crafts_with_unobtainable_mats_are_left_out() {
+    let (_server, bot) = miner("leveling_craft");
+    let char = bot.account.get_character(0).unwrap();
+    let crafts = bot
+        .leveling_helper
+        .best_crafts(1, Skill::Weaponcrafting, &char)
+        .into_iter()
+        .filter_map(|o| match o.action {
+            LevelingAction::Craft { item, .. } => Some(item.code().to_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert!(bot.account.time_to_get("wooden_staff").is_none());
+    assert!(!crafts.contains(&"wooden_staff".to_owned()));
+    assert!(crafts.contains(&"copper_dagger".to_owned()));
+}
+
+#[test]
+fn gear_reserved_in_the_bank_is_allocated_to_its_owner() {
+    let state = GameState::new(Fixtures::load(FIXTURES).unwrap(), "account")
+        .with_character("cook")
+        .with_bank_item("copper_dagger", 1);
+    let server = MockServer::start(state).unwrap();
+    let (client, bot) = bot(&server, "reserved_gear");
+    let char = bot.account.get_character(0).unwrap();
+    bot.account
+        .bank
+        .reserve(("copper_dagger", char.name()), 1)
+        .unwrap();
+    char.set_purpose(GearPurpose::Combat(client.monsters.get("chicken").unwrap()));
+
+    let gear = bot
+        .account
+        .allocated_gear(&char.name(), &bot.gear_finder)
+        .unwrap();
+    assert_eq!(gear.weapon.unwrap().code(), "copper_dagger");
+}
@@ -9,9 +9,10 @@ use bot::{
 use clap::{Parser, Subcommand, value_parser};
 use rustyline::{DefaultEditor, error::ReadlineError};
 use sdk::{
-    Code, CollectionClient, ItemContainer, Level, Quantity,
+    Code, CollectionClient, CraftPlanner, ItemContainer, Level, Quantity,
     entities::Character,
-    simulator::{FightSimulation, Participant},
+    models::MapLayer,
+    simulator::{FightParams, FightSimulation, Participant, calibration::Tolerance},
     skill::Skill,
 };
 use std::process::exit;
@@ -56,8 +57,21 @@ pub fn run(bot: &Bot) -> Result<()> {
 fn respond(line: &str, bot: &Bot, character: &mut Option<CharacterController>) -> Result<()> {
     match Cli::try_parse_from(line.split_whitespace())?.command {
         Commands::Orderboard { action } => match action {
-            OrderboardAction::Add { item, quantity } => {
+            OrderboardAction::Add {
+                item,
+                quantity,
+                plan,
+            } => {
                 bot.order_board.add(&item, quantity, None, Purpose::Cli)?;
+                if plan {
+                    let Some(plan) = CraftPlanner::new(bot.client.items.clone())
+                        .with_stock(bot.account.available_items())
+                        .plan(&item, quantity)
+                    else {
+                        bail!("item not found");
+                    };
+                    bot.order_board.add_plan(&plan, None, &Purpose::Cli)?;
+                }
             }
             OrderboardAction::Remove { item } => {
                 let Some(o) = bot.order_board.get(&item, None, &Purpose::Cli) else {
@@ -113,6 +127,33 @@ fn respond(line: &str, bot: &Bot, character: &mut Option<CharacterController>) -
                         .for_each(|s| println!("{s}"));
                 }
             }
+            ItemsAction::Plan {
+                item,
+                quantity,
+                recycle,
+                json,
+            } => {
+                let stock = character.as_ref().map_or_else(
+                    || bot.account.available_items(),
+                    CharacterController::bank_and_inventory_items,
+                );
+                let recyclable = recycle
+                    .into_iter()
+                    .filter_map(|code| Some((code.clone(), *stock.get(&code)?)))
+                    .collect();
+                let Some(plan) = CraftPlanner::new(bot.client.items.clone())
+                    .with_stock(stock)
+                    .with_recyclable(&recyclable)
+                    .plan(&item, quantity)
+                else {
+                    bail!("item not found");
+                };
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&plan)?);
+                } else {
+                    print!("{plan}");
+                }
+            }
             ItemsAction::BestCraft { skill } => {
                 let Some(char) = character else {
                     bail!("no character selected");
@@ -131,9 +172,9 @@ fn respond(line: &str, bot: &Bot, character: &mut Option<CharacterController>) -
                 };
                 println!("best {skill} crafts:");
                 bot.leveling_helper
-                    .best_crafts(char.skill_level(skill), skill)
+                    .best_crafts(char.skill_level(skill), skill, char)
                     .iter()
-                    .for_each(|i| println!("{}", i.name()));
+                    .for_each(|o| println!("{o}"));
             }
         },
         Commands::Events { action } => match action {
@@ -148,6 +189,18 @@ fn respond(line: &str, bot: &Bot, character: &mut Option<CharacterController>) -
                     .for_each(|e| println!("{e}"));
             }
         },
+        Commands::Logs { character, sync } => {
+            if sync {
+                println!("{} new logs", bot.client.logs.sync()?);
+            }
+            let logs = character.map_or_else(
+                || bot.client.logs.all(),
+                |character| bot.client.logs.of(&character),
+            );
+            for l in logs {
+                println!("[{}] {}: {}", l.created_at, l.character, l.description);
+            }
+        }
         Commands::Char { i } => {
             character.clone_from(&bot.account.get_character(i as usize));
             if let Some(char) = character.clone() {
@@ -165,12 +218,33 @@ fn respond(line: &str, bot: &Bot, character: &mut Option<CharacterController>) -
             };
             char.toggle_idle();
         }
+        Commands::Move { x, y, layer } => {
+            let Some(char) = character else {
+                bail!("no character selected");
+            };
+            char.send_cmd(CharacterCommand::Move { layer, x, y })?;
+        }
         Commands::Craft { item, quantity } => {
             let Some(char) = character else {
                 bail!("no character selected");
             };
             char.send_cmd(CharacterCommand::Craft { item, quantity })?;
         }
+        Commands::Boss {
+            monster,
+            participants,
+        } => {
+            let Some(char) = character else {
+                bail!("no character selected");
+            };
+            let Some(monster) = bot.client.monsters.get(&monster) else {
+                bail!("monster not found");
+            };
+            char.send_cmd(CharacterCommand::KillBoss {
+                monster,
+                participants: participants.into_iter().map(Into::into).collect(),
+            })?;
+        }
         Commands::Recycle { item, quantity } => {
             let Some(char) = character else {
                 bail!("no character selected");
@@ -191,6 +265,7 @@ fn respond(line: &str, bot: &Bot, character: &mut Option<CharacterController>) -
             from_npc,
             utilities,
             winning: _,
+            explain,
             entity,
         } => {
             let Some(char) = character else {
@@ -217,16 +292,19 @@ fn respond(line: &str, bot: &Bot, character: &mut Option<CharacterController>) -
                 bail!("entity not found")
             };
             let account = bot.account.clone();
-            let gear = bot
+            let resolver = bot
                 .gear_finder
                 .best_for(purpose)
                 .with_excluded_items(bot.config.excluded_items())
                 .with_available_items(char.available_items())
                 .with_skill_levels(char.skill_levels())
                 .with_filter(filter)
-                .with_can_craft(move |code| account.can_craft(code))
-                .resolve();
-            if let Some(gear) = gear {
+                .with_can_craft(move |code| account.can_craft(code));
+            if let Some(top) = explain {
+                print!("{}", resolver.explain(top));
+                return Ok(());
+            }
+            if let Some(gear) = resolver.resolve() {
                 println!("{gear}");
             } else {
                 println!("no gear found");
@@ -240,6 +318,12 @@ fn respond(line: &str, bot: &Bot, character: &mut Option<CharacterController>) -
             from_monster,
             utilities,
             winning: _,
+            trace,
+            json,
+            ignore_death,
+            no_utilities,
+            hp,
+            monster_hp,
             monster,
         } => {
             let Some(char) = character else {
@@ -269,20 +353,72 @@ fn respond(line: &str, bot: &Bot, character: &mut Option<CharacterController>) -
                 .with_filter(filter)
                 .with_can_craft(move |code| account.can_craft(code))
                 .resolve();
-            if let Some(gear) = gear {
-                println!("{gear}");
-                #[allow(clippy::redundant_clone)]
-                let sim = FightSimulation::new(
-                    Participant::new(char.name())
-                        .with_level(char.level())
-                        .with_gear(gear),
-                    monster.clone(),
-                );
-                println!("{:?}", sim.run());
-            } else {
+            // Fights ignoring death measure damage throughput, so they fall back
+            // to the current gear against monsters no gear can beat yet.
+            let Some(gear) = gear.or_else(|| ignore_death.then(|| char.gear())) else {
                 println!("no winning gear found");
+                return Ok(());
+            };
+            println!("{gear}");
+            let sim = FightSimulation::new(
+                Participant::new(char.name())
+                    .with_level(char.level())
+                    .with_gear(gear),
+                monster,
+            );
+            let mut params = FightParams::default();
+            if trace || json {
+                params = params.with_trace();
+            }
+            if ignore_death {
+                params = params.ignore_death();
+            }
+            if no_utilities {
+                params = params.without_utilities();
+            }
+            if let Some(hp) = hp {
+                params = params.with_hp(hp);
+            }
+            if let Some(hp) = monster_hp {
+                params = params.with_monster_hp(hp);
+            }
+            let mut report = sim.with_params(params).run();
+            match report.trace.take() {
+                Some(trace) if json => println!("{}", serde_json::to_string_pretty(&trace)?),
+                Some(trace) => print!("{trace}"),
+                None => {}
+            }
+            println!("{report:?}");
+            if ignore_death {
+                println!(
+                    "{:.1} damage per turn",
+                    f64::from(report.dmg_dealt) / f64::from(report.turns - 1)
+                );
             }
         }
+        Commands::Calibrate {
+            monster,
+            iterations,
+        } => {
+            let Some(char) = character else {
+                bail!("no character selected");
+            };
+            let Some(monster) = bot.client.monsters.get(&monster) else {
+                bail!("monster not found");
+            };
+            let sim = FightSimulation::new(
+                Participant::new(char.name())
+                    .with_level(char.level())
+                    .with_gear(char.gear()),
+                monster,
+            );
+            let report = bot.client.simulation.calibrate(&sim, iterations)?;
+            print!("{report}");
+            report
+                .discrepancies(&Tolerance::default())
+                .iter()
+                .for_each(|d| println!("discrepancy: {d}"));
+        }
         Commands::Deposit { item, quantity } => {
             let Some(char) = character else {
                 bail!("no character selected");
@@ -404,6 +540,11 @@ enum Commands {
         #[command(subcommand)]
         action: EventsAction,
     },
+    Logs {
+        character: Option<String>,
+        #[arg(short = 's', long)]
+        sync: bool,
+    },
     Char {
         #[arg(value_parser = value_parser!(u32), default_value = "0")]
         i: u32,
@@ -415,11 +556,25 @@ enum Commands {
     Pause,
     Resume,
     Cancel,
+    Move {
+        #[arg(allow_negative_numbers = true)]
+        x: i32,
+        #[arg(allow_negative_numbers = true)]
+        y: i32,
+        #[arg(short, long, default_value = "overworld", value_parser = parse_layer)]
+        layer: MapLayer,
+    },
     Craft {
         item: String,
         #[arg(default_value_t = 1)]
         quantity: u32,
     },
+    /// Fight the given boss with the selected character leading the given participants.
+    Boss {
+        monster: String,
+        #[arg(num_args = 2, required = true)]
+        participants: Vec<String>,
+    },
     Recycle {
         item: String,
         #[arg(default_value_t = 1)]
@@ -449,6 +604,9 @@ enum Commands {
         utilities: bool,
         #[arg(short = 'w', long)]
         winning: bool,
+        /// Explain how the gear was resolved, listing the given number of best loadouts.
+        #[arg(short = 'x', long, num_args = 0..=1, default_missing_value = "5")]
+        explain: Option<usize>,
         entity: String,
     },
     #[command(alias = "sim")]
@@ -467,7 +625,30 @@ enum Commands {
         utilities: bool,
         #[arg(short = 'w', long)]
         winning: bool,
+        /// Print the turn by turn trace of the fight.
+        #[arg(long)]
+        trace: bool,
+        /// Print the trace as JSON.
+        #[arg(long)]
+        json: bool,
+        /// Keep fighting for every turn to measure the damage dealt.
+        #[arg(long)]
+        ignore_death: bool,
+        /// Fight without utilities.
+        #[arg(long)]
+        no_utilities: bool,
+        /// HP the character starts the fight with.
+        #[arg(long)]
+        hp: Option<i32>,
+        /// HP the monster starts the fight with.
+        #[arg(long)]
+        monster_hp: Option<i32>,
+        monster: String,
+    },
+    Calibrate {
         monster: String,
+        #[arg(default_value_t = 100)]
+        iterations: u32,
     },
     Deposit {
         item: String,
@@ -495,6 +676,9 @@ enum OrderboardAction {
         item: String,
         #[arg(default_value_t = 1)]
         quantity: u32,
+        /// Also order the missing materials, down to the raw ones.
+        #[arg(short = 'p', long)]
+        plan: bool,
     },
     #[command(alias = "rm")]
     Remove { item: String },
@@ -520,6 +704,17 @@ enum ItemsAction {
     TimeToGet {
         item: String,
     },
+    /// Prints the craft tree and jobs required to get the item.
+    Plan {
+        item: String,
+        #[arg(default_value_t = 1)]
+        quantity: u32,
+        /// Item to recycle for materials, can be repeated.
+        #[arg(short = 'r', long)]
+        recycle: Vec<String>,
+        #[arg(short = 'j', long)]
+        json: bool,
+    },
     BestCraft {
         skill: Skill,
     },
@@ -555,3 +750,12 @@ enum SkillAction {
     #[command(alias = "l")]
     List,
 }
+
+fn parse_layer(layer: &str) -> Result<MapLayer, String> {
+    match layer {
+        "overworld" => Ok(MapLayer::Overworld),
+        "underground" => Ok(MapLayer::Underground),
+        "interior" => Ok(MapLayer::Interior),
+        _ => Err(format!("unknown layer: {layer}")),
+    }
+}
@@ -1,20 +1,28 @@
 use anyhow::Result;
-use bot::Bot;
+use bot::{Bot, bot_config};
+use clap::Parser;
 use log::LevelFilter;
-use sdk::Client;
-use std::env;
 
 mod cli;
 
+#[derive(Parser)]
+struct Args {
+    /// Loads the game data and the characters from the cache only, without
+    /// token nor network. Character actions are unavailable.
+    #[arg(long)]
+    offline: bool,
+}
+
 fn main() -> Result<()> {
+    let args = Args::parse();
     simple_logging::log_to_file("artifactsmmo.log", LevelFilter::Info)?;
-    let client = Client::new(
-        "https://api.artifactsmmo.com".into(),
-        env::var("ARTIFACTSMMO_TOKEN").unwrap_or_default(),
-        ".cache",
-    );
-    client.init();
-    let bot = Bot::new(client);
+    let builder = bot_config::client_builder()?;
+    if args.offline {
+        let bot = Bot::new(builder.build_offline()?);
+        bot.init_characters();
+        return cli::run(&bot);
+    }
+    let bot = Bot::new(builder.build()?);
     // bot.order_board
     //     .add("lizard_skin", 1000, None, Purpose::Cli)?;
     // bot.order_board
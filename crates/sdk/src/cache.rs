@@ -0,0 +1,367 @@
+use crate::{CACHE_DIR, CACHE_TTL, ClientError};
+use arc_swap::ArcSwapOption;
+use chrono::{DateTime, TimeDelta, Utc};
+use log::{error, warn};
+use openapi::models::StatusSchema;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::Path,
+    process,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+/// Version of the envelope layout, bumping it invalidates every cache file.
+const FORMAT: u32 = 1;
+
+/// Location and invalidation policy of the cache files, shared by the clients.
+#[derive(Clone)]
+pub struct CacheConfig(Arc<CacheConfigInner>);
+
+struct CacheConfigInner {
+    dir: Box<str>,
+    ttl: TimeDelta,
+    server: ArcSwapOption<ServerVersion>,
+}
+
+/// Version of the game data served by the API.
+#[derive(Debug, PartialEq, Eq)]
+struct ServerVersion {
+    version: String,
+    season: Option<i32>,
+}
+
+impl CacheConfig {
+    #[must_use]
+    pub fn new(dir: &str, ttl: TimeDelta) -> Self {
+        Self(Arc::new(CacheConfigInner {
+            dir: dir.into(),
+            ttl,
+            server: ArcSwapOption::empty(),
+        }))
+    }
+
+    #[must_use]
+    pub fn dir(&self) -> &str {
+        &self.0.dir
+    }
+
+    #[must_use]
+    pub fn ttl(&self) -> TimeDelta {
+        self.0.ttl
+    }
+
+    /// Records the version and season of the server. Files fetched from
+    /// another version or season are considered stale from then on.
+    pub(crate) fn set_server(&self, status: &StatusSchema) {
+        self.0.server.store(Some(Arc::new(ServerVersion {
+            version: status.version.clone(),
+            season: status.season.as_ref().and_then(|s| s.number),
+        })));
+    }
+
+    fn path(&self, file: &str) -> String {
+        format!("{}/{file}.ron", self.dir())
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self::new(CACHE_DIR, CACHE_TTL)
+    }
+}
+
+/// Content of a cache file: the data and what is needed to tell whether it
+/// is still valid.
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    format: u32,
+    fetched_at: DateTime<Utc>,
+    server_version: Option<String>,
+    season: Option<i32>,
+    schema: u32,
+    data: T,
+}
+
+impl<T> Envelope<T> {
+    fn new(config: &CacheConfig, schema: u32, data: T) -> Self {
+        let server = config.0.server.load();
+        Self {
+            format: FORMAT,
+            fetched_at: Utc::now(),
+            server_version: server.as_ref().map(|s| s.version.clone()),
+            season: server.as_ref().and_then(|s| s.season),
+            schema,
+            data,
+        }
+    }
+
+    /// Returns whether the data was written with the `schema` version of its
+    /// layout, is younger than the TTL and comes from the current server
+    /// version and season. The version is not checked until the server status
+    /// is known.
+    fn is_fresh(&self, config: &CacheConfig, schema: u32) -> bool {
+        let same_server = config.0.server.load().as_ref().is_none_or(|s| {
+            self.server_version.as_ref() == Some(&s.version) && self.season == s.season
+        });
+        self.format == FORMAT
+            && self.schema == schema
+            && Utc::now() - self.fetched_at < config.ttl()
+            && same_server
+    }
+}
+
+/// Data read from a cache file.
+pub struct CacheEntry<D> {
+    pub data: D,
+    /// Whether the data can be used without fetching it again.
+    pub fresh: bool,
+}
+
+pub trait Cached<D>
+where
+    D: for<'a> Deserialize<'a> + Serialize,
+{
+    const FILE: &'static str;
+    /// Version of the layout of `D`, to bump whenever a field of the cached
+    /// models is added, removed or renamed so that older files are refetched.
+    const SCHEMA_VERSION: u32;
+
+    fn cache_config(&self) -> &CacheConfig;
+
+    /// Returns cached data while it is fresh and data from `fetch_from_source`
+    /// otherwise. Stale data is used when the source is unreachable.
+    fn fetch(&self) -> Result<D, ClientError> {
+        let stale = match self.fetch_from_cache() {
+            Ok(entry) if entry.fresh => return Ok(entry.data),
+            Ok(entry) => Some(entry.data),
+            Err(_) => None,
+        };
+        match (self.fetch_and_cache(), stale) {
+            (Ok(data), _) => Ok(data),
+            (Err(e), Some(data)) => {
+                warn!("using stale {} cache: {e}", Self::FILE);
+                Ok(data)
+            }
+            (Err(e), None) => Err(e),
+        }
+    }
+
+    /// Returns data from `fetch_from_source` and writes it to the local cache
+    fn fetch_and_cache(&self) -> Result<D, ClientError> {
+        let data = self.fetch_from_source()?;
+        if let Err(e) = self.cache(&data) {
+            error!("failed to cache data: {e}");
+        }
+        Ok(data)
+    }
+
+    /// Reads and deserializes data from the local cache file. Files written
+    /// without envelope are read as stale.
+    fn fetch_from_cache(&self) -> anyhow::Result<CacheEntry<D>> {
+        read(self.cache_config(), Self::FILE, Self::SCHEMA_VERSION)
+    }
+
+    /// Writes data to the local cache file
+    fn cache(&self, data: &D) -> anyhow::Result<()> {
+        let envelope = Envelope::new(self.cache_config(), Self::SCHEMA_VERSION, data);
+        Ok(write_atomic(
+            &self.cache_path(),
+            &ron::ser::to_string_pretty(&envelope, PrettyConfig::default())?,
+        )?)
+    }
+
+    fn cache_path(&self) -> String {
+        self.cache_config().path(Self::FILE)
+    }
+
+    /// Returns data from the source of truth (e.g., the `ArtifactMMO` API)
+    fn fetch_from_source(&self) -> Result<D, ClientError>;
+
+    /// Updates the data and the local cache from the source of truth. The
+    /// current data is kept when the source is unreachable.
+    fn refresh(&self) -> Result<(), ClientError>;
+}
+
+/// Reads the cache `file` of `config` written with the `schema` version of
+/// its layout, see [`Cached::fetch_from_cache`].
+pub fn read<D: for<'a> Deserialize<'a>>(
+    config: &CacheConfig,
+    file: &str,
+    schema: u32,
+) -> anyhow::Result<CacheEntry<D>> {
+    let content = fs::read_to_string(config.path(file))?;
+    if let Ok(envelope) = ron::from_str::<Envelope<D>>(&content) {
+        let fresh = envelope.is_fresh(config, schema);
+        return Ok(CacheEntry {
+            data: envelope.data,
+            fresh,
+        });
+    }
+    Ok(CacheEntry {
+        data: ron::from_str(&content)?,
+        fresh: false,
+    })
+}
+
+/// Writes `content` to a temporary file then renames it over `path`, so that
+/// a crash while writing never leaves a truncated cache file behind.
+fn write_atomic(path: &str, content: &str) -> io::Result<()> {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    if let Some(dir) = Path::new(path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = format!(
+        "{path}.{}.{}.tmp",
+        process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    );
+    let write = || {
+        let mut file = File::create(&tmp)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    };
+    write().inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        collections::HashMap,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    struct Numbers {
+        cache: CacheConfig,
+        fetched: AtomicUsize,
+        reachable: bool,
+    }
+
+    impl Numbers {
+        fn new(dir: &str, ttl: TimeDelta, reachable: bool) -> Self {
+            Self {
+                cache: CacheConfig::new(dir, ttl),
+                fetched: AtomicUsize::new(0),
+                reachable,
+            }
+        }
+    }
+
+    impl Cached<HashMap<String, u32>> for Numbers {
+        const FILE: &str = "numbers";
+        const SCHEMA_VERSION: u32 = 1;
+
+        fn cache_config(&self) -> &CacheConfig {
+            &self.cache
+        }
+
+        fn fetch_from_source(&self) -> Result<HashMap<String, u32>, ClientError> {
+            if !self.reachable {
+                return Err(ClientError::Unavailable("numbers"));
+            }
+            let fetched = self.fetched.fetch_add(1, Ordering::Relaxed) + 1;
+            Ok(HashMap::from([("fetched".to_owned(), fetched as u32)]))
+        }
+
+        fn refresh(&self) -> Result<(), ClientError> {
+            self.fetch_and_cache().map(|_| ())
+        }
+    }
+
+    fn temp_dir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("sdk_cache_{name}_{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.to_str().unwrap().to_owned()
+    }
+
+    fn status(version: &str, season: i32) -> StatusSchema {
+        let mut status =
+            StatusSchema::new(version.to_owned(), Utc::now().into(), 50, 50, 0, vec![]);
+        status.season = Some(openapi::models::SeasonSchema {
+            number: Some(season),
+            ..openapi::models::SeasonSchema::new(vec![])
+        });
+        status
+    }
+
+    #[test]
+    fn reads_fresh_cache_without_fetching() {
+        let dir = temp_dir("fresh");
+        let numbers = Numbers::new(&dir, TimeDelta::hours(1), true);
+
+        assert_eq!(numbers.fetch().unwrap()["fetched"], 1);
+        assert_eq!(numbers.fetch().unwrap()["fetched"], 1);
+        assert_eq!(
+            fs::read_dir(&dir).unwrap().count(),
+            1,
+            "temporary file left behind"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn refetches_expired_cache() {
+        let dir = temp_dir("expired");
+        let numbers = Numbers::new(&dir, TimeDelta::zero(), true);
+
+        assert_eq!(numbers.fetch().unwrap()["fetched"], 1);
+        assert_eq!(numbers.fetch().unwrap()["fetched"], 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn refetches_cache_of_another_season() {
+        let dir = temp_dir("season");
+        let numbers = Numbers::new(&dir, TimeDelta::hours(1), true);
+        numbers.cache.set_server(&status("7.0.0", 5));
+
+        assert_eq!(numbers.fetch().unwrap()["fetched"], 1);
+        numbers.cache.set_server(&status("7.0.0", 5));
+        assert_eq!(numbers.fetch().unwrap()["fetched"], 1);
+        numbers.cache.set_server(&status("7.0.0", 6));
+        assert_eq!(numbers.fetch().unwrap()["fetched"], 2);
+        numbers.cache.set_server(&status("7.1.0", 6));
+        assert_eq!(numbers.fetch().unwrap()["fetched"], 3);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn refetches_cache_of_another_schema() {
+        let dir = temp_dir("schema");
+        let numbers = Numbers::new(&dir, TimeDelta::hours(1), true);
+        let envelope = Envelope::new(
+            &numbers.cache,
+            Numbers::SCHEMA_VERSION + 1,
+            HashMap::from([("fetched".to_owned(), 0)]),
+        );
+        write_atomic(
+            &numbers.cache_path(),
+            &ron::ser::to_string(&envelope).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(numbers.fetch().unwrap()["fetched"], 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn falls_back_to_stale_cache() {
+        let dir = temp_dir("stale");
+        let numbers = Numbers::new(&dir, TimeDelta::zero(), false);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(numbers.cache_path(), "{\"fetched\": 0}").unwrap();
+
+        assert_eq!(numbers.fetch().unwrap()["fetched"], 0);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(numbers.fetch().is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    ClientError, Code, EventBus, ItemsClient, MapsClient, MonstersClient, NpcsClient,
-    ResourcesClient, ServerClient, TasksClient,
+    CacheConfig, Cached, ClientError, Code, EventBus, ItemsClient, MapsClient, MonstersClient,
+    NpcsClient, ResourcesClient, ServerClient, TasksClient,
     client::{
         bank::BankClient,
         character::{CharacterClient, CharacterRequestHandler},
@@ -14,7 +14,7 @@ use crate::{
 use arc_swap::ArcSwap;
 use derive_more::Deref;
 use itertools::Itertools;
-use log::info;
+use log::{info, warn};
 use openapi::models::{AccountAchievementSchema, CharacterSchema, PendingItemSchema};
 use std::{
     sync::{Arc, RwLock},
@@ -46,6 +46,7 @@ pub struct AccountClient(Arc<AccountClientInner>);
 /// Hold and manage data related to a specific account
 pub struct AccountClientInner {
     name: String,
+    cache: CacheConfig,
     bank: BankClient,
     characters: RwLock<Vec<CharacterClient>>,
     achievements: RwLock<Vec<AccountAchievement>>,
@@ -60,6 +61,7 @@ impl AccountClient {
     #[must_use]
     pub(crate) fn new(
         name: String,
+        cache: CacheConfig,
         bank: BankClient,
         fetch_characters: CharactersSource,
         fetch_achievements: AccountAchievementsSource,
@@ -68,6 +70,7 @@ impl AccountClient {
     ) -> Self {
         Self(Arc::new(AccountClientInner {
             name,
+            cache,
             bank,
             characters: RwLock::default(),
             achievements: RwLock::default(),
@@ -79,12 +82,14 @@ impl AccountClient {
         }))
     }
 
-    pub fn init(&self) {
-        let () = thread::scope(|s| {
-            let _ = s.spawn(|| self.load_achievements());
-            let _ = s.spawn(|| self.load_pending_items());
-        });
-        info!("Account achievements and pending items initilized");
+    pub fn init(&self) -> Result<(), ClientError> {
+        thread::scope(|s| {
+            let achievements = s.spawn(|| self.load_achievements());
+            self.load_pending_items()?;
+            achievements.join().unwrap()?;
+            info!("Account achievements and pending items initilized");
+            Ok(())
+        })
     }
 
     #[must_use]
@@ -97,6 +102,8 @@ impl AccountClient {
         self.bank.clone()
     }
 
+    /// Loads the characters of the account, from the last cached state when
+    /// the source is unreachable.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn load_characters(
         &self,
@@ -110,7 +117,14 @@ impl AccountClient {
         grand_exchange: &GrandExchangeClient,
         event_bus: &EventBus,
     ) -> Result<(), ClientError> {
-        *self.characters.write().unwrap() = (self.fetch_characters)(self.name())?
+        let schemas = match self.fetch_and_cache() {
+            Ok(schemas) => schemas,
+            Err(e) => {
+                warn!("using cached characters: {e}");
+                self.fetch_from_cache().map_err(|_| e)?.data
+            }
+        };
+        *self.characters.write().unwrap() = schemas
             .into_iter()
             .enumerate()
             .map(|(id, schema)| {
@@ -215,3 +229,20 @@ impl AccountClient {
         pending.store(RawPendingItem::new(item));
     }
 }
+
+impl Cached<Vec<CharacterSchema>> for AccountClient {
+    const FILE: &str = "characters";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn cache_config(&self) -> &CacheConfig {
+        &self.cache
+    }
+
+    fn fetch_from_source(&self) -> Result<Vec<CharacterSchema>, ClientError> {
+        (self.fetch_characters)(self.name())
+    }
+
+    fn refresh(&self) -> Result<(), ClientError> {
+        self.fetch_and_cache().map(|_| ())
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
-    BANK_EXPANSION_SIZE, Code, HasDropTable, ItemContainer, LimitedContainer, Quantity, SlotLimited,
+    BANK_EXPANSION_SIZE, ClientError, Code, HasDropTable, ItemContainer, LimitedContainer,
+    Quantity, SlotLimited,
 };
 use arc_swap::ArcSwap;
 use derive_more::Deref;
@@ -7,8 +8,9 @@ use log::info;
 use openapi::models::{BankSchema, SimpleItemSchema};
 use std::{sync::Arc, thread, vec::Vec};
 
-type BankContentSource = Box<dyn Fn() -> Vec<SimpleItemSchema> + Send + Sync + 'static>;
-type BankDetailsSource = Box<dyn Fn() -> BankSchema + Send + Sync + 'static>;
+type BankContentSource =
+    Box<dyn Fn() -> Result<Vec<SimpleItemSchema>, ClientError> + Send + Sync + 'static>;
+type BankDetailsSource = Box<dyn Fn() -> Result<BankSchema, ClientError> + Send + Sync + 'static>;
 
 #[derive(Clone, Default, Deref)]
 #[deref(forward)]
@@ -32,12 +34,14 @@ impl BankClient {
         }))
     }
 
-    pub(crate) fn init(&self) {
-        let () = thread::scope(|s| {
-            let _ = s.spawn(|| self.set_details((self.fetch_details)()));
-            let _ = s.spawn(|| self.set_content((self.fetch_content)()));
-        });
-        info!("Bank client initilized");
+    pub(crate) fn init(&self) -> Result<(), ClientError> {
+        thread::scope(|s| {
+            let details = s.spawn(|| (self.fetch_details)());
+            self.set_content((self.fetch_content)()?);
+            self.set_details(details.join().unwrap()?);
+            info!("Bank client initilized");
+            Ok(())
+        })
     }
 
     pub fn set_gold(&self, gold: u32) {
@@ -67,8 +71,8 @@ impl Default for BankClientInner {
         Self {
             details: ArcSwap::default(),
             content: ArcSwap::default(),
-            fetch_details: Box::new(BankSchema::default),
-            fetch_content: Box::new(Vec::new),
+            fetch_details: Box::new(|| Ok(BankSchema::default())),
+            fetch_content: Box::new(|| Ok(vec![])),
         }
     }
 }
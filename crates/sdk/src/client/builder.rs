@@ -0,0 +1,121 @@
+use crate::{
+    API_URL, CACHE_DIR, CACHE_TTL, CacheConfig, Client, InitError, Subsystem,
+    client::{api_error, cached_account_name},
+};
+use api::ArtifactApi;
+use chrono::TimeDelta;
+use log::warn;
+use openapi::apis::Error;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Builds and initializes a [`Client`].
+///
+/// The builder can be deserialized, so that the API URL, the token, the
+/// cache directory and the cache TTL (in seconds) can be read from a
+/// configuration file.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ClientBuilder {
+    url: String,
+    token: String,
+    cache_dir: String,
+    cache_ttl: u64,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self {
+            url: API_URL.to_owned(),
+            token: String::new(),
+            cache_dir: CACHE_DIR.to_owned(),
+            cache_ttl: CACHE_TTL.num_seconds().unsigned_abs(),
+        }
+    }
+}
+
+impl ClientBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    #[must_use]
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = token.into();
+        self
+    }
+
+    #[must_use]
+    pub fn cache_dir(mut self, cache_dir: impl Into<String>) -> Self {
+        self.cache_dir = cache_dir.into();
+        self
+    }
+
+    /// Sets how long cached game data is used before being fetched again.
+    #[must_use]
+    pub const fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl.as_secs();
+        self
+    }
+
+    /// Connects to the API and initializes every client. Game data is read
+    /// from the cache directory while fresh and fetched otherwise.
+    ///
+    /// When the API is unreachable, the account, the server status, the
+    /// characters and the game data are read from the cache directory, the
+    /// bank, the achievements and the pending items being left empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`Subsystem`] that could not be initialized.
+    pub fn build(self) -> Result<Client, InitError> {
+        let api = ArtifactApi::new(self.url, self.token);
+        let ttl = i64::try_from(self.cache_ttl)
+            .ok()
+            .and_then(TimeDelta::try_seconds)
+            .unwrap_or(TimeDelta::MAX);
+        let cache = CacheConfig::new(&self.cache_dir, ttl);
+        match api.account.details() {
+            Ok(details) => {
+                let client = Client::with_api(api, details.username, cache);
+                client.init()?;
+                Ok(client)
+            }
+            Err(e) if is_unreachable(&e) => {
+                let Some(account_name) = cached_account_name(&cache) else {
+                    return Err(InitError::new(Subsystem::Account, api_error(e)));
+                };
+                warn!("using cached account '{account_name}': {e}");
+                let client = Client::with_api(api, account_name, cache);
+                client.init_unreachable()?;
+                Ok(client)
+            }
+            Err(e) => Err(InitError::new(Subsystem::Account, api_error(e))),
+        }
+    }
+
+    /// Builds a client from the cache directory only, see
+    /// [`Client::from_cache`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`Subsystem`] whose cache could not be read.
+    pub fn build_offline(self) -> Result<Client, InitError> {
+        Client::from_cache(&self.cache_dir)
+    }
+}
+
+/// Returns whether the request failed without reaching the API.
+const fn is_unreachable<T>(error: &Error<T>) -> bool {
+    matches!(
+        error,
+        Error::Reqwest(_) | Error::ReqwestMiddleware(_) | Error::Io(_)
+    )
+}
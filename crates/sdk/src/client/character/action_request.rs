@@ -13,7 +13,7 @@ pub enum ActionRequest<'a> {
     },
     Transition,
     Fight {
-        participants: Option<&'a [String; 2]>,
+        participants: Option<&'a [String]>,
     },
     Rest,
     Gather,
@@ -55,6 +55,8 @@ pub enum RequestError {
     DowncastError,
     #[error("request canceled")]
     Canceled,
+    #[error("client is offline")]
+    Offline,
 }
 
 impl<T> From<Error<T>> for RequestError {
@@ -24,7 +24,7 @@ pub trait CharacterRequestHandler: Send + Sync {
     fn request_transition(&self) -> Result<RawMap, RequestError>;
     fn request_fight(
         &self,
-        participants: Option<&[String; 2]>,
+        participants: Option<&[String]>,
     ) -> Result<CharacterFightSchema, RequestError>;
     fn request_rest(&self) -> Result<u32, RequestError>;
     fn request_gather(&self) -> Result<SkillInfoSchema, RequestError>;
@@ -20,6 +20,7 @@ use crate::{
         monsters::MonstersClient,
         npcs::NpcsClient,
         resources::ResourcesClient,
+        route::Route,
     },
     entities::{
         AccountAchievement, Character, CharacterHandle, CharacterName, Item, Map, PendingItem,
@@ -50,6 +51,7 @@ pub use handler::CharacterRequestHandler;
 pub use inventory::{Inventory, InventoryClient};
 
 pub mod handler;
+pub(crate) mod offline_handler;
 pub(crate) mod request_handler;
 
 pub mod action_request;
@@ -178,6 +180,23 @@ impl CharacterClient {
         Ok(())
     }
 
+    /// Plans the moves and transitions leading to `destination` through the
+    /// maps and transitions whose conditions the character meets.
+    #[must_use]
+    pub fn route_to(&self, destination: (MapLayer, i32, i32)) -> Option<Route> {
+        self.maps.route(self.position(), destination, |e| {
+            self.meets_conditions_for(e)
+        })
+    }
+
+    /// Returns the map of `maps` the character can reach with the shortest
+    /// route, along with the route.
+    #[must_use]
+    pub fn closest_among(&self, maps: &[RawMap]) -> Option<(RawMap, Route)> {
+        self.maps
+            .closest_from_among(self.position(), maps, |e| self.meets_conditions_for(e))
+    }
+
     pub fn transition(&self) -> Result<RawMap, TransitionError> {
         self.can_transition()?;
         Ok(self.handler().request_transition()?)
@@ -196,13 +215,13 @@ impl CharacterClient {
 
     pub fn fight(
         &self,
-        participants: Option<&[String; 2]>,
+        participants: Option<&[String]>,
     ) -> Result<CharacterFightSchema, FightError> {
         self.can_fight(participants)?;
         Ok(self.handler().request_fight(participants)?)
     }
 
-    pub fn can_fight(&self, participants: Option<&[String; 2]>) -> Result<(), FightError> {
+    pub fn can_fight(&self, participants: Option<&[String]>) -> Result<(), FightError> {
         let Some(monster) = self
             .current_map()
             .content_code()
@@ -918,7 +937,7 @@ impl CharacterClient {
         gear
     }
 
-    pub fn meets_conditions_for(&self, entity: &impl HasConditions) -> bool {
+    pub fn meets_conditions_for(&self, entity: &(impl HasConditions + ?Sized)) -> bool {
         entity.conditions().into_iter().flatten().all(|condition| {
             let value = condition.value as u32;
             // TODO: simplify this
@@ -1137,9 +1156,10 @@ mod tests {
             ..default_schema()
         });
         assert_matches!(
-            char.can_fight(Some(&["partner".into(), "partner2".into()])),
+            char.can_fight(Some(&["partner".into(), "partner2".into()][..])),
             Ok(())
         );
+        assert_matches!(char.can_fight(Some(&["partner".into()][..])), Ok(()));
 
         let char = character(CharacterSchema {
             inventory: Some(inventory_with(&[("raw_beef", 100)])),
@@ -1159,7 +1179,7 @@ mod tests {
 
         let char = character(schema.clone());
         assert_matches!(
-            char.can_fight(Some(&["p".into(), "q".into()])),
+            char.can_fight(Some(&["p".into(), "q".into()][..])),
             Err(FightError::MonsterIsNotABoss)
         );
 
@@ -1169,7 +1189,7 @@ mod tests {
             ..schema
         });
         assert_matches!(
-            char.can_fight(Some(&["nonexistent".into(), "also_missing".into()])),
+            char.can_fight(Some(&["nonexistent".into(), "also_missing".into()][..])),
             Err(FightError::CharacterNotFound)
         );
     }
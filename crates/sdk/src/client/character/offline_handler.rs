@@ -0,0 +1,188 @@
+use crate::{
+    client::character::{CharacterRequestHandler, error::RequestError},
+    entities::RawMap,
+};
+use openapi::models::{
+    CharacterFightSchema, EquipSchema, GeTransactionSchema, NpcItemTransactionSchema,
+    RecyclingItemsSchema, RewardsSchema, SimpleItemSchema, SkillInfoSchema, TaskSchema,
+    TaskTradeSchema, UnequipSchema,
+};
+use std::time::Duration;
+
+/// Handler of the characters of an offline client: the character data is the
+/// cached one and every action fails with [`RequestError::Offline`].
+pub struct OfflineRequestHandler;
+
+impl CharacterRequestHandler for OfflineRequestHandler {
+    fn refresh_data(&self) {}
+
+    fn pause(&self) {}
+
+    fn resume(&self) {}
+
+    fn cancel(&self) {}
+
+    fn is_paused(&self) -> bool {
+        false
+    }
+
+    fn remaining_cooldown(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    fn request_move(&self, _x: i32, _y: i32) -> Result<RawMap, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_transition(&self) -> Result<RawMap, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_fight(
+        &self,
+        _participants: Option<&[String]>,
+    ) -> Result<CharacterFightSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_rest(&self) -> Result<u32, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_gather(&self) -> Result<SkillInfoSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_craft(
+        &self,
+        _item_code: &str,
+        _quantity: u32,
+    ) -> Result<SkillInfoSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_delete(
+        &self,
+        _item_code: &str,
+        _quantity: u32,
+    ) -> Result<SimpleItemSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_recycle(
+        &self,
+        _item_code: &str,
+        _quantity: u32,
+    ) -> Result<RecyclingItemsSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_deposit_item(&self, _items: &[SimpleItemSchema]) -> Result<(), RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_withdraw_item(&self, _items: &[SimpleItemSchema]) -> Result<(), RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_deposit_gold(&self, _quantity: u32) -> Result<u32, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_withdraw_gold(&self, _quantity: u32) -> Result<u32, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_expand_bank(&self) -> Result<u32, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_equip(&self, _items: &[EquipSchema]) -> Result<(), RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_unequip(&self, _slots: &[UnequipSchema]) -> Result<(), RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_use_item(&self, _item_code: &str, _quantity: u32) -> Result<(), RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_accept_task(&self) -> Result<TaskSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_complete_task(&self) -> Result<RewardsSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_cancel_task(&self) -> Result<(), RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_trade_task_item(
+        &self,
+        _item_code: &str,
+        _quantity: u32,
+    ) -> Result<TaskTradeSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_exchange_tasks_coin(&self) -> Result<RewardsSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_npc_buy(
+        &self,
+        _item_code: &str,
+        _quantity: u32,
+    ) -> Result<NpcItemTransactionSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_npc_sell(
+        &self,
+        _item_code: &str,
+        _quantity: u32,
+    ) -> Result<NpcItemTransactionSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_give_item(
+        &self,
+        _items: &[SimpleItemSchema],
+        _character: &str,
+    ) -> Result<(), RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_give_gold(&self, _quantity: u32, _character: &str) -> Result<(), RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_claim_pending_item(&self, _id: &str) -> Result<(), RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_ge_buy_order(
+        &self,
+        _id: &str,
+        _quantity: u32,
+    ) -> Result<GeTransactionSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_ge_create_order(
+        &self,
+        _item_code: &str,
+        _quantity: u32,
+        _price: u32,
+    ) -> Result<(), RequestError> {
+        Err(RequestError::Offline)
+    }
+
+    fn request_ge_cancel_order(&self, _id: &str) -> Result<GeTransactionSchema, RequestError> {
+        Err(RequestError::Offline)
+    }
+}
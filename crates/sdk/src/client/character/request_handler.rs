@@ -1,4 +1,4 @@
-use crate::entities::{Character, RawCharacter};
+use crate::entities::{Character, CharacterName, RawCharacter};
 use crate::{
     AccountClient, EventBus, SdkEvent,
     bank::Bank,
@@ -8,25 +8,31 @@ use crate::{
         server::ServerClient,
     },
     entities::RawMap,
+    skill::Skill,
 };
 use api::ArtifactApi;
 use chrono::Utc;
 use log::{debug, error, info, warn};
 use openapi::models::{
     BankExtensionTransactionResponseSchema, BankGoldTransactionResponseSchema,
-    CharacterFightResponseSchema, CharacterFightSchema, CharacterMovementResponseSchema,
-    CharacterTransitionResponseSchema, ClaimPendingItemResponseSchema, DeleteItemResponseSchema,
+    BankItemTransactionResponseSchema, CharacterFightResponseSchema, CharacterFightSchema,
+    CharacterMovementResponseSchema, CharacterTransitionResponseSchema,
+    ClaimPendingItemResponseSchema, DeleteItemResponseSchema, EquipmentResponseSchema,
     GeCreateOrderTransactionResponseSchema, GeTransactionResponseSchema, GeTransactionSchema,
     GiveGoldResponseSchema, GiveItemResponseSchema, NpcItemTransactionSchema,
     NpcMerchantTransactionResponseSchema, RecyclingItemsSchema, RecyclingResponseSchema,
     RewardDataResponseSchema, RewardsSchema, SimpleItemSchema, SkillInfoSchema,
     SkillResponseSchema, TaskResponseSchema, TaskSchema, TaskTradeResponseSchema, TaskTradeSchema,
-    UnequipSchema,
+    UnequipSchema, UseItemResponseSchema,
 };
 use openapi::models::{CharacterRestResponseSchema, EquipSchema};
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread::sleep;
 use std::time::Duration;
+use strum::IntoEnumIterator;
+
+/// Delay before retrying a request that failed with an unknown server error.
+const UNKNOWN_ERROR_RETRY_DELAY: Duration = Duration::from_secs(10);
 
 /// First layer of abstraction around the character API.
 /// It is responsible for handling the character action requests response and errors
@@ -38,6 +44,7 @@ pub struct CharacterHttpRequestHandler {
     server: ServerClient,
     pause_state: Arc<PauseState>,
     event_bus: EventBus,
+    retry_delay: Duration,
 }
 
 impl CharacterHttpRequestHandler {
@@ -55,6 +62,7 @@ impl CharacterHttpRequestHandler {
             server,
             pause_state: PauseState::default().into(),
             event_bus,
+            retry_delay: UNKNOWN_ERROR_RETRY_DELAY,
         }
     }
 
@@ -68,42 +76,62 @@ impl CharacterHttpRequestHandler {
         }
         match action.send(&self.data.name(), &self.api) {
             Ok(res) => {
+                let previous = self.snapshot(&*res);
                 self.update_data(&*res);
-                self.emit_event(&action);
+                self.emit_events(&action, &*res, &previous);
                 Ok(res)
             }
             Err(e) => self.handle_request_error(action, e),
         }
     }
 
-    fn emit_event(&self, action: &ActionRequest) {
+    /// Returns the current data of the characters updated by `res`.
+    fn snapshot(&self, res: &(dyn ResponseSchema + 'static)) -> Vec<RawCharacter> {
+        res.characters()
+            .into_iter()
+            .filter_map(|c| {
+                if *self.data.name() == *c.name {
+                    Some(self.data.load())
+                } else {
+                    self.account.get_character(&c.name).map(|c| c.data.load())
+                }
+            })
+            .collect()
+    }
+
+    fn emit_events(
+        &self,
+        action: &ActionRequest,
+        res: &(dyn ResponseSchema + 'static),
+        previous: &[RawCharacter],
+    ) {
         let character = self.data.name();
-        match action {
-            ActionRequest::DepositItem { items } => {
-                self.event_bus.emit(SdkEvent::ItemDeposited {
-                    character,
-                    items: items.to_vec(),
-                });
-            }
-            ActionRequest::WithdrawItem { items } => {
-                self.event_bus.emit(SdkEvent::ItemWithdrawn {
-                    character,
-                    items: items.to_vec(),
-                });
-            }
-            ActionRequest::DepositGold { quantity } => {
-                self.event_bus.emit(SdkEvent::GoldDeposited {
-                    character,
-                    amount: *quantity,
-                });
-            }
-            ActionRequest::WithdrawGold { quantity } => {
-                self.event_bus.emit(SdkEvent::GoldWithdrawn {
-                    character,
-                    amount: *quantity,
-                });
+        self.event_bus.emit(SdkEvent::CooldownStarted {
+            character: character.clone(),
+            cooldown: res.cooldown().clone(),
+        });
+        if let Some(event) = action_event(character, action, res) {
+            self.event_bus.emit(event);
+        }
+        for old in previous {
+            let Some(new) = res
+                .characters()
+                .into_iter()
+                .find(|c| *c.name == *old.name())
+            else {
+                continue;
+            };
+            let new = RawCharacter::new(new.clone());
+            for skill in Skill::iter() {
+                let level = new.skill_level(skill);
+                if level > old.skill_level(skill) {
+                    self.event_bus.emit(SdkEvent::LeveledUp {
+                        character: new.name(),
+                        skill,
+                        level,
+                    });
+                }
             }
-            _ => {}
         }
     }
 
@@ -150,31 +178,57 @@ impl CharacterHttpRequestHandler {
                         "{}: code 499 received, resyncronizing server time",
                         self.data.name()
                     );
-                    self.server.update_offset();
+                    if let Err(e) = self.server.update_offset() {
+                        error!("{}: failed to resyncronize: {e}", self.data.name());
+                    }
                     return self.request_action(action);
                 }
                 if res.error.code == 500 || res.error.code == 520 {
                     error!(
-                        "{}: unknown error ({}), retrying in 10 seconds.",
+                        "{}: unknown error ({}), retrying in {} seconds.",
                         self.data.name(),
-                        res.error.code
+                        res.error.code,
+                        self.retry_delay.as_secs()
                     );
-                    sleep(Duration::from_secs(10));
+                    sleep(self.retry_delay);
                     return self.request_action(action);
                 }
+                self.action_failed(&action, error)
             }
             RequestError::Reqwest(ref req) => {
                 if req.is_timeout() {
                     error!("{}: request timed-out, retrying...", self.data.name());
                     return self.request_action(action);
                 }
+                self.action_failed(&action, error)
             }
             RequestError::Serde(_) | RequestError::Io(_) | RequestError::DowncastError => {
                 warn!("{}: refreshing data", self.data.name());
                 self.refresh_data();
+                self.action_failed(&action, error)
+            }
+            RequestError::Canceled | RequestError::Offline | RequestError::ReqwestMiddleware(_) => {
+                self.action_failed(&action, error)
             }
-            RequestError::Canceled | RequestError::ReqwestMiddleware(_) => return Err(error),
         }
+    }
+
+    /// Emits [`SdkEvent::ActionFailed`] for the `action` then returns the
+    /// `error`.
+    fn action_failed(
+        &self,
+        action: &ActionRequest,
+        error: RequestError,
+    ) -> Result<Box<dyn ResponseSchema>, RequestError> {
+        self.event_bus.emit(SdkEvent::ActionFailed {
+            character: self.data.name(),
+            action: action.to_string(),
+            code: match error {
+                RequestError::ResponseError(ref res) => Some(res.error.code),
+                _ => None,
+            },
+            error: error.to_string(),
+        });
         Err(error)
     }
 
@@ -267,7 +321,7 @@ impl CharacterRequestHandler for CharacterHttpRequestHandler {
 
     fn request_fight(
         &self,
-        participants: Option<&[String; 2]>,
+        participants: Option<&[String]>,
     ) -> Result<CharacterFightSchema, RequestError> {
         self.request_action(ActionRequest::Fight { participants })
             .and_then(downcast_response::<CharacterFightResponseSchema>)
@@ -534,3 +588,399 @@ fn downcast_response<T: ResponseSchema + 'static>(
         .map(|b| *b)
         .map_err(|_| RequestError::DowncastError)
 }
+
+/// Returns the event describing the outcome of `action`, built from the
+/// payload of its response.
+#[allow(clippy::too_many_lines)]
+fn action_event(
+    character: CharacterName,
+    action: &ActionRequest,
+    res: &(dyn ResponseSchema + 'static),
+) -> Option<SdkEvent> {
+    Some(match *action {
+        ActionRequest::Move { .. } => SdkEvent::Moved {
+            character,
+            destination: res
+                .downcast_ref::<CharacterMovementResponseSchema>()?
+                .data
+                .destination
+                .clone(),
+        },
+        ActionRequest::Transition => SdkEvent::Transitioned {
+            character,
+            destination: res
+                .downcast_ref::<CharacterTransitionResponseSchema>()?
+                .data
+                .destination
+                .clone(),
+        },
+        ActionRequest::Fight { .. } => SdkEvent::Fought {
+            character,
+            fight: Box::new(
+                res.downcast_ref::<CharacterFightResponseSchema>()?
+                    .data
+                    .fight
+                    .clone(),
+            ),
+        },
+        ActionRequest::Rest => SdkEvent::Rested {
+            character,
+            hp_restored: res
+                .downcast_ref::<CharacterRestResponseSchema>()?
+                .data
+                .hp_restored as u32,
+        },
+        ActionRequest::Gather => SdkEvent::Gathered {
+            character,
+            details: res
+                .downcast_ref::<SkillResponseSchema>()?
+                .data
+                .details
+                .clone(),
+        },
+        ActionRequest::Craft { .. } => SdkEvent::Crafted {
+            character,
+            details: res
+                .downcast_ref::<SkillResponseSchema>()?
+                .data
+                .details
+                .clone(),
+        },
+        ActionRequest::Recycle { .. } => SdkEvent::Recycled {
+            character,
+            details: res
+                .downcast_ref::<RecyclingResponseSchema>()?
+                .data
+                .details
+                .clone(),
+        },
+        ActionRequest::Delete { .. } => SdkEvent::ItemDeleted {
+            character,
+            item: res
+                .downcast_ref::<DeleteItemResponseSchema>()?
+                .data
+                .item
+                .clone(),
+        },
+        ActionRequest::DepositItem { .. } => SdkEvent::ItemDeposited {
+            character,
+            items: res
+                .downcast_ref::<BankItemTransactionResponseSchema>()?
+                .data
+                .items
+                .clone(),
+        },
+        ActionRequest::WithdrawItem { .. } => SdkEvent::ItemWithdrawn {
+            character,
+            items: res
+                .downcast_ref::<BankItemTransactionResponseSchema>()?
+                .data
+                .items
+                .clone(),
+        },
+        ActionRequest::DepositGold { quantity } => SdkEvent::GoldDeposited {
+            character,
+            amount: quantity,
+        },
+        ActionRequest::WithdrawGold { quantity } => SdkEvent::GoldWithdrawn {
+            character,
+            amount: quantity,
+        },
+        ActionRequest::ExpandBank => SdkEvent::BankExpanded {
+            character,
+            price: res
+                .downcast_ref::<BankExtensionTransactionResponseSchema>()?
+                .data
+                .transaction
+                .price,
+        },
+        ActionRequest::Equip { .. } => SdkEvent::Equipped {
+            character,
+            items: res
+                .downcast_ref::<EquipmentResponseSchema>()?
+                .data
+                .items
+                .clone(),
+        },
+        ActionRequest::Unequip { .. } => SdkEvent::Unequipped {
+            character,
+            items: res
+                .downcast_ref::<EquipmentResponseSchema>()?
+                .data
+                .items
+                .clone(),
+        },
+        ActionRequest::UseItem { quantity, .. } => SdkEvent::ItemUsed {
+            character,
+            item: Box::new(
+                res.downcast_ref::<UseItemResponseSchema>()?
+                    .data
+                    .item
+                    .clone(),
+            ),
+            quantity,
+        },
+        ActionRequest::AcceptTask => SdkEvent::TaskAccepted {
+            character,
+            task: res.downcast_ref::<TaskResponseSchema>()?.data.task.clone(),
+        },
+        ActionRequest::CompleteTask => SdkEvent::TaskCompleted {
+            character,
+            rewards: res
+                .downcast_ref::<RewardDataResponseSchema>()?
+                .data
+                .rewards
+                .clone(),
+        },
+        ActionRequest::CancelTask => SdkEvent::TaskCancelled { character },
+        ActionRequest::TradeTaskItem { .. } => SdkEvent::TaskItemTraded {
+            character,
+            trade: res
+                .downcast_ref::<TaskTradeResponseSchema>()?
+                .data
+                .trade
+                .clone(),
+        },
+        ActionRequest::ExchangeTasksCoins => SdkEvent::TasksCoinsExchanged {
+            character,
+            rewards: res
+                .downcast_ref::<RewardDataResponseSchema>()?
+                .data
+                .rewards
+                .clone(),
+        },
+        ActionRequest::NpcBuy { .. } => SdkEvent::NpcBought {
+            character,
+            transaction: res
+                .downcast_ref::<NpcMerchantTransactionResponseSchema>()?
+                .data
+                .transaction
+                .clone(),
+        },
+        ActionRequest::NpcSell { .. } => SdkEvent::NpcSold {
+            character,
+            transaction: res
+                .downcast_ref::<NpcMerchantTransactionResponseSchema>()?
+                .data
+                .transaction
+                .clone(),
+        },
+        ActionRequest::GiveItem { .. } => {
+            let data = &res.downcast_ref::<GiveItemResponseSchema>()?.data;
+            SdkEvent::ItemGiven {
+                character,
+                receiver: data.receiver_character.name.clone(),
+                items: data.items.clone(),
+            }
+        }
+        ActionRequest::GiveGold { .. } => {
+            let data = &res.downcast_ref::<GiveGoldResponseSchema>()?.data;
+            SdkEvent::GoldGiven {
+                character,
+                receiver: data.receiver_character.name.clone(),
+                amount: data.quantity,
+            }
+        }
+        ActionRequest::ClaimPendingItem { .. } => SdkEvent::PendingItemClaimed {
+            character,
+            item: res
+                .downcast_ref::<ClaimPendingItemResponseSchema>()?
+                .data
+                .item
+                .clone(),
+        },
+        ActionRequest::GeBuyOrder { .. } => SdkEvent::GeOrderFilled {
+            character,
+            order: res
+                .downcast_ref::<GeTransactionResponseSchema>()?
+                .data
+                .order
+                .clone(),
+        },
+        ActionRequest::GeCreateOrder { .. } => SdkEvent::GeOrderCreated {
+            character,
+            order: res
+                .downcast_ref::<GeCreateOrderTransactionResponseSchema>()?
+                .data
+                .order
+                .clone(),
+        },
+        ActionRequest::GeCancelOrder { .. } => SdkEvent::GeOrderCancelled {
+            character,
+            order: res
+                .downcast_ref::<GeTransactionResponseSchema>()?
+                .data
+                .order
+                .clone(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BusMessage, CacheConfig,
+        test_utils::{ACCOUNT, default_schema},
+    };
+    use api::cassette::{CassetteMiddleware, Interaction};
+    use openapi::models::{
+        CharacterRestDataSchema, CharacterSchema, CooldownSchema, StatusResponseSchema,
+    };
+    use reqwest_middleware::ClientBuilder;
+
+    const REST: &str = "/my/char1/action/rest";
+
+    fn handler(cassette: &Arc<CassetteMiddleware>) -> CharacterHttpRequestHandler {
+        let api = ArtifactApi::with_client(
+            "http://localhost".to_owned(),
+            "token".to_owned(),
+            ClientBuilder::new(reqwest::Client::new())
+                .with_arc(cassette.clone())
+                .build(),
+        );
+        let mut handler = CharacterHttpRequestHandler::new(
+            api.clone(),
+            CharacterHandle::new(CharacterSchema {
+                name: "char1".to_owned(),
+                ..default_schema()
+            }),
+            ACCOUNT.clone(),
+            ServerClient::new(api, CacheConfig::default()),
+            EventBus::new(10),
+        );
+        handler.retry_delay = Duration::ZERO;
+        handler
+    }
+
+    fn error(code: u16) -> Interaction {
+        Interaction::json(
+            "POST",
+            REST,
+            code,
+            format!(r#"{{"error":{{"code":{code},"message":"error"}}}}"#),
+        )
+    }
+
+    fn rested(hp_restored: i32) -> Interaction {
+        rested_as(
+            hp_restored,
+            CharacterSchema {
+                name: "char1".to_owned(),
+                ..default_schema()
+            },
+        )
+    }
+
+    fn rested_as(hp_restored: i32, character: CharacterSchema) -> Interaction {
+        let body = CharacterRestResponseSchema::new(CharacterRestDataSchema::new(
+            CooldownSchema {
+                total_seconds: 5,
+                ..Default::default()
+            },
+            hp_restored,
+            character,
+        ));
+        Interaction::json("POST", REST, 200, serde_json::to_string(&body).unwrap())
+    }
+
+    #[test]
+    fn retries_when_action_already_in_progress() {
+        let cassette = Arc::new(CassetteMiddleware::from_interactions(vec![
+            error(489),
+            rested(10),
+        ]));
+
+        assert_eq!(handler(&cassette).request_rest().unwrap(), 10);
+        assert_eq!(cassette.remaining(), 0);
+    }
+
+    #[test]
+    fn resyncs_server_time_on_cooldown_error() {
+        let status = StatusResponseSchema::default();
+        let cassette = Arc::new(CassetteMiddleware::from_interactions(vec![
+            error(499),
+            Interaction::json("GET", "/", 200, serde_json::to_string(&status).unwrap()),
+            rested(10),
+        ]));
+
+        assert_eq!(handler(&cassette).request_rest().unwrap(), 10);
+        assert_eq!(cassette.remaining(), 0);
+    }
+
+    #[test]
+    fn retries_unknown_server_errors() {
+        let cassette = Arc::new(CassetteMiddleware::from_interactions(vec![
+            error(500),
+            error(520),
+            rested(10),
+        ]));
+
+        assert_eq!(handler(&cassette).request_rest().unwrap(), 10);
+        assert_eq!(cassette.remaining(), 0);
+    }
+
+    #[test]
+    fn returns_unhandled_errors() {
+        let cassette = Arc::new(CassetteMiddleware::from_interactions(vec![
+            error(598),
+            rested(10),
+        ]));
+
+        assert!(matches!(
+            handler(&cassette).request_rest(),
+            Err(RequestError::ResponseError(ref e)) if e.error.code == 598
+        ));
+        assert_eq!(cassette.remaining(), 1);
+    }
+
+    #[test]
+    fn emits_cooldown_action_and_level_up_events() {
+        let cassette = Arc::new(CassetteMiddleware::from_interactions(vec![rested_as(
+            10,
+            CharacterSchema {
+                name: "char1".to_owned(),
+                cooking_level: 2,
+                ..default_schema()
+            },
+        )]));
+        let handler = handler(&cassette);
+        let events = handler.event_bus.subscribe();
+
+        handler.request_rest().unwrap();
+        assert!(matches!(
+            events.try_recv(),
+            Ok(BusMessage::Event(SdkEvent::CooldownStarted { cooldown, .. })) if cooldown.total_seconds == 5
+        ));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(BusMessage::Event(SdkEvent::Rested {
+                hp_restored: 10,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(BusMessage::Event(SdkEvent::LeveledUp { skill: Skill::Cooking, level: 2, character }))
+                if *character == *"char1"
+        ));
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn emits_unhandled_errors() {
+        let cassette = Arc::new(CassetteMiddleware::from_interactions(vec![
+            error(489),
+            error(598),
+        ]));
+        let handler = handler(&cassette);
+        let events = handler.event_bus.subscribe();
+
+        assert!(handler.request_rest().is_err());
+        assert!(matches!(
+            events.try_recv(),
+            Ok(BusMessage::Event(SdkEvent::ActionFailed { code: Some(598), action, .. })) if action == "Rest"
+        ));
+        assert!(events.try_recv().is_err());
+    }
+}
@@ -5,18 +5,20 @@ use openapi::models::{
     ActionType, BankExtensionTransactionResponseSchema, BankGoldTransactionResponseSchema,
     BankItemTransactionResponseSchema, CharacterFightResponseSchema,
     CharacterMovementResponseSchema, CharacterRestResponseSchema, CharacterSchema,
-    CharacterTransitionResponseSchema, ClaimPendingItemResponseSchema, DeleteItemResponseSchema,
-    EquipmentResponseSchema, FightResult, GeCreateOrderTransactionResponseSchema,
-    GeTransactionResponseSchema, GiveGoldResponseSchema, GiveItemResponseSchema,
-    NpcMerchantTransactionResponseSchema, PendingItemSchema, RecyclingResponseSchema,
-    RewardDataResponseSchema, SimpleItemSchema, SkillResponseSchema, TaskCancelledResponseSchema,
-    TaskResponseSchema, TaskTradeResponseSchema, UseItemResponseSchema,
+    CharacterTransitionResponseSchema, ClaimPendingItemResponseSchema, CooldownSchema,
+    DeleteItemResponseSchema, EquipmentResponseSchema, FightResult,
+    GeCreateOrderTransactionResponseSchema, GeTransactionResponseSchema, GiveGoldResponseSchema,
+    GiveItemResponseSchema, NpcMerchantTransactionResponseSchema, PendingItemSchema,
+    RecyclingResponseSchema, RewardDataResponseSchema, SimpleItemSchema, SkillResponseSchema,
+    TaskCancelledResponseSchema, TaskResponseSchema, TaskTradeResponseSchema,
+    UseItemResponseSchema,
 };
 use std::fmt::{self, Display, Formatter};
 
 pub trait ResponseSchema: Downcast {
     fn pretty(&self) -> String;
     fn character(&self) -> &CharacterSchema;
+    fn cooldown(&self) -> &CooldownSchema;
 
     fn characters(&self) -> Vec<&CharacterSchema> {
         vec![self.character()]
@@ -54,6 +56,10 @@ impl ResponseSchema for CharacterMovementResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for CharacterTransitionResponseSchema {
@@ -69,6 +75,10 @@ impl ResponseSchema for CharacterTransitionResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for CharacterFightResponseSchema {
@@ -92,6 +102,10 @@ impl ResponseSchema for CharacterFightResponseSchema {
         self.data.characters.first().unwrap()
     }
 
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
+
     fn characters(&self) -> Vec<&CharacterSchema> {
         self.data.characters.iter().collect_vec()
     }
@@ -108,6 +122,10 @@ impl ResponseSchema for CharacterRestResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for UseItemResponseSchema {
@@ -121,6 +139,10 @@ impl ResponseSchema for UseItemResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for SkillResponseSchema {
@@ -142,6 +164,10 @@ impl ResponseSchema for SkillResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for DeleteItemResponseSchema {
@@ -158,6 +184,10 @@ impl ResponseSchema for DeleteItemResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for BankItemTransactionResponseSchema {
@@ -179,6 +209,10 @@ impl ResponseSchema for BankItemTransactionResponseSchema {
         &self.data.character
     }
 
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
+
     fn bank_content(&self) -> Option<&Vec<SimpleItemSchema>> {
         Some(&self.data.bank)
     }
@@ -201,6 +235,10 @@ impl ResponseSchema for BankGoldTransactionResponseSchema {
         &self.data.character
     }
 
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
+
     fn bank_gold(&self) -> Option<u32> {
         Some(self.data.bank.quantity)
     }
@@ -220,6 +258,10 @@ impl ResponseSchema for BankExtensionTransactionResponseSchema {
         &self.data.character
     }
 
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
+
     fn extension_price(&self) -> Option<u32> {
         Some(self.data.transaction.price)
     }
@@ -238,6 +280,10 @@ impl ResponseSchema for RecyclingResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for EquipmentResponseSchema {
@@ -262,6 +308,10 @@ impl ResponseSchema for EquipmentResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for TaskResponseSchema {
@@ -279,6 +329,10 @@ impl ResponseSchema for TaskResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for RewardDataResponseSchema {
@@ -295,6 +349,10 @@ impl ResponseSchema for RewardDataResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for TaskCancelledResponseSchema {
@@ -308,6 +366,10 @@ impl ResponseSchema for TaskCancelledResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for TaskTradeResponseSchema {
@@ -324,6 +386,10 @@ impl ResponseSchema for TaskTradeResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for NpcMerchantTransactionResponseSchema {
@@ -343,6 +409,10 @@ impl ResponseSchema for NpcMerchantTransactionResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for GiveItemResponseSchema {
@@ -360,6 +430,10 @@ impl ResponseSchema for GiveItemResponseSchema {
         &self.data.character
     }
 
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
+
     fn characters(&self) -> Vec<&CharacterSchema> {
         vec![&self.character(), &self.data.receiver_character]
     }
@@ -380,6 +454,10 @@ impl ResponseSchema for GiveGoldResponseSchema {
         &self.data.character
     }
 
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
+
     fn characters(&self) -> Vec<&CharacterSchema> {
         vec![&self.character(), &self.data.receiver_character]
     }
@@ -403,6 +481,10 @@ impl ResponseSchema for ClaimPendingItemResponseSchema {
         &self.data.character
     }
 
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
+
     fn characters(&self) -> Vec<&CharacterSchema> {
         vec![&self.character()]
     }
@@ -432,6 +514,10 @@ impl ResponseSchema for GeTransactionResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl ResponseSchema for GeCreateOrderTransactionResponseSchema {
@@ -449,6 +535,10 @@ impl ResponseSchema for GeCreateOrderTransactionResponseSchema {
     fn character(&self) -> &CharacterSchema {
         &self.data.character
     }
+
+    fn cooldown(&self) -> &CooldownSchema {
+        &self.data.cooldown
+    }
 }
 
 impl<T: ResponseSchema + 'static> From<T> for Box<dyn ResponseSchema> {
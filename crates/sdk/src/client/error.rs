@@ -1,8 +1,46 @@
 use std::error::Error as StdError;
+use strum_macros::Display;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum ClientError {
     #[error("api error: {0}")]
     Api(#[from] Box<dyn StdError + Send + Sync>),
+    #[error("{0} unavailable")]
+    Unavailable(&'static str),
+}
+
+/// Error returned when a part of the [`Client`](crate::Client) could not be
+/// initialized, neither from its source nor from its cache.
+#[derive(Debug, Error)]
+#[error("failed to initialize {subsystem}: {source}")]
+pub struct InitError {
+    pub subsystem: Subsystem,
+    pub source: ClientError,
+}
+
+impl InitError {
+    #[must_use]
+    pub const fn new(subsystem: Subsystem, source: ClientError) -> Self {
+        Self { subsystem, source }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum Subsystem {
+    Account,
+    Bank,
+    Characters,
+    Server,
+    Events,
+    Resources,
+    Monsters,
+    Items,
+    Tasks,
+    TasksRewards,
+    Maps,
+    Npcs,
+    NpcsItems,
+    Logs,
 }
@@ -1,12 +1,12 @@
 use crate::{
-    Cached,
+    CACHE_TTL, CacheConfig, Cached, ClientError,
     entities::{ActiveEvent, Event},
 };
 use arc_swap::ArcSwap;
 use chrono::{DateTime, Duration, Utc};
 use derive_more::Deref;
 use itertools::Itertools;
-use log::{debug, info};
+use log::{error, info};
 use sdk_derive::CollectionClient;
 use std::{
     collections::HashMap,
@@ -14,8 +14,10 @@ use std::{
     thread,
 };
 
-type EventsSource = Box<dyn Fn() -> HashMap<String, Event> + Send + Sync + 'static>;
-type EventsActiveSource = Box<dyn Fn() -> Vec<ActiveEvent> + Send + Sync + 'static>;
+type EventsSource =
+    Box<dyn Fn() -> Result<HashMap<String, Event>, ClientError> + Send + Sync + 'static>;
+type EventsActiveSource =
+    Box<dyn Fn() -> Result<Vec<ActiveEvent>, ClientError> + Send + Sync + 'static>;
 
 #[derive(Clone, Deref, CollectionClient)]
 #[deref(forward)]
@@ -23,7 +25,7 @@ type EventsActiveSource = Box<dyn Fn() -> Vec<ActiveEvent> + Send + Sync + 'stat
 pub struct EventsClient(Arc<EventsClientInner>);
 
 pub struct EventsClientInner {
-    cache_dir: Box<str>,
+    cache: CacheConfig,
     data: ArcSwap<HashMap<String, Event>>,
     fetch: EventsSource,
     fetch_active: EventsActiveSource,
@@ -34,12 +36,12 @@ pub struct EventsClientInner {
 impl EventsClient {
     #[must_use]
     pub(crate) fn new(
-        cache_dir: &str,
+        cache: CacheConfig,
         fetch: EventsSource,
         fetch_active: EventsActiveSource,
     ) -> Self {
         Self(Arc::new(EventsClientInner {
-            cache_dir: cache_dir.into(),
+            cache,
             data: ArcSwap::default(),
             fetch,
             fetch_active,
@@ -48,24 +50,23 @@ impl EventsClient {
         }))
     }
 
-    #[must_use]
-    pub fn from_cache(path: &str) -> Self {
+    pub fn from_cache(path: &str) -> Result<Self, ClientError> {
         let client = Self::new(
-            path,
-            Box::new(|| unreachable!("EventsClient::from_cache has no API fallback")),
-            Box::new(|| unreachable!("EventsClient::from_cache has no API fallback")),
+            CacheConfig::new(path, CACHE_TTL),
+            Box::new(|| Err(ClientError::Unavailable("events"))),
+            Box::new(|| Err(ClientError::Unavailable("events"))),
         );
-        client.init();
-        client
+        client.init()?;
+        Ok(client)
     }
 
-    pub fn init(&self) {
-        let () = thread::scope(|s| {
-            // TODO: handle errors
-            let _ = s.spawn(|| self.0.data.store(Arc::new(self.fetch())));
-            let _ = s.spawn(|| self.refresh_active());
-        });
-        info!("Event client initilized");
+    pub fn init(&self) -> Result<(), ClientError> {
+        thread::scope(|s| {
+            s.spawn(|| self.refresh_active());
+            self.0.data.store(Arc::new(self.fetch()?));
+            info!("Event client initilized");
+            Ok(())
+        })
     }
 
     #[must_use]
@@ -85,8 +86,10 @@ impl EventsClient {
             return;
         }
         self.update_last_refresh(now);
-        *events = (self.fetch_active)();
-        debug!("events refreshed.");
+        match (self.fetch_active)() {
+            Ok(active) => *events = active,
+            Err(e) => error!("failed to refresh active events: {e}"),
+        }
     }
 
     fn update_last_refresh(&self, now: DateTime<Utc>) {
@@ -107,16 +110,18 @@ impl EventsClient {
 
 impl Cached<HashMap<String, Event>> for EventsClient {
     const FILE: &str = "events";
+    const SCHEMA_VERSION: u32 = 1;
 
-    fn cache_dir(&self) -> &str {
-        &self.cache_dir
+    fn cache_config(&self) -> &CacheConfig {
+        &self.cache
     }
 
-    fn fetch_from_source(&self) -> HashMap<String, Event> {
+    fn fetch_from_source(&self) -> Result<HashMap<String, Event>, ClientError> {
         (self.fetch)()
     }
 
-    fn refresh(&self) {
-        self.0.data.store(Arc::new(self.fetch_from_source()));
+    fn refresh(&self) -> Result<(), ClientError> {
+        self.0.data.store(Arc::new(self.fetch_and_cache()?));
+        Ok(())
     }
 }
@@ -1,7 +1,7 @@
 use crate::{
-    Cached, Code, CollectionClient, HasDropTable, Level, Quantity,
+    CacheConfig, Cached, ClientError, Code, CollectionClient, HasDropTable, Level, Quantity,
     client::{
-        monsters::MonstersClient, npcs::NpcsClient, resources::ResourcesClient,
+        Snapshot, monsters::MonstersClient, npcs::NpcsClient, resources::ResourcesClient,
         tasks_rewards::TasksRewardsClient,
     },
     consts::{TASKS_COIN, TASKS_REWARDS_SPECIFICS},
@@ -11,7 +11,8 @@ use crate::{
     skill::Skill,
 };
 
-type ItemsSource = Box<dyn Fn() -> HashMap<String, Item> + Send + Sync + 'static>;
+type ItemsSource =
+    Box<dyn Fn() -> Result<HashMap<String, Item>, ClientError> + Send + Sync + 'static>;
 
 use arc_swap::ArcSwap;
 use derive_more::Deref;
@@ -24,11 +25,12 @@ use strum_macros::{AsRefStr, Display, EnumIs, EnumIter, EnumString};
 #[derive(Clone, Deref, CollectionClient)]
 #[deref(forward)]
 #[element(Item)]
+#[snapshot_path(self.0.snapshot)]
 pub struct ItemsClient(Arc<ItemsClientInner>);
 
 pub struct ItemsClientInner {
-    cache_dir: Box<str>,
-    data: ArcSwap<HashMap<String, Item>>,
+    cache: CacheConfig,
+    snapshot: ArcSwap<Snapshot<String, Item, ItemsIndex>>,
     fetch: ItemsSource,
     resources: ResourcesClient,
     monsters: MonstersClient,
@@ -39,7 +41,7 @@ pub struct ItemsClientInner {
 impl ItemsClient {
     #[must_use]
     pub(crate) fn new(
-        cache_dir: &str,
+        cache: CacheConfig,
         fetch: ItemsSource,
         resources: ResourcesClient,
         monsters: MonstersClient,
@@ -47,8 +49,8 @@ impl ItemsClient {
         npcs: NpcsClient,
     ) -> Self {
         Self(Arc::new(ItemsClientInner {
-            cache_dir: cache_dir.into(),
-            data: ArcSwap::default(),
+            cache,
+            snapshot: ArcSwap::default(),
             fetch,
             resources,
             monsters,
@@ -57,9 +59,30 @@ impl ItemsClient {
         }))
     }
 
-    pub fn init(&self) {
-        self.data.store(Arc::new(self.fetch()));
+    pub fn init(&self) -> Result<(), ClientError> {
+        self.store(self.fetch()?);
         info!("Items client initilized");
+        Ok(())
+    }
+
+    /// Stores a new snapshot of the items and rebuilds its indices.
+    fn store(&self, items: HashMap<String, Item>) {
+        let index = ItemsIndex::new(&items);
+        self.snapshot.store(Arc::new(Snapshot {
+            data: Arc::new(items),
+            index,
+        }));
+    }
+
+    /// Returns the items of the current snapshot whose codes are given by
+    /// `index` for the same snapshot.
+    fn indexed(&self, index: impl Fn(&ItemsIndex) -> Option<&Vec<String>>) -> Vec<Item> {
+        let snapshot = self.snapshot.load();
+        index(&snapshot.index)
+            .into_iter()
+            .flatten()
+            .filter_map(|code| snapshot.data.get(code).cloned())
+            .collect_vec()
     }
 
     #[must_use]
@@ -77,24 +100,7 @@ impl ItemsClient {
     /// required to craft it.
     #[must_use]
     pub fn base_mats_of(&self, code: &str) -> Vec<SimpleItemSchema> {
-        let Some(item) = self.get(code) else {
-            return vec![];
-        };
-        let mut consolidated: HashMap<String, u32> = HashMap::new();
-        for mat in item.mats() {
-            let sub_bases = self.base_mats_of(&mat.code);
-            if sub_bases.is_empty() {
-                *consolidated.entry(mat.code.clone()).or_default() += mat.quantity;
-            } else {
-                for b in sub_bases {
-                    *consolidated.entry(b.code).or_default() += b.quantity * mat.quantity;
-                }
-            }
-        }
-        consolidated
-            .into_iter()
-            .map(|(code, quantity)| SimpleItemSchema::new(code, quantity))
-            .collect_vec()
+        base_mats_of(&self.snapshot.load().data, code)
     }
 
     /// Takes a `resource` code and returns the items that can be crafted
@@ -117,9 +123,7 @@ impl ItemsClient {
     /// Takes an item `code` and returns the items directly crafted with it.
     #[must_use]
     pub fn crafted_with(&self, code: &str) -> Vec<Item> {
-        self.iter()
-            .filter(|i| i.is_crafted_with(code))
-            .collect_vec()
+        self.indexed(|index| index.crafted_with.get(code))
     }
 
     #[must_use]
@@ -142,9 +146,7 @@ impl ItemsClient {
     /// Takes an item `code` and returns the items crafted with it as base mat.
     #[must_use]
     pub fn crafted_with_base_mat(&self, code: &str) -> Vec<Item> {
-        self.iter()
-            .filter(|i| self.is_crafted_with_base_mat(i.code(), code))
-            .collect_vec()
+        self.indexed(|index| index.crafted_with_base_mat.get(code))
     }
 
     /// Takes an item `code` and checks if it is crafted with `mat` as a base
@@ -204,13 +206,7 @@ impl ItemsClient {
 
     #[must_use]
     pub fn upgrades_of(&self, code: &str) -> Vec<Item> {
-        let Some(item) = self.get(code) else {
-            return vec![];
-        };
-        self.iter()
-            .filter(|other| other.code() != item.code())
-            .filter(|other| other.is_upgrade_of(&item))
-            .collect_vec()
+        self.indexed(|index| index.upgrades.get(code))
     }
 
     pub fn sources_of(&self, code: &str) -> Vec<ItemSource> {
@@ -274,21 +270,92 @@ impl ItemsClient {
 
 impl Cached<HashMap<String, Item>> for ItemsClient {
     const FILE: &'static str = "items";
+    const SCHEMA_VERSION: u32 = 1;
 
-    fn cache_dir(&self) -> &str {
-        &self.cache_dir
+    fn cache_config(&self) -> &CacheConfig {
+        &self.cache
     }
 
-    fn fetch_from_source(&self) -> HashMap<String, Item> {
+    fn fetch_from_source(&self) -> Result<HashMap<String, Item>, ClientError> {
         (self.fetch)()
     }
 
-    fn refresh(&self) {
-        self.data.store(Arc::new(self.fetch_from_source()));
+    fn refresh(&self) -> Result<(), ClientError> {
+        self.store(self.fetch_and_cache()?);
+        Ok(())
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Display, AsRefStr, EnumIter, EnumString, EnumIs)]
+/// Reverse indices of an items snapshot, mapping an item code to the codes of
+/// the related items.
+#[derive(Default)]
+struct ItemsIndex {
+    crafted_with: HashMap<String, Vec<String>>,
+    crafted_with_base_mat: HashMap<String, Vec<String>>,
+    upgrades: HashMap<String, Vec<String>>,
+}
+
+impl ItemsIndex {
+    fn new(items: &HashMap<String, Item>) -> Self {
+        let mut index = Self::default();
+        for item in items.values() {
+            for mat in item.mats() {
+                index
+                    .crafted_with
+                    .entry(mat.code.clone())
+                    .or_default()
+                    .push(item.code().to_owned());
+            }
+            for mat in base_mats_of(items, item.code()) {
+                index
+                    .crafted_with_base_mat
+                    .entry(mat.code)
+                    .or_default()
+                    .push(item.code().to_owned());
+            }
+        }
+        for same_type in items
+            .values()
+            .into_group_map_by(|item| item.r#type())
+            .values()
+        {
+            for item in same_type {
+                let upgrades = same_type
+                    .iter()
+                    .filter(|other| other.code() != item.code() && other.is_upgrade_of(item))
+                    .map(|other| other.code().to_owned())
+                    .collect_vec();
+                index.upgrades.insert(item.code().to_owned(), upgrades);
+            }
+        }
+        index
+    }
+}
+
+fn base_mats_of(items: &HashMap<String, Item>, code: &str) -> Vec<SimpleItemSchema> {
+    let Some(item) = items.get(code) else {
+        return vec![];
+    };
+    let mut consolidated: HashMap<String, u32> = HashMap::new();
+    for mat in item.mats() {
+        let sub_bases = base_mats_of(items, &mat.code);
+        if sub_bases.is_empty() {
+            *consolidated.entry(mat.code.clone()).or_default() += mat.quantity;
+        } else {
+            for b in sub_bases {
+                *consolidated.entry(b.code).or_default() += b.quantity * mat.quantity;
+            }
+        }
+    }
+    consolidated
+        .into_iter()
+        .map(|(code, quantity)| SimpleItemSchema::new(code, quantity))
+        .collect_vec()
+}
+
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Hash, Display, AsRefStr, EnumIter, EnumString, EnumIs,
+)]
 #[strum(serialize_all = "snake_case")]
 pub enum Type {
     Consumable,
@@ -416,10 +483,19 @@ impl PartialEq<LevelConditionCode> for String {
 #[cfg(test)]
 mod tests {
     use crate::{
-        Code, Quantity,
+        Code, CollectionClient, HasDropTable, Quantity,
         simulator::{DamageType, HasEffects},
-        test_utils::{ITEMS, item, monster},
+        test_utils::{ITEMS, MONSTERS, item, monster},
     };
+    use itertools::Itertools;
+
+    fn codes<T: Code>(entities: impl IntoIterator<Item = T>) -> Vec<String> {
+        entities
+            .into_iter()
+            .map(|e| e.code().to_owned())
+            .sorted()
+            .collect()
+    }
 
     #[test]
     fn item_damage_against() {
@@ -485,4 +561,39 @@ mod tests {
             9
         );
     }
+
+    #[test]
+    fn indices_match_full_scans() {
+        assert_eq!(
+            codes(ITEMS.crafted_with("copper_bar")),
+            codes(ITEMS.iter().filter(|i| i.is_crafted_with("copper_bar")))
+        );
+        assert!(!ITEMS.crafted_with("copper_bar").is_empty());
+        assert_eq!(
+            codes(ITEMS.upgrades_of("copper_dagger")),
+            codes(
+                ITEMS
+                    .iter()
+                    .filter(|i| i.code() != "copper_dagger")
+                    .filter(|i| i.is_upgrade_of(&item("copper_dagger")))
+            )
+        );
+        assert_eq!(
+            codes(ITEMS.crafted_with_base_mat("copper_ore")),
+            codes(
+                ITEMS
+                    .iter()
+                    .filter(|i| ITEMS.is_crafted_with_base_mat(i.code(), "copper_ore"))
+            )
+        );
+        assert_eq!(
+            codes(MONSTERS.dropping("raw_chicken")),
+            codes(
+                MONSTERS
+                    .iter()
+                    .filter(|m| m.drops().iter().any(|d| d.code() == "raw_chicken"))
+            )
+        );
+        assert!(!MONSTERS.dropping("raw_chicken").is_empty());
+    }
 }
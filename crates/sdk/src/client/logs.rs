@@ -0,0 +1,204 @@
+use crate::{CacheConfig, Cached, ClientError};
+use chrono::{DateTime, FixedOffset};
+use derive_more::Deref;
+use itertools::Itertools;
+use log::{error, info};
+use openapi::models::LogSchema;
+use std::sync::{Arc, RwLock};
+
+/// Logs kept in the journal, the oldest ones being dropped past it.
+const MAX_LOGS: usize = 10_000;
+
+type LogsSource = Box<
+    dyn Fn(Option<DateTime<FixedOffset>>) -> Result<Vec<LogSchema>, ClientError>
+        + Send
+        + Sync
+        + 'static,
+>;
+
+/// Local journal of the account characters logs, including the actions taken
+/// outside of the bot. Logs are kept from the oldest to the most recent, up to
+/// the last `MAX_LOGS`.
+#[derive(Clone, Deref)]
+#[deref(forward)]
+pub struct LogsClient(Arc<LogsClientInner>);
+
+pub struct LogsClientInner {
+    cache: CacheConfig,
+    logs: RwLock<Vec<LogSchema>>,
+    fetch: LogsSource,
+}
+
+impl LogsClient {
+    #[must_use]
+    pub(crate) fn new(cache: CacheConfig, fetch: LogsSource) -> Self {
+        Self(Arc::new(LogsClientInner {
+            cache,
+            logs: RwLock::default(),
+            fetch,
+        }))
+    }
+
+    /// Loads the stored logs, then fetches the ones created since.
+    pub fn init(&self) {
+        let mut logs = self
+            .fetch_from_cache()
+            .map(|entry| entry.data)
+            .unwrap_or_default();
+        cap(&mut logs, MAX_LOGS);
+        *self.logs.write().unwrap() = logs;
+        if let Err(e) = self.sync() {
+            error!("failed to sync logs: {e}");
+        }
+        info!("Logs client initialized");
+    }
+
+    /// Fetches the logs created since the `cursor`, stores them and returns how
+    /// many were added.
+    pub fn sync(&self) -> Result<usize, ClientError> {
+        let fetched = (self.fetch)(self.cursor())?;
+        let (added, logs) = {
+            let mut logs = self.logs.write().unwrap();
+            let added = merge(&mut logs, fetched);
+            cap(&mut logs, MAX_LOGS);
+            (added, logs.clone())
+        };
+        if added > 0
+            && let Err(e) = self.cache(&logs)
+        {
+            error!("failed to store logs: {e}");
+        }
+        Ok(added)
+    }
+
+    /// Returns the creation time of the most recent log stored.
+    #[must_use]
+    pub fn cursor(&self) -> Option<DateTime<FixedOffset>> {
+        self.logs.read().unwrap().last().map(|l| l.created_at)
+    }
+
+    #[must_use]
+    pub fn all(&self) -> Vec<LogSchema> {
+        self.logs.read().unwrap().clone()
+    }
+
+    #[must_use]
+    pub fn of(&self, character: &str) -> Vec<LogSchema> {
+        self.logs
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|l| l.character == character)
+            .cloned()
+            .collect_vec()
+    }
+
+    #[must_use]
+    pub fn since(&self, since: DateTime<FixedOffset>) -> Vec<LogSchema> {
+        self.logs
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|l| l.created_at >= since)
+            .cloned()
+            .collect_vec()
+    }
+}
+
+/// Adds the `fetched` logs missing from `logs`, keeping them ordered by
+/// creation time. Logs sharing the cursor timestamp are fetched again, so
+/// duplicates are skipped. Returns the number of logs added.
+fn merge(logs: &mut Vec<LogSchema>, fetched: Vec<LogSchema>) -> usize {
+    let before = logs.len();
+    let cursor = logs.last().map(|l| l.created_at);
+    for log in fetched.into_iter().sorted_by_key(|l| l.created_at) {
+        if cursor.is_some_and(|c| log.created_at <= c) && logs.contains(&log) {
+            continue;
+        }
+        logs.push(log);
+    }
+    logs.sort_by_key(|l| l.created_at);
+    logs.len() - before
+}
+
+/// Drops the oldest `logs` beyond the `max` most recent ones.
+fn cap(logs: &mut Vec<LogSchema>, max: usize) {
+    let excess = logs.len().saturating_sub(max);
+    logs.drain(..excess);
+}
+
+impl Cached<Vec<LogSchema>> for LogsClient {
+    const FILE: &str = "logs";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn cache_config(&self) -> &CacheConfig {
+        &self.cache
+    }
+
+    fn fetch_from_source(&self) -> Result<Vec<LogSchema>, ClientError> {
+        (self.fetch)(None)
+    }
+
+    fn refresh(&self) -> Result<(), ClientError> {
+        self.sync().map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openapi::models::LogType;
+
+    fn log(character: &str, r#type: LogType, created_at: &str) -> LogSchema {
+        LogSchema::new(
+            character.to_owned(),
+            "account".to_owned(),
+            r#type,
+            String::new(),
+            None,
+            0,
+            DateTime::parse_from_rfc3339(created_at).unwrap(),
+        )
+    }
+
+    #[test]
+    fn merge_orders_and_skips_duplicates() {
+        let mut logs = vec![
+            log("char1", LogType::Movement, "2025-01-01T00:00:00Z"),
+            log("char1", LogType::Fight, "2025-01-01T00:00:10Z"),
+        ];
+        let fetched = vec![
+            log("char2", LogType::Crafting, "2025-01-01T00:00:20Z"),
+            log("char2", LogType::Gathering, "2025-01-01T00:00:10Z"),
+            log("char1", LogType::Fight, "2025-01-01T00:00:10Z"),
+        ];
+
+        assert_eq!(merge(&mut logs, fetched), 2);
+        assert_eq!(
+            logs.iter().map(|l| l.r#type).collect_vec(),
+            vec![
+                LogType::Movement,
+                LogType::Fight,
+                LogType::Gathering,
+                LogType::Crafting
+            ]
+        );
+    }
+
+    #[test]
+    fn cap_drops_the_oldest_logs() {
+        let mut logs = vec![
+            log("char1", LogType::Movement, "2025-01-01T00:00:00Z"),
+            log("char1", LogType::Fight, "2025-01-01T00:00:10Z"),
+            log("char1", LogType::Crafting, "2025-01-01T00:00:20Z"),
+        ];
+
+        cap(&mut logs, 2);
+        assert_eq!(
+            logs.iter().map(|l| l.r#type).collect_vec(),
+            vec![LogType::Fight, LogType::Crafting]
+        );
+        cap(&mut logs, 2);
+        assert_eq!(logs.len(), 2);
+    }
+}
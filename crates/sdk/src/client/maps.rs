@@ -1,6 +1,10 @@
 use crate::{
-    Cached, CollectionClient, Data,
-    client::events::EventsClient,
+    CacheConfig, Cached, ClientError, CollectionClient, Data, HasConditions,
+    client::{
+        Snapshot,
+        events::EventsClient,
+        route::{self, Route},
+    },
     entities::{Map, MapHandle, RawMap},
     skill::Skill,
 };
@@ -17,17 +21,26 @@ use std::{
     },
 };
 
-type MapsSource = Box<dyn Fn() -> HashMap<(MapLayer, i32, i32), MapHandle> + Send + Sync + 'static>;
+type MapsSource = Box<
+    dyn Fn() -> Result<HashMap<(MapLayer, i32, i32), MapHandle>, ClientError>
+        + Send
+        + Sync
+        + 'static,
+>;
+
+type ContentIndex = HashMap<String, Vec<(MapLayer, i32, i32)>>;
 
 #[derive(Clone, Deref, CollectionClient)]
 #[deref(forward)]
 #[key((MapLayer, i32, i32))]
 #[element(MapHandle)]
+#[snapshot_path(self.0.snapshot)]
 pub struct MapsClient(Arc<MapsClientInner>);
 
 pub struct MapsClientInner {
-    cache_dir: Box<str>,
-    data: ArcSwap<HashMap<(MapLayer, i32, i32), MapHandle>>,
+    cache: CacheConfig,
+    /// Maps along with the positions of the maps holding each content code.
+    snapshot: ArcSwap<Snapshot<(MapLayer, i32, i32), MapHandle, ContentIndex>>,
     min_x: AtomicI32,
     max_x: AtomicI32,
     min_y: AtomicI32,
@@ -40,10 +53,10 @@ pub struct MapsClientInner {
 
 impl MapsClient {
     #[must_use]
-    pub(crate) fn new(cache_dir: &str, fetch: MapsSource, events: EventsClient) -> Self {
+    pub(crate) fn new(cache: CacheConfig, fetch: MapsSource, events: EventsClient) -> Self {
         Self(Arc::new(MapsClientInner {
-            cache_dir: cache_dir.into(),
-            data: ArcSwap::default(),
+            cache,
+            snapshot: ArcSwap::default(),
             min_x: AtomicI32::new(0),
             max_x: AtomicI32::new(0),
             min_y: AtomicI32::new(0),
@@ -55,10 +68,11 @@ impl MapsClient {
         }))
     }
 
-    pub fn init(&self) {
-        self.data.store(Arc::new(self.fetch()));
+    pub fn init(&self) -> Result<(), ClientError> {
+        self.store(self.fetch()?);
         self.init_sizes();
         info!("Maps client initialized");
+        Ok(())
     }
 
     fn init_sizes(&self) {
@@ -82,6 +96,25 @@ impl MapsClient {
         self.height.store(span(min_y, max_y), Ordering::SeqCst);
     }
 
+    /// Stores a new snapshot of the maps and rebuilds the index of their
+    /// contents.
+    fn store(&self, maps: HashMap<(MapLayer, i32, i32), MapHandle>) {
+        let mut contents = ContentIndex::new();
+        for map in maps.values() {
+            let map = map.load();
+            if let Some(content) = map.content() {
+                contents
+                    .entry(content.code.clone())
+                    .or_default()
+                    .push(map.position());
+            }
+        }
+        self.snapshot.store(Arc::new(Snapshot {
+            data: Arc::new(maps),
+            index: contents,
+        }));
+    }
+
     fn events(&self) -> EventsClient {
         self.events.clone()
     }
@@ -131,30 +164,58 @@ impl MapsClient {
         self.iter().map(|map| map.load()).collect_vec()
     }
 
+    /// Puts back the maps of the expired events and applies the maps of the
+    /// active ones. A new snapshot is stored only if a map changed, the maps
+    /// of the previous one being left untouched.
     pub fn refresh_from_events(&self) {
+        let mut changes = HashMap::new();
         for e in self.events().active() {
-            if e.is_expired()
-                && let Some(map) = self.get(&e.map().position())
-            {
-                map.store(e.previous_map());
+            if e.is_expired() {
+                changes.insert(e.map().position(), e.previous_map());
             }
         }
         self.events().refresh_active();
         for e in self.events().active() {
-            if !e.is_expired()
-                && let Some(map) = self.get(&e.map().position())
-            {
-                map.store(e.map());
+            if !e.is_expired() {
+                changes.insert(e.map().position(), e.map());
             }
         }
+        let data = self.data();
+        changes.retain(|position, map| data.get(position).is_some_and(|m| m.load() != *map));
+        if changes.is_empty() {
+            return;
+        }
+        let mut maps = (*data).clone();
+        for (position, map) in changes {
+            maps.insert(position, MapHandle::from(map));
+        }
+        self.store(maps);
+    }
+
+    /// Returns the map of `maps` with the shortest route from `from`, see
+    /// [`MapsClient::route`].
+    #[must_use]
+    pub fn closest_from_among(
+        &self,
+        from: (MapLayer, i32, i32),
+        maps: &[RawMap],
+        accessible: impl Fn(&dyn HasConditions) -> bool,
+    ) -> Option<(RawMap, Route)> {
+        let targets = maps.iter().map(Map::position).collect_vec();
+        let (target, route) = route::plan(&self.data(), from, &targets, accessible)?;
+        Some((maps[target].clone(), route))
     }
 
-    //TODO: handle layer
+    /// Plans the moves and transitions leading from `from` to `to`, using only
+    /// the maps and transitions whose conditions are accepted by `accessible`.
     #[must_use]
-    pub fn closest_from_among(x: i32, y: i32, maps: &[RawMap]) -> Option<RawMap> {
-        maps.iter()
-            .min_by_key(|m| i32::abs(x - m.x()) + i32::abs(y - m.y()))
-            .cloned()
+    pub fn route(
+        &self,
+        from: (MapLayer, i32, i32),
+        to: (MapLayer, i32, i32),
+        accessible: impl Fn(&dyn HasConditions) -> bool,
+    ) -> Option<Route> {
+        route::plan(&self.data(), from, &[to], accessible).map(|(_, route)| route)
     }
 
     #[must_use]
@@ -167,9 +228,13 @@ impl MapsClient {
 
     #[must_use]
     pub fn with_content_code(&self, code: &str) -> Vec<RawMap> {
-        self.all_raw()
+        let snapshot = self.snapshot.load();
+        snapshot
+            .index
+            .get(code)
             .into_iter()
-            .filter_map(|m| m.content_code_is(code).then_some(m))
+            .flatten()
+            .filter_map(|position| Some(snapshot.data.get(position)?.load()))
             .collect()
     }
 
@@ -239,18 +304,20 @@ impl MapsClient {
 
 impl Cached<HashMap<(MapLayer, i32, i32), MapHandle>> for MapsClient {
     const FILE: &'static str = "maps";
+    const SCHEMA_VERSION: u32 = 1;
 
-    fn cache_dir(&self) -> &str {
-        &self.cache_dir
+    fn cache_config(&self) -> &CacheConfig {
+        &self.cache
     }
 
-    fn fetch_from_source(&self) -> HashMap<(MapLayer, i32, i32), MapHandle> {
+    fn fetch_from_source(&self) -> Result<HashMap<(MapLayer, i32, i32), MapHandle>, ClientError> {
         (self.fetch)()
     }
 
-    fn refresh(&self) {
-        self.data.store(Arc::new(self.fetch_from_source()));
+    fn refresh(&self) -> Result<(), ClientError> {
+        self.store(self.fetch_and_cache()?);
         self.init_sizes();
+        Ok(())
     }
 }
 
@@ -264,11 +331,41 @@ const fn span(min: i32, max: i32) -> u32 {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_utils::MAPS;
+    use crate::{entities::Map, test_utils::MAPS};
+    use itertools::Itertools;
+    use std::sync::Arc;
 
     #[test]
     fn test_bound() {
         assert_eq!(MAPS.height(), 28);
         assert_eq!(MAPS.width(), 17);
     }
+
+    #[test]
+    fn indexes_content_codes() {
+        let scanned = MAPS
+            .all_raw()
+            .into_iter()
+            .filter(|m| m.content_code_is("chicken"))
+            .map(|m| m.position())
+            .sorted()
+            .collect_vec();
+
+        assert!(!scanned.is_empty());
+        assert_eq!(
+            MAPS.with_content_code("chicken")
+                .iter()
+                .map(Map::position)
+                .sorted()
+                .collect_vec(),
+            scanned
+        );
+    }
+
+    #[test]
+    fn unchanged_events_keep_the_snapshot() {
+        let before = MAPS.snapshot.load_full();
+        MAPS.refresh_from_events();
+        assert!(Arc::ptr_eq(&before, &MAPS.snapshot.load_full()));
+    }
 }
@@ -1,42 +1,67 @@
 use crate::{
-    Cached, EventBus,
-    client::character::request_handler::CharacterHttpRequestHandler,
+    CACHE_TTL, CacheConfig, Cached, EventBus, cache,
+    client::{
+        account::CharacterHandlerBuilder,
+        character::{
+            offline_handler::OfflineRequestHandler, request_handler::CharacterHttpRequestHandler,
+        },
+    },
     entities::{
         ActiveEvent, Event, Item, MapHandle, Monster, Npc, NpcItem, Resource, Task, TaskReward,
     },
 };
 use api::ArtifactApi;
 use derive_more::Deref;
-use openapi::models::MapLayer;
+use log::{error, warn};
+use openapi::models::{CharacterSchema, MapLayer};
 use std::{
     borrow::Borrow,
     collections::HashMap,
+    error::Error as StdError,
     hash::Hash,
     sync::Arc,
-    thread::{self},
+    thread::{self, Scope, ScopedJoinHandle},
 };
 
 pub mod account;
 pub mod bank;
+pub mod builder;
 pub mod character;
 pub mod error;
 pub mod events;
 pub mod grand_exchange;
 pub mod items;
+pub mod logs;
 pub mod maps;
 pub mod monsters;
 pub mod npcs;
 pub mod npcs_items;
 pub mod resources;
+pub mod route;
 pub mod server;
+pub mod simulation;
 pub mod tasks;
 pub mod tasks_rewards;
 
 pub use crate::client::{
-    account::AccountClient, bank::BankClient, character::CharacterClient, error::ClientError,
-    events::EventsClient, grand_exchange::GrandExchangeClient, items::ItemsClient,
-    maps::MapsClient, monsters::MonstersClient, npcs::NpcsClient, npcs_items::NpcsItemsClient,
-    resources::ResourcesClient, server::ServerClient, tasks::TasksClient,
+    account::AccountClient,
+    bank::BankClient,
+    builder::ClientBuilder,
+    character::CharacterClient,
+    error::{ClientError, InitError, Subsystem},
+    events::EventsClient,
+    grand_exchange::GrandExchangeClient,
+    items::ItemsClient,
+    logs::LogsClient,
+    maps::MapsClient,
+    monsters::MonstersClient,
+    npcs::NpcsClient,
+    npcs_items::NpcsItemsClient,
+    resources::ResourcesClient,
+    route::{Route, RouteStep},
+    server::ServerClient,
+    simulation::SimulationClient,
+    tasks::TasksClient,
     tasks_rewards::TasksRewardsClient,
 };
 
@@ -129,6 +154,22 @@ pub trait Data: private::Sealed {
     fn data(&self) -> Arc<HashMap<Self::Key, Self::Entity>>;
 }
 
+/// Data of a client along with the indices built from it, stored in a single
+/// `ArcSwap` so that readers never see the indices of another snapshot.
+pub(crate) struct Snapshot<K, V, I> {
+    pub data: Arc<HashMap<K, V>>,
+    pub index: I,
+}
+
+impl<K, V, I: Default> Default for Snapshot<K, V, I> {
+    fn default() -> Self {
+        Self {
+            data: Arc::new(HashMap::new()),
+            index: I::default(),
+        }
+    }
+}
+
 #[derive(Clone, Deref)]
 #[deref(forward)]
 pub struct Client(Arc<ClientInner>);
@@ -144,157 +185,216 @@ pub struct ClientInner {
     pub maps: MapsClient,
     pub npcs: NpcsClient,
     pub grand_exchange: GrandExchangeClient,
+    pub simulation: SimulationClient,
+    pub logs: LogsClient,
     pub event_bus: EventBus,
+    cache: CacheConfig,
 }
 
 impl Client {
-    #[must_use]
+    /// Builds the clients on top of `api`, without fetching any data.
+    pub(crate) fn with_api(api: ArtifactApi, account_name: String, cache: CacheConfig) -> Self {
+        let handler_api = api.clone();
+        Self::with_handler(
+            api,
+            account_name,
+            cache,
+            Box::new(move |data, account, server, event_bus| {
+                Arc::new(CharacterHttpRequestHandler::new(
+                    handler_api.clone(),
+                    data,
+                    account,
+                    server,
+                    event_bus,
+                ))
+            }),
+        )
+    }
+
+    /// Builds a client from the cache directory only, without token nor
+    /// network.
+    ///
+    /// Characters are the ones cached by the last online run, if any, and
+    /// their actions fail with [`RequestError::Offline`]. The bank, the
+    /// achievements and the pending items are left empty.
+    ///
+    /// [`RequestError::Offline`]: character::error::RequestError::Offline
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`Subsystem`] whose cache could not be read.
+    pub fn from_cache(cache_dir: &str) -> Result<Self, InitError> {
+        let cache = CacheConfig::new(cache_dir, CACHE_TTL);
+        let characters = cached_characters(&cache);
+        let account_name = characters
+            .as_ref()
+            .ok()
+            .and_then(|characters| characters.first())
+            .map(|c| c.account.clone())
+            .unwrap_or_default();
+        let client = Self::with_handler(
+            ArtifactApi::offline(),
+            account_name,
+            cache,
+            Box::new(|_, _, _, _| Arc::new(OfflineRequestHandler)),
+        );
+        thread::scope(|s| {
+            let mut handles = client.spawn_init(s);
+            if characters.is_ok() {
+                handles.push((Subsystem::Characters, s.spawn(|| client.load_characters())));
+            } else {
+                warn!("no cached characters, the offline client has none");
+            }
+            join_all(handles)
+        })?;
+        Ok(client)
+    }
+
     #[allow(clippy::too_many_lines)]
-    pub fn new(url: String, token: String, cache_dir: &str) -> Self {
+    fn with_handler(
+        api: ArtifactApi,
+        account_name: String,
+        cache: CacheConfig,
+        create_handler: CharacterHandlerBuilder,
+    ) -> Self {
         let event_bus = EventBus::new(64);
-        let api = ArtifactApi::new(url, token);
-        let account_name = api.account.details().unwrap().username;
         let bank = BankClient::new(
-            make_fetcher(api.clone(), |api| api.bank.get_details().unwrap()),
-            make_fetcher(api.clone(), |api| api.bank.get_items().unwrap()),
+            make_fetcher(api.clone(), |api| api.bank.get_details().map_err(api_error)),
+            make_fetcher(api.clone(), |api| api.bank.get_items().map_err(api_error)),
         );
         let account = {
             let api_characters = api.clone();
             let api_achievements = api.clone();
             let api_pending = api.clone();
-            let api_handler = api.clone();
             AccountClient::new(
                 account_name,
+                cache.clone(),
                 bank,
                 Box::new(move |name| {
                     api_characters
                         .account
                         .characters(name)
                         .map(|res| res.data)
-                        .map_err(|e| ClientError::Api(Box::new(e)))
+                        .map_err(api_error)
                 }),
                 Box::new(move |name| {
                     api_achievements
                         .account
                         .achievements(name)
-                        .map_err(|e| ClientError::Api(Box::new(e)))
-                }),
-                Box::new(move || {
-                    api_pending
-                        .account
-                        .pending_items()
-                        .map_err(|e| ClientError::Api(Box::new(e)))
-                }),
-                Box::new(move |data, account, server, event_bus| {
-                    Arc::new(CharacterHttpRequestHandler::new(
-                        api_handler.clone(),
-                        data,
-                        account,
-                        server,
-                        event_bus,
-                    ))
+                        .map_err(api_error)
                 }),
+                Box::new(move || api_pending.account.pending_items().map_err(api_error)),
+                create_handler,
             )
         };
-        let server = ServerClient::new(api.clone());
+        let server = ServerClient::new(api.clone(), cache.clone());
         let events = EventsClient::new(
-            cache_dir,
+            cache.clone(),
             make_fetcher(api.clone(), |api| {
-                api.events
+                Ok(api
+                    .events
                     .get_all()
-                    .unwrap()
+                    .map_err(api_error)?
                     .into_iter()
                     .map(|event| (event.code.clone(), Event::new(event)))
-                    .collect()
+                    .collect())
             }),
             make_fetcher(api.clone(), |api| {
-                api.events
+                Ok(api
+                    .events
                     .get_active()
+                    .map_err(api_error)?
                     .into_iter()
-                    .flatten()
                     .map(ActiveEvent::new)
-                    .collect()
+                    .collect())
             }),
         );
         let resources = ResourcesClient::new(
-            cache_dir,
+            cache.clone(),
             make_fetcher(api.clone(), |api| {
-                api.resources
+                Ok(api
+                    .resources
                     .get_all()
-                    .unwrap()
+                    .map_err(api_error)?
                     .into_iter()
                     .map(|r| (r.code.clone(), Resource::new(r)))
-                    .collect()
+                    .collect())
             }),
             events.clone(),
         );
         let monsters = MonstersClient::new(
-            cache_dir,
+            cache.clone(),
             make_fetcher(api.clone(), |api| {
-                api.monsters
+                Ok(api
+                    .monsters
                     .get_all()
-                    .unwrap()
+                    .map_err(api_error)?
                     .into_iter()
                     .map(|m| (m.code.clone(), Monster::new(m)))
-                    .collect()
+                    .collect())
             }),
             events.clone(),
         );
         let tasks_rewards = TasksRewardsClient::new(
-            cache_dir,
+            cache.clone(),
             make_fetcher(api.clone(), |api| {
-                api.tasks
+                Ok(api
+                    .tasks
                     .get_rewards()
-                    .unwrap()
+                    .map_err(api_error)?
                     .into_iter()
                     .map(|tr| (tr.code.clone(), TaskReward::new(tr)))
-                    .collect()
+                    .collect())
             }),
         );
         let tasks = TasksClient::new(
-            cache_dir,
+            cache.clone(),
             make_fetcher(api.clone(), |api| {
-                api.tasks
+                Ok(api
+                    .tasks
                     .get_all()
-                    .unwrap()
+                    .map_err(api_error)?
                     .into_iter()
                     .map(|task| (task.code.clone(), Task::new(task)))
-                    .collect()
+                    .collect())
             }),
             tasks_rewards.clone(),
         );
         let npcs_items = NpcsItemsClient::new(
-            cache_dir,
+            cache.clone(),
             make_fetcher(api.clone(), |api| {
-                api.npcs
+                Ok(api
+                    .npcs
                     .get_items()
-                    .unwrap()
+                    .map_err(api_error)?
                     .into_iter()
                     .map(|npc| (npc.code.clone(), NpcItem::new(npc)))
-                    .collect()
+                    .collect())
             }),
         );
         let npcs = NpcsClient::new(
-            cache_dir,
+            cache.clone(),
             make_fetcher(api.clone(), |api| {
-                api.npcs
+                Ok(api
+                    .npcs
                     .get_all()
-                    .unwrap()
+                    .map_err(api_error)?
                     .into_iter()
                     .map(|npc| (npc.code.clone(), Npc::new(npc)))
-                    .collect()
+                    .collect())
             }),
             npcs_items,
         );
         let items = ItemsClient::new(
-            cache_dir,
+            cache.clone(),
             make_fetcher(api.clone(), |api| {
-                api.items
+                Ok(api
+                    .items
                     .get_all()
-                    .unwrap()
+                    .map_err(api_error)?
                     .into_iter()
                     .map(|i| (i.code.clone(), Item::new(i)))
-                    .collect()
+                    .collect())
             }),
             resources.clone(),
             monsters.clone(),
@@ -302,17 +402,30 @@ impl Client {
             npcs.clone(),
         );
         let maps = MapsClient::new(
-            cache_dir,
+            cache.clone(),
             make_fetcher(api.clone(), |api| {
-                api.maps
+                Ok(api
+                    .maps
                     .get_all()
-                    .unwrap()
+                    .map_err(api_error)?
                     .into_iter()
                     .map(|m| ((m.layer, m.x, m.y), MapHandle::new(m)))
-                    .collect::<HashMap<(MapLayer, i32, i32), MapHandle>>()
+                    .collect::<HashMap<(MapLayer, i32, i32), MapHandle>>())
             }),
             events.clone(),
         );
+        let logs = {
+            let api = api.clone();
+            LogsClient::new(
+                cache.clone(),
+                Box::new(move |since| {
+                    since
+                        .map_or_else(|| api.logs.all(), |since| api.logs.all_since(since))
+                        .map_err(api_error)
+                }),
+            )
+        };
+        let simulation = SimulationClient::new(api.clone());
         let grand_exchange = GrandExchangeClient::new(api);
         Self(Arc::new(ClientInner {
             account,
@@ -325,54 +438,117 @@ impl Client {
             maps,
             npcs,
             grand_exchange,
+            simulation,
+            logs,
             event_bus,
+            cache,
         }))
     }
 
-    pub fn init(&self) {
+    /// Initializes the server client, so that caches from another server
+    /// version or season are refreshed, then every other client concurrently,
+    /// each one falling back to its cache when the source is unreachable.
+    pub(crate) fn init(&self) -> Result<(), InitError> {
+        self.server
+            .init()
+            .map_err(|e| InitError::new(Subsystem::Server, e))?;
+        self.cache.set_server(&self.server.status());
+        thread::scope(|s| {
+            let mut handles = vec![
+                (Subsystem::Account, s.spawn(|| self.account.init())),
+                (Subsystem::Bank, s.spawn(|| self.account.bank().init())),
+                (Subsystem::Characters, s.spawn(|| self.load_characters())),
+            ];
+            handles.extend(self.spawn_init(s));
+            join_all(handles)
+        })
+    }
+
+    /// Initializes the client while its API is unreachable: the server
+    /// status, the characters and the game data are read from the cache while
+    /// the bank, the achievements and the pending items are left empty.
+    pub(crate) fn init_unreachable(&self) -> Result<(), InitError> {
+        self.server
+            .init()
+            .map_err(|e| InitError::new(Subsystem::Server, e))?;
+        self.cache.set_server(&self.server.status());
+        warn!("API unreachable, bank, achievements and pending items are left empty");
         thread::scope(|s| {
-            s.spawn(|| self.server.init());
-            s.spawn(|| self.account.init());
-            s.spawn(|| self.account.bank().init());
-            s.spawn(|| {
-                self.account.load_characters(
-                    &self.items,
-                    &self.resources,
-                    &self.monsters,
-                    &self.maps,
-                    &self.npcs,
-                    &self.tasks,
-                    &self.server,
-                    &self.grand_exchange,
-                    &self.event_bus,
-                )
-            });
-            s.spawn(|| self.maps.init());
-            s.spawn(|| self.items.init());
-            s.spawn(|| self.resources.init());
-            s.spawn(|| self.monsters.init());
-            s.spawn(|| self.npcs.init());
-            s.spawn(|| self.npcs.items().init());
-            s.spawn(|| self.tasks.init());
-            s.spawn(|| self.tasks.rewards().init());
-            s.spawn(|| self.events.init());
-        });
+            let mut handles = vec![(Subsystem::Characters, s.spawn(|| self.load_characters()))];
+            handles.extend(self.spawn_init(s));
+            join_all(handles)
+        })
+    }
+
+    fn load_characters(&self) -> Result<(), ClientError> {
+        self.account.load_characters(
+            &self.items,
+            &self.resources,
+            &self.monsters,
+            &self.maps,
+            &self.npcs,
+            &self.tasks,
+            &self.server,
+            &self.grand_exchange,
+            &self.event_bus,
+        )
     }
 
+    /// Spawns the initialization of the game data clients, each one falling
+    /// back to its cache when the source is unreachable.
+    fn spawn_init<'scope>(
+        &'scope self,
+        s: &'scope Scope<'scope, '_>,
+    ) -> Vec<(Subsystem, ScopedJoinHandle<'scope, Result<(), ClientError>>)> {
+        vec![
+            (Subsystem::Maps, s.spawn(|| self.maps.init())),
+            (Subsystem::Items, s.spawn(|| self.items.init())),
+            (Subsystem::Resources, s.spawn(|| self.resources.init())),
+            (Subsystem::Monsters, s.spawn(|| self.monsters.init())),
+            (Subsystem::Npcs, s.spawn(|| self.npcs.init())),
+            (Subsystem::NpcsItems, s.spawn(|| self.npcs.items().init())),
+            (Subsystem::Tasks, s.spawn(|| self.tasks.init())),
+            (
+                Subsystem::TasksRewards,
+                s.spawn(|| self.tasks.rewards().init()),
+            ),
+            (Subsystem::Events, s.spawn(|| self.events.init())),
+            (
+                Subsystem::Logs,
+                s.spawn(|| {
+                    self.logs.init();
+                    Ok(())
+                }),
+            ),
+        ]
+    }
+
+    /// Refreshes the game data from the API, keeping the current data of the
+    /// clients whose source is unreachable.
     pub fn refresh_data(&self) {
-        self.server.update_status();
+        match self.server.update_status() {
+            Ok(()) => self.cache.set_server(&self.server.status()),
+            Err(e) => error!("failed to refresh server status: {e}"),
+        }
         // self.account.refresh();
         // self.account.bank().refresh();
         // self.account.characters().refresh();
-        self.maps.refresh();
-        self.items.refresh();
-        self.resources.refresh();
-        self.monsters.refresh();
-        self.npcs.refresh();
-        self.npcs.items().refresh();
-        self.tasks.refresh();
-        self.tasks.rewards().refresh();
-        self.events.refresh();
+        for (subsystem, result) in [
+            (Subsystem::Maps, self.maps.refresh()),
+            (Subsystem::Items, self.items.refresh()),
+            (Subsystem::Resources, self.resources.refresh()),
+            (Subsystem::Monsters, self.monsters.refresh()),
+            (Subsystem::Npcs, self.npcs.refresh()),
+            (Subsystem::NpcsItems, self.npcs.items().refresh()),
+            (Subsystem::Tasks, self.tasks.refresh()),
+            (Subsystem::TasksRewards, self.tasks.rewards().refresh()),
+            (Subsystem::Events, self.events.refresh()),
+            (Subsystem::Logs, self.logs.refresh()),
+        ] {
+            if let Err(e) = result {
+                error!("failed to refresh {subsystem}: {e}");
+            }
+        }
     }
 
     #[must_use]
@@ -413,3 +589,53 @@ where
 {
     Box::new(move || fetch(api.clone()))
 }
+
+/// Joins the initialization `handles`, returning the first failure.
+fn join_all(
+    handles: Vec<(Subsystem, ScopedJoinHandle<'_, Result<(), ClientError>>)>,
+) -> Result<(), InitError> {
+    handles.into_iter().try_for_each(|(subsystem, handle)| {
+        handle
+            .join()
+            .unwrap()
+            .map_err(|e| InitError::new(subsystem, e))
+    })
+}
+
+/// Reads the characters stored in the cache `config` by the last online run.
+fn cached_characters(config: &CacheConfig) -> anyhow::Result<Vec<CharacterSchema>> {
+    cache::read::<Vec<CharacterSchema>>(
+        config,
+        <AccountClient as Cached<Vec<CharacterSchema>>>::FILE,
+        <AccountClient as Cached<Vec<CharacterSchema>>>::SCHEMA_VERSION,
+    )
+    .map(|entry| entry.data)
+}
+
+/// Returns the account name stored in the cache `config` along with its
+/// characters, if any.
+pub(crate) fn cached_account_name(config: &CacheConfig) -> Option<String> {
+    cached_characters(config)
+        .ok()?
+        .first()
+        .map(|c| c.account.clone())
+}
+
+fn api_error<E: StdError + Send + Sync + 'static>(error: E) -> ClientError {
+    ClientError::Api(Box::new(error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_offline_client_from_fixtures() {
+        let client =
+            Client::from_cache(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures")).unwrap();
+
+        assert!(client.items.get("copper_dagger").is_some());
+        assert!(client.monsters.get("chicken").is_some());
+        assert!(client.account.characters().is_empty());
+    }
+}
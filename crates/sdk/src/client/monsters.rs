@@ -1,9 +1,11 @@
 use crate::{
-    Cached, CanProvideXp, Code, CollectionClient, HasDropTable, Level,
-    client::events::EventsClient,
+    CACHE_TTL, CacheConfig, Cached, CanProvideXp, ClientError, Code, CollectionClient,
+    HasDropTable, Level,
+    client::{Snapshot, events::EventsClient},
     entities::{EventSchemaExt, Monster},
 };
-type MonstersSource = Box<dyn Fn() -> HashMap<String, Monster> + Send + Sync + 'static>;
+type MonstersSource =
+    Box<dyn Fn() -> Result<HashMap<String, Monster>, ClientError> + Send + Sync + 'static>;
 
 use arc_swap::ArcSwap;
 use derive_more::Deref;
@@ -14,46 +16,71 @@ use std::{collections::HashMap, sync::Arc};
 #[derive(Clone, Deref, CollectionClient)]
 #[deref(forward)]
 #[element(Monster)]
+#[snapshot_path(self.0.snapshot)]
 pub struct MonstersClient(Arc<MonstersClientInner>);
 
 pub struct MonstersClientInner {
-    cache_dir: Box<str>,
-    data: ArcSwap<HashMap<String, Monster>>,
+    cache: CacheConfig,
+    /// Monsters along with the codes of the monsters dropping each item.
+    snapshot: ArcSwap<Snapshot<String, Monster, HashMap<String, Vec<String>>>>,
     fetch: MonstersSource,
     events: EventsClient,
 }
 
 impl MonstersClient {
     #[must_use]
-    pub(crate) fn new(cache_dir: &str, fetch: MonstersSource, events: EventsClient) -> Self {
+    pub(crate) fn new(cache: CacheConfig, fetch: MonstersSource, events: EventsClient) -> Self {
         Self(Arc::new(MonstersClientInner {
-            cache_dir: cache_dir.into(),
-            data: ArcSwap::default(),
+            cache,
+            snapshot: ArcSwap::default(),
             fetch,
             events,
         }))
     }
 
-    #[must_use]
-    pub fn from_cache(path: &str) -> Self {
+    pub fn from_cache(path: &str) -> Result<Self, ClientError> {
         let client = Self::new(
-            path,
-            Box::new(|| unreachable!("MonstersClient::from_cache has no API fallback")),
-            EventsClient::from_cache(path),
+            CacheConfig::new(path, CACHE_TTL),
+            Box::new(|| Err(ClientError::Unavailable("monsters"))),
+            EventsClient::from_cache(path)?,
         );
-        client.init();
-        client
+        client.init()?;
+        Ok(client)
     }
 
-    pub fn init(&self) {
-        self.data.store(Arc::new(self.fetch()));
+    pub fn init(&self) -> Result<(), ClientError> {
+        self.store(self.fetch()?);
         info!("Monster client initilized");
+        Ok(())
+    }
+
+    /// Stores a new snapshot of the monsters and rebuilds the index of their
+    /// drops.
+    fn store(&self, monsters: HashMap<String, Monster>) {
+        let mut droppers: HashMap<String, Vec<String>> = HashMap::new();
+        for m in monsters.values() {
+            for drop in m.drops() {
+                droppers
+                    .entry(drop.code().to_owned())
+                    .or_default()
+                    .push(m.code().to_owned());
+            }
+        }
+        self.0.snapshot.store(Arc::new(Snapshot {
+            data: Arc::new(monsters),
+            index: droppers,
+        }));
     }
 
     #[must_use]
     pub fn dropping(&self, item_code: &str) -> Vec<Monster> {
-        self.iter()
-            .filter(|m| m.drops().iter().any(|d| d.code() == item_code))
+        let snapshot = self.snapshot.load();
+        snapshot
+            .index
+            .get(item_code)
+            .into_iter()
+            .flatten()
+            .filter_map(|code| snapshot.data.get(code).cloned())
             .collect_vec()
     }
 
@@ -80,16 +107,18 @@ impl MonstersClient {
 
 impl Cached<HashMap<String, Monster>> for MonstersClient {
     const FILE: &'static str = "monsters";
+    const SCHEMA_VERSION: u32 = 1;
 
-    fn cache_dir(&self) -> &str {
-        &self.cache_dir
+    fn cache_config(&self) -> &CacheConfig {
+        &self.cache
     }
 
-    fn fetch_from_source(&self) -> HashMap<String, Monster> {
+    fn fetch_from_source(&self) -> Result<HashMap<String, Monster>, ClientError> {
         (self.fetch)()
     }
 
-    fn refresh(&self) {
-        self.0.data.store(Arc::new(self.fetch_from_source()));
+    fn refresh(&self) -> Result<(), ClientError> {
+        self.store(self.fetch_and_cache()?);
+        Ok(())
     }
 }
@@ -1,11 +1,15 @@
-use crate::{Cached, Code, CollectionClient, client::npcs_items::NpcsItemsClient, entities::Npc};
+use crate::{
+    CACHE_TTL, CacheConfig, Cached, ClientError, Code, CollectionClient,
+    client::npcs_items::NpcsItemsClient, entities::Npc,
+};
 use arc_swap::ArcSwap;
 use derive_more::Deref;
 use itertools::Itertools;
 use log::info;
 use std::{collections::HashMap, sync::Arc};
 
-type NpcsSource = Box<dyn Fn() -> HashMap<String, Npc> + Send + Sync + 'static>;
+type NpcsSource =
+    Box<dyn Fn() -> Result<HashMap<String, Npc>, ClientError> + Send + Sync + 'static>;
 
 #[derive(Clone, Deref, CollectionClient)]
 #[deref(forward)]
@@ -13,7 +17,7 @@ type NpcsSource = Box<dyn Fn() -> HashMap<String, Npc> + Send + Sync + 'static>;
 pub struct NpcsClient(Arc<NpcsClientInner>);
 
 pub struct NpcsClientInner {
-    cache_dir: Box<str>,
+    cache: CacheConfig,
     data: ArcSwap<HashMap<String, Npc>>,
     fetch: NpcsSource,
     items: NpcsItemsClient,
@@ -21,29 +25,29 @@ pub struct NpcsClientInner {
 
 impl NpcsClient {
     #[must_use]
-    pub(crate) fn new(cache_dir: &str, fetch: NpcsSource, items: NpcsItemsClient) -> Self {
+    pub(crate) fn new(cache: CacheConfig, fetch: NpcsSource, items: NpcsItemsClient) -> Self {
         Self(Arc::new(NpcsClientInner {
-            cache_dir: cache_dir.into(),
+            cache,
             data: ArcSwap::default(),
             fetch,
             items,
         }))
     }
 
-    #[must_use]
-    pub fn from_cache(path: &str) -> Self {
+    pub fn from_cache(path: &str) -> Result<Self, ClientError> {
         let client = Self::new(
-            path,
-            Box::new(|| unreachable!("NpcsClient::from_cache has no API fallback")),
-            NpcsItemsClient::from_cache(path),
+            CacheConfig::new(path, CACHE_TTL),
+            Box::new(|| Err(ClientError::Unavailable("npcs"))),
+            NpcsItemsClient::from_cache(path)?,
         );
-        client.init();
-        client
+        client.init()?;
+        Ok(client)
     }
 
-    pub fn init(&self) {
-        self.data.store(Arc::new(self.fetch()));
+    pub fn init(&self) -> Result<(), ClientError> {
+        self.data.store(Arc::new(self.fetch()?));
         info!("Npcs client initilized");
+        Ok(())
     }
 
     #[must_use]
@@ -72,16 +76,18 @@ impl NpcsClient {
 
 impl Cached<HashMap<String, Npc>> for NpcsClient {
     const FILE: &'static str = "npcs";
+    const SCHEMA_VERSION: u32 = 1;
 
-    fn cache_dir(&self) -> &str {
-        &self.cache_dir
+    fn cache_config(&self) -> &CacheConfig {
+        &self.cache
     }
 
-    fn fetch_from_source(&self) -> HashMap<String, Npc> {
+    fn fetch_from_source(&self) -> Result<HashMap<String, Npc>, ClientError> {
         (self.fetch)()
     }
 
-    fn refresh(&self) {
-        self.data.store(Arc::new(self.fetch_from_source()));
+    fn refresh(&self) -> Result<(), ClientError> {
+        self.data.store(Arc::new(self.fetch_and_cache()?));
+        Ok(())
     }
 }
@@ -1,5 +1,6 @@
-use crate::{Cached, entities::NpcItem};
-type NpcsItemsSource = Box<dyn Fn() -> HashMap<String, NpcItem> + Send + Sync + 'static>;
+use crate::{CACHE_TTL, CacheConfig, Cached, ClientError, entities::NpcItem};
+type NpcsItemsSource =
+    Box<dyn Fn() -> Result<HashMap<String, NpcItem>, ClientError> + Send + Sync + 'static>;
 
 use arc_swap::ArcSwap;
 use derive_more::Deref;
@@ -13,49 +14,51 @@ use std::{collections::HashMap, sync::Arc};
 pub struct NpcsItemsClient(Arc<NpcsItemsClientInner>);
 
 pub struct NpcsItemsClientInner {
-    cache_dir: Box<str>,
+    cache: CacheConfig,
     data: ArcSwap<HashMap<String, NpcItem>>,
     fetch: NpcsItemsSource,
 }
 
 impl NpcsItemsClient {
     #[must_use]
-    pub(crate) fn new(cache_dir: &str, fetch: NpcsItemsSource) -> Self {
+    pub(crate) fn new(cache: CacheConfig, fetch: NpcsItemsSource) -> Self {
         Self(Arc::new(NpcsItemsClientInner {
-            cache_dir: cache_dir.into(),
+            cache,
             data: ArcSwap::default(),
             fetch,
         }))
     }
 
-    #[must_use]
-    pub fn from_cache(path: &str) -> Self {
+    pub fn from_cache(path: &str) -> Result<Self, ClientError> {
         let client = Self::new(
-            path,
-            Box::new(|| unreachable!("NpcsItemsClient::from_cache has no API fallback")),
+            CacheConfig::new(path, CACHE_TTL),
+            Box::new(|| Err(ClientError::Unavailable("npcs items"))),
         );
-        client.init();
-        client
+        client.init()?;
+        Ok(client)
     }
 
-    pub fn init(&self) {
-        self.data.store(Arc::new(self.fetch()));
+    pub fn init(&self) -> Result<(), ClientError> {
+        self.data.store(Arc::new(self.fetch()?));
         info!("Npcs Items client initilized");
+        Ok(())
     }
 }
 
 impl Cached<HashMap<String, NpcItem>> for NpcsItemsClient {
     const FILE: &'static str = "npcs_items";
+    const SCHEMA_VERSION: u32 = 1;
 
-    fn cache_dir(&self) -> &str {
-        &self.cache_dir
+    fn cache_config(&self) -> &CacheConfig {
+        &self.cache
     }
 
-    fn fetch_from_source(&self) -> HashMap<String, NpcItem> {
+    fn fetch_from_source(&self) -> Result<HashMap<String, NpcItem>, ClientError> {
         (self.fetch)()
     }
 
-    fn refresh(&self) {
-        self.data.store(Arc::new(self.fetch_from_source()));
+    fn refresh(&self) -> Result<(), ClientError> {
+        self.data.store(Arc::new(self.fetch_and_cache()?));
+        Ok(())
     }
 }
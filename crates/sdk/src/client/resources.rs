@@ -1,10 +1,11 @@
 use crate::{
-    Cached, Code, CollectionClient, HasDropTable,
-    client::events::EventsClient,
+    CACHE_TTL, CacheConfig, Cached, ClientError, Code, CollectionClient, HasDropTable,
+    client::{Snapshot, events::EventsClient},
     entities::{EventSchemaExt, Resource},
 };
 
-type ResourcesSource = Box<dyn Fn() -> HashMap<String, Resource> + Send + Sync + 'static>;
+type ResourcesSource =
+    Box<dyn Fn() -> Result<HashMap<String, Resource>, ClientError> + Send + Sync + 'static>;
 
 use arc_swap::ArcSwap;
 use derive_more::Deref;
@@ -15,46 +16,71 @@ use std::{collections::HashMap, sync::Arc};
 #[derive(Clone, Deref, CollectionClient)]
 #[deref(forward)]
 #[element(Resource)]
+#[snapshot_path(self.0.snapshot)]
 pub struct ResourcesClient(Arc<ResourcesClientInner>);
 
 pub struct ResourcesClientInner {
-    cache_dir: Box<str>,
-    data: ArcSwap<HashMap<String, Resource>>,
+    cache: CacheConfig,
+    /// Resources along with the codes of the resources dropping each item.
+    snapshot: ArcSwap<Snapshot<String, Resource, HashMap<String, Vec<String>>>>,
     fetch: ResourcesSource,
     events: EventsClient,
 }
 
 impl ResourcesClient {
     #[must_use]
-    pub(crate) fn new(cache_dir: &str, fetch: ResourcesSource, events: EventsClient) -> Self {
+    pub(crate) fn new(cache: CacheConfig, fetch: ResourcesSource, events: EventsClient) -> Self {
         Self(Arc::new(ResourcesClientInner {
-            cache_dir: cache_dir.into(),
-            data: ArcSwap::default(),
+            cache,
+            snapshot: ArcSwap::default(),
             fetch,
             events,
         }))
     }
 
-    #[must_use]
-    pub fn from_cache(path: &str) -> Self {
+    pub fn from_cache(path: &str) -> Result<Self, ClientError> {
         let client = Self::new(
-            path,
-            Box::new(|| unreachable!("ResourcesClient::from_cache has no API fallback")),
-            EventsClient::from_cache(path),
+            CacheConfig::new(path, CACHE_TTL),
+            Box::new(|| Err(ClientError::Unavailable("resources"))),
+            EventsClient::from_cache(path)?,
         );
-        client.init();
-        client
+        client.init()?;
+        Ok(client)
     }
 
-    pub fn init(&self) {
-        self.0.data.store(Arc::new(self.fetch()));
+    pub fn init(&self) -> Result<(), ClientError> {
+        self.store(self.fetch()?);
         info!("Resource client initilized");
+        Ok(())
+    }
+
+    /// Stores a new snapshot of the resources and rebuilds the index of their
+    /// drops.
+    fn store(&self, resources: HashMap<String, Resource>) {
+        let mut droppers: HashMap<String, Vec<String>> = HashMap::new();
+        for r in resources.values() {
+            for drop in r.drops() {
+                droppers
+                    .entry(drop.code().to_owned())
+                    .or_default()
+                    .push(r.code().to_owned());
+            }
+        }
+        self.0.snapshot.store(Arc::new(Snapshot {
+            data: Arc::new(resources),
+            index: droppers,
+        }));
     }
 
     #[must_use]
     pub fn dropping(&self, item_code: &str) -> Vec<Resource> {
-        self.iter()
-            .filter(|r| r.drops().iter().any(|d| d.code() == item_code))
+        let snapshot = self.snapshot.load();
+        snapshot
+            .index
+            .get(item_code)
+            .into_iter()
+            .flatten()
+            .filter_map(|code| snapshot.data.get(code).cloned())
             .collect_vec()
     }
 
@@ -67,16 +93,18 @@ impl ResourcesClient {
 
 impl Cached<HashMap<String, Resource>> for ResourcesClient {
     const FILE: &'static str = "resources";
+    const SCHEMA_VERSION: u32 = 1;
 
-    fn cache_dir(&self) -> &str {
-        &self.cache_dir
+    fn cache_config(&self) -> &CacheConfig {
+        &self.cache
     }
 
-    fn fetch_from_source(&self) -> HashMap<String, Resource> {
+    fn fetch_from_source(&self) -> Result<HashMap<String, Resource>, ClientError> {
         (self.fetch)()
     }
 
-    fn refresh(&self) {
-        self.0.data.store(Arc::new(self.fetch_from_source()));
+    fn refresh(&self) -> Result<(), ClientError> {
+        self.store(self.fetch_and_cache()?);
+        Ok(())
     }
 }
@@ -0,0 +1,300 @@
+use crate::{
+    GOLD, HasConditions,
+    consts::MOVE_TIME,
+    entities::{Map, MapHandle},
+};
+use openapi::models::{ConditionOperator, MapLayer, SimpleItemSchema, TransitionSchema};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Distance equivalent of taking a transition.
+const TRANSITION_DISTANCE: u32 = 1;
+/// Distance added to transitions costing gold or items, so that they are only
+/// taken when no free route is much shorter.
+const PAID_TRANSITION_DISTANCE: u32 = 50;
+
+type Position = (MapLayer, i32, i32);
+
+/// Step of a [`Route`], to be performed in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteStep {
+    /// Move to `(x, y)` on the current layer.
+    Move { x: i32, y: i32 },
+    /// Take the transition of the current map, leading to `destination`.
+    Transition { destination: Position },
+}
+
+/// Moves and transitions leading from a map to another.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Route {
+    pub steps: Vec<RouteStep>,
+    /// Number of tiles walked, transitions included.
+    pub distance: u32,
+    /// Gold consumed by the transitions.
+    pub gold: u32,
+    /// Items consumed by the transitions.
+    pub items: Vec<SimpleItemSchema>,
+}
+
+impl Route {
+    /// Returns the cooldown in seconds accumulated by following the route.
+    #[must_use]
+    pub const fn time(&self) -> u32 {
+        self.distance * MOVE_TIME
+    }
+
+    /// Returns whether `gold` and the items held, as returned by `held`, cover
+    /// the cost of every transition of the route together.
+    pub fn is_affordable(&self, gold: u32, held: impl Fn(&str) -> u32) -> bool {
+        self.gold <= gold
+            && self
+                .items
+                .iter()
+                .fold(HashMap::<&str, u32>::new(), |mut total, item| {
+                    *total.entry(&item.code).or_default() += item.quantity;
+                    total
+                })
+                .into_iter()
+                .all(|(code, quantity)| quantity <= held(code))
+    }
+}
+
+/// Plans the shortest route from `from` to the closest of `targets`,
+/// returning the index of the target reached.
+///
+/// Characters walk between adjacent maps of a layer and take transitions to
+/// change layer or to reach isolated areas. Maps and transitions are only used
+/// when `accessible` accepts their conditions. Since transitions are accepted
+/// one at a time, the total cost of a route can exceed what the character
+/// carries, see [`Route::is_affordable`].
+pub(crate) fn plan(
+    maps: &HashMap<Position, MapHandle>,
+    from: Position,
+    targets: &[Position],
+    accessible: impl Fn(&dyn HasConditions) -> bool,
+) -> Option<(usize, Route)> {
+    let walkable = maps
+        .iter()
+        .filter_map(|(position, map)| {
+            let map = map.load();
+            (!map.is_blocked() && accessible(map.access())).then_some(*position)
+        })
+        .collect::<HashSet<_>>();
+    let transitions = maps
+        .values()
+        .map(MapHandle::load)
+        .filter_map(|map| {
+            let transition = map.transition()?;
+            (walkable.contains(&map.position()) && accessible(transition))
+                .then(|| (map.position(), transition.clone()))
+        })
+        .collect::<Vec<_>>();
+
+    // Nodes are the start, the targets and the ends of the transitions, the
+    // exit of a transition following its entry.
+    let mut nodes = vec![from];
+    nodes.extend(targets.iter().filter(|t| walkable.contains(t)));
+    let first_transition = nodes.len();
+    for (entry, transition) in &transitions {
+        nodes.push(*entry);
+        nodes.push((transition.layer, transition.x, transition.y));
+    }
+    let transition_of = |node: usize| {
+        (node >= first_transition && (node - first_transition).is_multiple_of(2))
+            .then(|| (node - first_transition) / 2)
+    };
+    let edges = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| {
+            let distances = walk_distances(&walkable, *node);
+            let mut edges = nodes
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| i != j)
+                .filter_map(|(j, n)| Some((j, *distances.get(n)?, None)))
+                .collect::<Vec<_>>();
+            if let Some(t) = transition_of(i) {
+                let (gold, items) = cost(&transitions[t].1);
+                let distance = if gold > 0 || !items.is_empty() {
+                    TRANSITION_DISTANCE + PAID_TRANSITION_DISTANCE
+                } else {
+                    TRANSITION_DISTANCE
+                };
+                edges.push((i + 1, distance, Some(t)));
+            }
+            edges
+        })
+        .collect::<Vec<_>>();
+
+    // Dijkstra over the nodes, the graph is small enough for the quadratic
+    // version.
+    let mut distance = vec![u32::MAX; nodes.len()];
+    let mut previous: Vec<Option<(usize, Option<usize>)>> = vec![None; nodes.len()];
+    let mut visited = vec![false; nodes.len()];
+    distance[0] = 0;
+    while let Some(node) = (0..nodes.len())
+        .filter(|&n| !visited[n] && distance[n] < u32::MAX)
+        .min_by_key(|&n| distance[n])
+    {
+        visited[node] = true;
+        for &(next, weight, transition) in &edges[node] {
+            let candidate = distance[node] + weight;
+            if candidate < distance[next] {
+                distance[next] = candidate;
+                previous[next] = Some((node, transition));
+            }
+        }
+    }
+
+    let (target, node) = targets
+        .iter()
+        .enumerate()
+        .filter_map(|(t, target)| Some((t, nodes.iter().position(|n| n == target)?)))
+        .filter(|&(_, n)| distance[n] < u32::MAX)
+        .min_by_key(|&(_, n)| distance[n])?;
+    let mut route = Route::default();
+    let mut current = node;
+    while let Some((prev, transition)) = previous[current] {
+        if let Some(t) = transition {
+            let (gold, items) = cost(&transitions[t].1);
+            route.gold += gold;
+            route.items.extend(items);
+            route.distance += TRANSITION_DISTANCE;
+            route.steps.push(RouteStep::Transition {
+                destination: nodes[current],
+            });
+        } else if nodes[prev] != nodes[current] {
+            route.distance += distance[current] - distance[prev];
+            // Walking through another node is a single move.
+            if !matches!(route.steps.last(), Some(RouteStep::Move { .. })) {
+                let (_, x, y) = nodes[current];
+                route.steps.push(RouteStep::Move { x, y });
+            }
+        }
+        current = prev;
+    }
+    route.steps.reverse();
+    Some((target, route))
+}
+
+/// Returns the number of tiles walked to reach the walkable maps of the layer
+/// of `from`.
+fn walk_distances(walkable: &HashSet<Position>, from: Position) -> HashMap<Position, u32> {
+    let mut distances = HashMap::from([(from, 0)]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(position @ (layer, x, y)) = queue.pop_front() {
+        let distance = distances[&position];
+        for next in [
+            (layer, x + 1, y),
+            (layer, x - 1, y),
+            (layer, x, y + 1),
+            (layer, x, y - 1),
+        ] {
+            if walkable.contains(&next) && !distances.contains_key(&next) {
+                distances.insert(next, distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// Returns the gold and the items consumed by `transition`.
+fn cost(transition: &TransitionSchema) -> (u32, Vec<SimpleItemSchema>) {
+    let mut gold = 0;
+    let mut items = vec![];
+    for condition in transition.conditions().into_iter().flatten() {
+        if condition.operator != ConditionOperator::Cost {
+            continue;
+        }
+        let quantity = condition.value.unsigned_abs();
+        if condition.code == GOLD {
+            gold += quantity;
+        } else {
+            items.push(SimpleItemSchema::new(condition.code.clone(), quantity));
+        }
+    }
+    (gold, items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{client::Data, test_utils::MAPS};
+
+    const ORIGIN: Position = (MapLayer::Overworld, 0, 0);
+
+    fn route(to: Position, accessible: impl Fn(&dyn HasConditions) -> bool) -> Option<Route> {
+        plan(&MAPS.data(), ORIGIN, &[to], accessible).map(|(_, route)| route)
+    }
+
+    #[test]
+    fn walks_on_the_same_layer() {
+        let route = route((MapLayer::Overworld, 2, -3), |_| true).unwrap();
+
+        assert_eq!(route.steps, vec![RouteStep::Move { x: 2, y: -3 }]);
+        assert_eq!(route.distance, 5);
+        assert_eq!(route.time(), 5 * MOVE_TIME);
+    }
+
+    #[test]
+    fn takes_transitions_to_other_layers() {
+        let route = route((MapLayer::Underground, 3, -5), |_| true).unwrap();
+
+        assert_eq!(
+            route.steps,
+            vec![
+                RouteStep::Move { x: 5, y: -3 },
+                RouteStep::Transition {
+                    destination: (MapLayer::Underground, 5, -3)
+                },
+                RouteStep::Move { x: 3, y: -5 },
+            ]
+        );
+        assert_eq!(route.distance, 8 + TRANSITION_DISTANCE + 4);
+        assert_eq!(route.gold, 0);
+    }
+
+    #[test]
+    fn pays_transitions_to_isolated_areas() {
+        let island = (MapLayer::Overworld, -2, 19);
+        let route = route(island, |_| true).unwrap();
+
+        assert_eq!(route.gold, 1000);
+        assert!(route.steps.contains(&RouteStep::Transition {
+            destination: (MapLayer::Overworld, -2, 21)
+        }));
+        let free = |e: &dyn HasConditions| e.conditions().is_none_or(Vec::is_empty);
+        assert!(self::route(island, free).is_none());
+    }
+
+    #[test]
+    fn affordability_sums_the_cost_of_every_transition() {
+        let route = Route {
+            gold: 1000,
+            items: vec![
+                SimpleItemSchema::new("key".to_owned(), 1),
+                SimpleItemSchema::new("key".to_owned(), 1),
+            ],
+            ..Default::default()
+        };
+        let keys = |n| move |code: &str| if code == "key" { n } else { 0 };
+
+        assert!(route.is_affordable(1000, keys(2)));
+        assert!(!route.is_affordable(1000, keys(1)));
+        assert!(!route.is_affordable(999, keys(2)));
+    }
+
+    #[test]
+    fn picks_closest_target() {
+        let targets = [
+            (MapLayer::Underground, 3, -5),
+            (MapLayer::Overworld, 1, 1),
+            (MapLayer::Overworld, -3, 2),
+        ];
+
+        let (target, route) = plan(&MAPS.data(), ORIGIN, &targets, |_| true).unwrap();
+        assert_eq!(target, 1);
+        assert_eq!(route.distance, 2);
+    }
+}
@@ -1,7 +1,8 @@
+use crate::{CacheConfig, Cached, ClientError};
 use api::ArtifactApi;
 use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
 use derive_more::Deref;
-use log::{debug, info};
+use log::{debug, error, info, warn};
 use openapi::models::StatusSchema;
 use std::sync::{Arc, RwLock};
 
@@ -11,30 +12,56 @@ pub struct ServerClient(Arc<ServerClientInner>);
 
 pub struct ServerClientInner {
     api: ArtifactApi,
+    cache: CacheConfig,
     status: RwLock<StatusSchema>,
     time_offset: RwLock<TimeDelta>,
 }
 
 impl ServerClient {
     #[must_use]
-    pub(crate) fn new(api: ArtifactApi) -> Self {
+    pub(crate) fn new(api: ArtifactApi, cache: CacheConfig) -> Self {
         Self(Arc::new(ServerClientInner {
             api,
+            cache,
             status: RwLock::default(),
             time_offset: RwLock::default(),
         }))
     }
 
-    pub fn init(&self) {
-        self.update_offset();
+    /// Fetches the server status and stores it, using the stored one with
+    /// no time offset when the server is unreachable.
+    pub fn init(&self) -> Result<(), ClientError> {
+        match self.update_offset() {
+            Ok(()) => {
+                if let Err(e) = self.cache(&self.status()) {
+                    error!("failed to cache server status: {e}");
+                }
+            }
+            Err(e) => {
+                let Ok(entry) = self.fetch_from_cache() else {
+                    return Err(e);
+                };
+                warn!("using cached server status: {e}");
+                *self.status.write().unwrap() = entry.data;
+            }
+        }
         info!("Server client initilized");
+        Ok(())
     }
 
-    pub fn update_status(&self) {
-        let Some(status) = self.api.server.status() else {
-            return;
-        };
+    pub fn update_status(&self) -> Result<(), ClientError> {
+        let status = self
+            .api
+            .server
+            .status()
+            .ok_or(ClientError::Unavailable("server status"))?;
         *self.status.write().unwrap() = status.data;
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn status(&self) -> StatusSchema {
+        self.status.read().unwrap().clone()
     }
 
     fn server_time(&self) -> DateTime<FixedOffset> {
@@ -51,9 +78,9 @@ impl ServerClient {
         Utc::now() + self.time_offset()
     }
 
-    pub fn update_offset(&self) {
+    pub fn update_offset(&self) -> Result<(), ClientError> {
         let send = Utc::now().fixed_offset();
-        self.update_status();
+        self.update_status()?;
         let recv = Utc::now().fixed_offset();
         let rtt = recv - send;
         *self.time_offset.write().unwrap() = self.server_time() + (rtt / 2) - recv;
@@ -72,5 +99,27 @@ impl ServerClient {
             offset.subsec_millis()
         );
         debug!("synced time: {}", recv + offset);
+        Ok(())
+    }
+}
+
+impl Cached<StatusSchema> for ServerClient {
+    const FILE: &str = "status";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn cache_config(&self) -> &CacheConfig {
+        &self.cache
+    }
+
+    fn fetch_from_source(&self) -> Result<StatusSchema, ClientError> {
+        self.api
+            .server
+            .status()
+            .map(|status| status.data)
+            .ok_or(ClientError::Unavailable("server status"))
+    }
+
+    fn refresh(&self) -> Result<(), ClientError> {
+        self.update_status()
     }
 }
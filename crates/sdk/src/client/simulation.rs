@@ -0,0 +1,46 @@
+use crate::{
+    ClientError, Code,
+    simulator::{FightSimulation, calibration::CalibrationReport},
+};
+use api::ArtifactApi;
+use openapi::models::CombatSimulationDataSchema;
+
+#[derive(Debug, Default, Clone)]
+pub struct SimulationClient {
+    api: ArtifactApi,
+}
+
+impl SimulationClient {
+    #[must_use]
+    pub(crate) const fn new(api: ArtifactApi) -> Self {
+        Self { api }
+    }
+
+    /// Runs the given `simulation` `iterations` times on the server.
+    pub fn simulate(
+        &self,
+        simulation: &FightSimulation,
+        iterations: u32,
+    ) -> Result<CombatSimulationDataSchema, ClientError> {
+        self.api
+            .simulation
+            .fight(
+                simulation.fake_characters(),
+                simulation.monster().code(),
+                iterations,
+            )
+            .map(|res| res.data)
+            .map_err(|e| ClientError::Api(Box::new(e)))
+    }
+
+    /// Runs the given `simulation` both locally and on the server and reports
+    /// how the local simulator compares to the game rules.
+    pub fn calibrate(
+        &self,
+        simulation: &FightSimulation,
+        iterations: u32,
+    ) -> Result<CalibrationReport, ClientError> {
+        let server = self.simulate(simulation, iterations)?;
+        Ok(CalibrationReport::new(simulation, iterations, &server))
+    }
+}
@@ -1,11 +1,12 @@
-use crate::{Cached, TasksRewardsClient, entities::Task};
+use crate::{CacheConfig, Cached, ClientError, TasksRewardsClient, entities::Task};
 use arc_swap::ArcSwap;
 use derive_more::Deref;
 use log::info;
 use sdk_derive::CollectionClient;
-use std::{collections::HashMap, sync::Arc, thread};
+use std::{collections::HashMap, sync::Arc};
 
-type TasksSource = Box<dyn Fn() -> HashMap<String, Task> + Send + Sync + 'static>;
+type TasksSource =
+    Box<dyn Fn() -> Result<HashMap<String, Task>, ClientError> + Send + Sync + 'static>;
 
 #[derive(Clone, Deref, CollectionClient)]
 #[deref(forward)]
@@ -13,7 +14,7 @@ type TasksSource = Box<dyn Fn() -> HashMap<String, Task> + Send + Sync + 'static
 pub struct TasksClient(Arc<TasksClientInner>);
 
 pub struct TasksClientInner {
-    cache_dir: Box<str>,
+    cache: CacheConfig,
     data: ArcSwap<HashMap<String, Task>>,
     fetch: TasksSource,
     rewards: TasksRewardsClient,
@@ -21,21 +22,19 @@ pub struct TasksClientInner {
 
 impl TasksClient {
     #[must_use]
-    pub(crate) fn new(cache_dir: &str, fetch: TasksSource, rewards: TasksRewardsClient) -> Self {
+    pub(crate) fn new(cache: CacheConfig, fetch: TasksSource, rewards: TasksRewardsClient) -> Self {
         Self(Arc::new(TasksClientInner {
-            cache_dir: cache_dir.into(),
+            cache,
             data: ArcSwap::default(),
             fetch,
             rewards,
         }))
     }
 
-    pub fn init(&self) {
-        let () = thread::scope(|s| {
-            let _ = s.spawn(|| self.data.store(Arc::new(self.fetch())));
-            let _ = s.spawn(|| self.rewards().init());
-        });
+    pub fn init(&self) -> Result<(), ClientError> {
+        self.data.store(Arc::new(self.fetch()?));
         info!("Tasks client initilized");
+        Ok(())
     }
 
     #[must_use]
@@ -46,16 +45,18 @@ impl TasksClient {
 
 impl Cached<HashMap<String, Task>> for TasksClient {
     const FILE: &'static str = "tasks";
+    const SCHEMA_VERSION: u32 = 1;
 
-    fn cache_dir(&self) -> &str {
-        &self.cache_dir
+    fn cache_config(&self) -> &CacheConfig {
+        &self.cache
     }
 
-    fn fetch_from_source(&self) -> HashMap<String, Task> {
+    fn fetch_from_source(&self) -> Result<HashMap<String, Task>, ClientError> {
         (self.fetch)()
     }
 
-    fn refresh(&self) {
-        self.data.store(Arc::new(self.fetch_from_source()));
+    fn refresh(&self) -> Result<(), ClientError> {
+        self.data.store(Arc::new(self.fetch_and_cache()?));
+        Ok(())
     }
 }
@@ -1,10 +1,11 @@
-use crate::{Cached, CollectionClient, entities::TaskReward};
+use crate::{CacheConfig, Cached, ClientError, CollectionClient, entities::TaskReward};
 use arc_swap::ArcSwap;
 use derive_more::Deref;
 use log::info;
 use std::{collections::HashMap, sync::Arc};
 
-type TasksRewardsSource = Box<dyn Fn() -> HashMap<String, TaskReward> + Send + Sync + 'static>;
+type TasksRewardsSource =
+    Box<dyn Fn() -> Result<HashMap<String, TaskReward>, ClientError> + Send + Sync + 'static>;
 
 #[derive(Clone, Deref, CollectionClient)]
 #[deref(forward)]
@@ -12,24 +13,25 @@ type TasksRewardsSource = Box<dyn Fn() -> HashMap<String, TaskReward> + Send + S
 pub struct TasksRewardsClient(Arc<TasksRewardsClientInner>);
 
 pub struct TasksRewardsClientInner {
-    cache_dir: Box<str>,
+    cache: CacheConfig,
     data: ArcSwap<HashMap<String, TaskReward>>,
     fetch: TasksRewardsSource,
 }
 
 impl TasksRewardsClient {
     #[must_use]
-    pub(crate) fn new(cache_dir: &str, fetch: TasksRewardsSource) -> Self {
+    pub(crate) fn new(cache: CacheConfig, fetch: TasksRewardsSource) -> Self {
         Self(Arc::new(TasksRewardsClientInner {
-            cache_dir: cache_dir.into(),
+            cache,
             data: ArcSwap::default(),
             fetch,
         }))
     }
 
-    pub fn init(&self) {
-        self.data.store(Arc::new(self.fetch()));
+    pub fn init(&self) -> Result<(), ClientError> {
+        self.data.store(Arc::new(self.fetch()?));
         info!("Tasks rewards client initilized");
+        Ok(())
     }
 
     #[must_use]
@@ -41,16 +43,65 @@ impl TasksRewardsClient {
 
 impl Cached<HashMap<String, TaskReward>> for TasksRewardsClient {
     const FILE: &'static str = "tasks_rewards";
+    const SCHEMA_VERSION: u32 = 1;
 
-    fn cache_dir(&self) -> &str {
-        &self.cache_dir
+    fn cache_config(&self) -> &CacheConfig {
+        &self.cache
     }
 
-    fn fetch_from_source(&self) -> HashMap<String, TaskReward> {
+    fn fetch_from_source(&self) -> Result<HashMap<String, TaskReward>, ClientError> {
         (self.fetch)()
     }
 
-    fn refresh(&self) {
-        self.data.store(Arc::new(self.fetch_from_source()));
+    fn refresh(&self) -> Result<(), ClientError> {
+        self.data.store(Arc::new(self.fetch_and_cache()?));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+    use openapi::models::DropRateSchema;
+    use std::{
+        fs,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    fn rewards() -> HashMap<String, TaskReward> {
+        let reward = TaskReward::new(DropRateSchema::new("tasks_coin".into(), 1, 1, 3));
+        HashMap::from([("tasks_coin".to_owned(), reward)])
+    }
+
+    #[test]
+    fn falls_back_to_cache_when_source_is_unreachable() {
+        let dir = std::env::temp_dir().join(format!("tasks_rewards_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dir = dir.to_str().unwrap();
+        let reachable = Arc::new(AtomicBool::new(true));
+        let source = reachable.clone();
+        let client = TasksRewardsClient::new(
+            CacheConfig::new(dir, TimeDelta::zero()),
+            Box::new(move || {
+                if source.load(Ordering::SeqCst) {
+                    Ok(rewards())
+                } else {
+                    Err(ClientError::Unavailable("tasks rewards"))
+                }
+            }),
+        );
+
+        client.init().unwrap();
+        reachable.store(false, Ordering::SeqCst);
+        assert!(client.refresh().is_err());
+        assert_eq!(client.max_quantity(), 3);
+        let offline = TasksRewardsClient::new(
+            CacheConfig::new(dir, TimeDelta::zero()),
+            Box::new(|| Err(ClientError::Unavailable("tasks rewards"))),
+        );
+        offline.init().unwrap();
+        fs::remove_dir_all(dir).unwrap();
+        assert_eq!(offline.max_quantity(), 3);
     }
 }
@@ -1,6 +1,17 @@
+use chrono::TimeDelta;
+
+pub const API_URL: &str = "https://api.artifactsmmo.com";
+pub const CACHE_DIR: &str = ".cache";
+/// Maximum age of a cache file before its data is fetched again.
+pub const CACHE_TTL: TimeDelta = TimeDelta::days(1);
+
 pub const MAX_LEVEL_DIFF: u32 = 10;
 
 pub const CRAFT_TIME: u32 = 5;
+/// Cooldown in seconds of a move, per map walked.
+pub const MOVE_TIME: u32 = 5;
+/// Rough time in seconds spent completing a task, travel excluded.
+pub const TASK_TIME: u32 = 2000;
 pub const MAX_LEVEL: u32 = 45;
 pub const TASK_CANCEL_PRICE: u32 = 1;
 pub const TASK_EXCHANGE_PRICE: u32 = 6;
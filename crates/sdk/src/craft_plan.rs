@@ -0,0 +1,441 @@
+use crate::{
+    Code, CollectionClient, Level,
+    client::items::{ItemSource, ItemsClient},
+    entities::Item,
+    skill::Skill,
+};
+use itertools::Itertools;
+use openapi::models::SimpleItemSchema;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+
+/// Plans the crafting of an item down to the materials to gather, kill for or
+/// buy, taking into account the items already available.
+#[derive(Clone)]
+pub struct CraftPlanner {
+    items: ItemsClient,
+    stock: HashMap<String, u32>,
+    recycling: HashMap<String, u32>,
+}
+
+impl CraftPlanner {
+    #[must_use]
+    pub fn new(items: ItemsClient) -> Self {
+        Self {
+            items,
+            stock: HashMap::new(),
+            recycling: HashMap::new(),
+        }
+    }
+
+    /// Sets the items available in the bank and inventories.
+    #[must_use]
+    pub fn with_stock(mut self, stock: HashMap<String, u32>) -> Self {
+        self.stock = stock;
+        self
+    }
+
+    /// Credits the materials expected from recycling the given quantities of
+    /// `items`. Recycling returns a share of the materials of an item, spread
+    /// proportionally to the recipe.
+    #[must_use]
+    pub fn with_recyclable(mut self, items: &HashMap<String, u32>) -> Self {
+        for (code, quantity) in items {
+            let Some(item) = self.items.get(code).filter(Item::is_recyclable) else {
+                continue;
+            };
+            let total = item.mats_quantity();
+            for mat in item.mats() {
+                let credit = quantity * item.recycled_quantity() * mat.quantity / total;
+                if credit > 0 {
+                    *self.recycling.entry(mat.code.clone()).or_default() += credit;
+                }
+            }
+        }
+        self
+    }
+
+    /// Plans the crafting of `quantity` of the item `code`, or returns `None`
+    /// if the item or one of its materials is unknown.
+    #[must_use]
+    pub fn plan(&self, code: &str, quantity: u32) -> Option<CraftPlan> {
+        let mut state = PlanningState {
+            stock: self.stock.clone(),
+            recycling: self.recycling.clone(),
+            path: vec![],
+        };
+        let root = self.node(code, quantity, &mut state)?;
+        let mut heights = HashMap::new();
+        let jobs = root
+            .flatten()
+            .into_iter()
+            .filter(|n| n.missing > 0)
+            .into_group_map_by(|n| n.item.clone())
+            .into_iter()
+            .map(|(item, nodes)| Job {
+                quantity: nodes.iter().map(|n| n.missing).sum(),
+                step: nodes
+                    .iter()
+                    .map(|n| n.step.clone())
+                    .reduce(Step::merge)
+                    .unwrap_or_else(|| Step::Obtain { sources: vec![] }),
+                item,
+            })
+            .sorted_by_cached_key(|j| (self.height(&j.item, &mut heights), j.item.clone()))
+            .collect_vec();
+        Some(CraftPlan { root, jobs })
+    }
+
+    fn node(&self, code: &str, quantity: u32, state: &mut PlanningState) -> Option<PlanNode> {
+        let item = self.items.get(code)?;
+        let in_stock = take(&mut state.stock, code, quantity);
+        let recycled = take(&mut state.recycling, code, quantity - in_stock);
+        let missing = quantity - in_stock - recycled;
+        let mut node = PlanNode {
+            item: code.to_owned(),
+            quantity,
+            in_stock,
+            recycled,
+            missing,
+            step: Step::Obtain {
+                sources: self.sources_of(code),
+            },
+            mats: vec![],
+        };
+        // Recipes are not expected to loop, but an item being crafted up the
+        // tree is obtained rather than crafted again.
+        let Some(skill) = item
+            .skill_to_craft()
+            .filter(|_| !state.path.iter().any(|p| p == code))
+        else {
+            return Some(node);
+        };
+        let craft_quantity = item.craft_quantity().max(1);
+        let crafts = missing.div_ceil(craft_quantity);
+        if crafts > 0 {
+            state.path.push(code.to_owned());
+            node.mats = item
+                .mats()
+                .iter()
+                .map(|mat| self.node(&mat.code, mat.quantity * crafts, state))
+                .collect::<Option<Vec<_>>>()?;
+            state.path.pop();
+            let surplus = crafts * craft_quantity - missing;
+            if surplus > 0 {
+                *state.stock.entry(code.to_owned()).or_default() += surplus;
+            }
+        }
+        node.step = Step::Craft {
+            skill,
+            level: item
+                .craft_schema()
+                .and_then(|s| s.level)
+                .map_or_else(|| item.level(), |l| l as u32),
+            crafts,
+        };
+        Some(node)
+    }
+
+    fn sources_of(&self, code: &str) -> Vec<Source> {
+        self.items
+            .sources_of(code)
+            .into_iter()
+            .filter_map(|s| match s {
+                ItemSource::Resource(r) => Some(Source::Resource(r.code().to_owned())),
+                ItemSource::Monster(m) => Some(Source::Monster(m.code().to_owned())),
+                ItemSource::Npc(n) => Some(Source::Npc(n.code().to_owned())),
+                ItemSource::TaskReward => Some(Source::TaskReward),
+                ItemSource::Task => Some(Source::Task),
+                ItemSource::Craft => None,
+            })
+            .collect_vec()
+    }
+
+    /// Returns the number of crafting steps between `code` and its raw
+    /// materials, so that sorting jobs by height orders them topologically.
+    fn height(&self, code: &str, heights: &mut HashMap<String, u32>) -> u32 {
+        if let Some(height) = heights.get(code) {
+            return *height;
+        }
+        // Guards against looping recipes.
+        heights.insert(code.to_owned(), 0);
+        let height = self.items.get(code).map_or(0, |item| {
+            item.mats()
+                .iter()
+                .map(|m| self.height(&m.code, heights) + 1)
+                .max()
+                .unwrap_or(0)
+        });
+        heights.insert(code.to_owned(), height);
+        height
+    }
+}
+
+struct PlanningState {
+    stock: HashMap<String, u32>,
+    recycling: HashMap<String, u32>,
+    path: Vec<String>,
+}
+
+/// Removes up to `quantity` of `code` from `items` and returns the quantity
+/// removed.
+fn take(items: &mut HashMap<String, u32>, code: &str, quantity: u32) -> u32 {
+    let Some(available) = items.get_mut(code) else {
+        return 0;
+    };
+    let taken = quantity.min(*available);
+    *available -= taken;
+    taken
+}
+
+/// Craft tree of an item along with the jobs required to complete it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CraftPlan {
+    pub root: PlanNode,
+    /// Jobs merged by item, each job coming after the jobs producing its
+    /// materials.
+    pub jobs: Vec<Job>,
+}
+
+impl CraftPlan {
+    /// Returns the items and quantities to obtain for the plan to be
+    /// completed, raw materials first.
+    #[must_use]
+    pub fn missing(&self) -> Vec<SimpleItemSchema> {
+        self.jobs
+            .iter()
+            .map(|j| SimpleItemSchema::new(j.item.clone(), j.quantity))
+            .collect_vec()
+    }
+}
+
+impl Display for CraftPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.root.fmt_tree(f, 0)?;
+        writeln!(f, "jobs:")?;
+        for (i, job) in self.jobs.iter().enumerate() {
+            writeln!(f, "  {}. {job}", i + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Node of a craft tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlanNode {
+    pub item: String,
+    /// Quantity required by the parent node.
+    pub quantity: u32,
+    /// Quantity taken from the stock.
+    pub in_stock: u32,
+    /// Quantity expected from recycling.
+    pub recycled: u32,
+    /// Quantity left to craft or obtain.
+    pub missing: u32,
+    pub step: Step,
+    /// Nodes of the materials, when the item is crafted.
+    pub mats: Vec<Self>,
+}
+
+impl PlanNode {
+    fn flatten(&self) -> Vec<&Self> {
+        let mut nodes = vec![self];
+        nodes.extend(self.mats.iter().flat_map(Self::flatten));
+        nodes
+    }
+
+    fn fmt_tree(&self, f: &mut Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{} x{}",
+            "",
+            self.item,
+            self.quantity,
+            indent = depth * 2
+        )?;
+        if self.in_stock > 0 {
+            write!(f, ", {} in stock", self.in_stock)?;
+        }
+        if self.recycled > 0 {
+            write!(f, ", {} recycled", self.recycled)?;
+        }
+        if self.missing > 0 {
+            write!(f, ": {}", self.step)?;
+        }
+        writeln!(f)?;
+        for mat in &self.mats {
+            mat.fmt_tree(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Item to craft or obtain in a given quantity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Job {
+    pub item: String,
+    pub quantity: u32,
+    pub step: Step,
+}
+
+impl Display for Job {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} x{}: {}", self.item, self.quantity, self.step)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Step {
+    /// Crafts the item `crafts` times, each craft yielding the craft quantity
+    /// of the item.
+    Craft {
+        skill: Skill,
+        level: u32,
+        crafts: u32,
+    },
+    /// Obtains the item from one of `sources`.
+    Obtain { sources: Vec<Source> },
+}
+
+impl Step {
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (
+                Self::Craft {
+                    skill,
+                    level,
+                    crafts,
+                },
+                Self::Craft { crafts: other, .. },
+            ) => Self::Craft {
+                skill,
+                level,
+                crafts: crafts + other,
+            },
+            (step, _) => step,
+        }
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Craft {
+                skill,
+                level,
+                crafts,
+            } => write!(f, "craft {crafts} time(s) ({skill} {level})"),
+            Self::Obtain { sources } if sources.is_empty() => write!(f, "no source"),
+            Self::Obtain { sources } => {
+                write!(f, "obtain from {}", sources.iter().join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "code", rename_all = "snake_case")]
+pub enum Source {
+    Resource(String),
+    Monster(String),
+    Npc(String),
+    TaskReward,
+    Task,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Resource(code) => write!(f, "resource {code}"),
+            Self::Monster(code) => write!(f, "monster {code}"),
+            Self::Npc(code) => write!(f, "npc {code}"),
+            Self::TaskReward => write!(f, "task reward"),
+            Self::Task => write!(f, "task"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::ITEMS;
+
+    fn planner() -> CraftPlanner {
+        CraftPlanner::new(ITEMS.clone())
+    }
+
+    #[test]
+    fn plans_full_craft_tree() {
+        let plan = planner().plan("copper_dagger", 2).unwrap();
+
+        assert_eq!(plan.root.missing, 2);
+        assert_eq!(plan.root.mats[0].item, "copper_bar");
+        assert_eq!(plan.root.mats[0].quantity, 12);
+        assert_eq!(plan.root.mats[0].mats[0].item, "copper_ore");
+        assert_eq!(plan.root.mats[0].mats[0].quantity, 120);
+        assert_eq!(
+            plan.jobs.iter().map(|j| j.item.as_str()).collect_vec(),
+            vec!["copper_ore", "copper_bar", "copper_dagger"]
+        );
+        assert!(matches!(
+            &plan.jobs[0].step,
+            Step::Obtain { sources } if sources.contains(&Source::Resource("copper_rocks".to_owned()))
+        ));
+        assert_eq!(
+            plan.jobs[2].step,
+            Step::Craft {
+                skill: Skill::Weaponcrafting,
+                level: 1,
+                crafts: 2
+            }
+        );
+    }
+
+    #[test]
+    fn uses_stock_and_recycling_credit() {
+        let plan = planner()
+            .with_stock(HashMap::from([
+                ("copper_bar".to_owned(), 4),
+                ("copper_ore".to_owned(), 30),
+            ]))
+            .with_recyclable(&HashMap::from([("copper_dagger".to_owned(), 1)]))
+            .plan("copper_dagger", 2)
+            .unwrap();
+
+        let bars = &plan.root.mats[0];
+        assert_eq!((bars.in_stock, bars.recycled, bars.missing), (4, 2, 6));
+        let ore = &bars.mats[0];
+        assert_eq!((ore.quantity, ore.in_stock, ore.missing), (60, 30, 30));
+        assert_eq!(
+            plan.missing()
+                .iter()
+                .map(|m| (m.code.as_str(), m.quantity))
+                .collect_vec(),
+            vec![("copper_ore", 30), ("copper_bar", 6), ("copper_dagger", 2)]
+        );
+    }
+
+    #[test]
+    fn batches_crafts() {
+        let plan = planner().plan("cookie", 5).unwrap();
+
+        assert!(matches!(
+            plan.root.step,
+            Step::Craft {
+                skill: Skill::Cooking,
+                crafts: 2,
+                ..
+            }
+        ));
+        assert!(plan.to_string().contains("cookie x5"));
+        assert!(
+            serde_json::to_string(&plan)
+                .unwrap()
+                .contains(r#""type":"craft""#)
+        );
+    }
+}
@@ -102,6 +102,12 @@ impl MapHandle {
     }
 }
 
+impl From<RawMap> for MapHandle {
+    fn from(raw: RawMap) -> Self {
+        Self(Arc::new(RwLock::new(raw)))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct RawMap(Arc<MapSchema>);
 
@@ -1,11 +1,86 @@
-use crate::entities::CharacterName;
-use bus::{Bus, BusReader};
+use crate::{entities::CharacterName, skill::Skill};
+use futures_util::Stream;
+use itertools::Itertools;
 use log::warn;
-use openapi::models::SimpleItemSchema;
-use std::sync::{Arc, Mutex};
+use openapi::models::{
+    CharacterFightSchema, CooldownSchema, EquipmentItemSchema, GeOrderCreatedSchema,
+    GeTransactionSchema, ItemSchema, MapSchema, NpcItemTransactionSchema, PendingItemSchema,
+    RecyclingItemsSchema, RewardsSchema, SimpleItemSchema, SkillInfoSchema, TaskSchema,
+    TaskTradeSchema,
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    pin::Pin,
+    sync::{
+        Arc, Condvar, Mutex, MutexGuard, Weak,
+        mpsc::{RecvError, RecvTimeoutError, TryRecvError},
+    },
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
+use strum_macros::{Display, EnumDiscriminants, EnumIter};
 
-#[derive(Debug, Clone)]
+/// Game activity of the characters, emitted by the request handler once the
+/// server has answered. Events carry the payload of the response.
+#[derive(Debug, Clone, EnumDiscriminants)]
+#[strum_discriminants(name(SdkEventKind), derive(Hash, Display, EnumIter))]
 pub enum SdkEvent {
+    /// A cooldown started after an action, emitted before the action event.
+    CooldownStarted {
+        character: CharacterName,
+        cooldown: CooldownSchema,
+    },
+    /// A skill (or the combat level) reached a new level.
+    LeveledUp {
+        character: CharacterName,
+        skill: Skill,
+        level: u32,
+    },
+    /// An action failed and was not retried.
+    ActionFailed {
+        character: CharacterName,
+        action: String,
+        /// Code of the error returned by the server, if any.
+        code: Option<u32>,
+        error: String,
+    },
+    Moved {
+        character: CharacterName,
+        destination: MapSchema,
+    },
+    Transitioned {
+        character: CharacterName,
+        destination: MapSchema,
+    },
+    Fought {
+        character: CharacterName,
+        fight: Box<CharacterFightSchema>,
+    },
+    Rested {
+        character: CharacterName,
+        hp_restored: u32,
+    },
+    Gathered {
+        character: CharacterName,
+        details: SkillInfoSchema,
+    },
+    Crafted {
+        character: CharacterName,
+        details: SkillInfoSchema,
+    },
+    Recycled {
+        character: CharacterName,
+        details: RecyclingItemsSchema,
+    },
+    ItemDeleted {
+        character: CharacterName,
+        item: SimpleItemSchema,
+    },
+    ItemUsed {
+        character: CharacterName,
+        item: Box<ItemSchema>,
+        quantity: u32,
+    },
     ItemDeposited {
         character: CharacterName,
         items: Vec<SimpleItemSchema>,
@@ -22,29 +97,475 @@ pub enum SdkEvent {
         character: CharacterName,
         amount: u32,
     },
+    BankExpanded {
+        character: CharacterName,
+        price: u32,
+    },
+    Equipped {
+        character: CharacterName,
+        items: Vec<EquipmentItemSchema>,
+    },
+    Unequipped {
+        character: CharacterName,
+        items: Vec<EquipmentItemSchema>,
+    },
+    TaskAccepted {
+        character: CharacterName,
+        task: TaskSchema,
+    },
+    TaskCompleted {
+        character: CharacterName,
+        rewards: RewardsSchema,
+    },
+    TaskCancelled {
+        character: CharacterName,
+    },
+    TaskItemTraded {
+        character: CharacterName,
+        trade: TaskTradeSchema,
+    },
+    TasksCoinsExchanged {
+        character: CharacterName,
+        rewards: RewardsSchema,
+    },
+    NpcBought {
+        character: CharacterName,
+        transaction: NpcItemTransactionSchema,
+    },
+    NpcSold {
+        character: CharacterName,
+        transaction: NpcItemTransactionSchema,
+    },
+    ItemGiven {
+        character: CharacterName,
+        receiver: String,
+        items: Vec<SimpleItemSchema>,
+    },
+    GoldGiven {
+        character: CharacterName,
+        receiver: String,
+        amount: u32,
+    },
+    PendingItemClaimed {
+        character: CharacterName,
+        item: PendingItemSchema,
+    },
+    GeOrderFilled {
+        character: CharacterName,
+        order: GeTransactionSchema,
+    },
+    GeOrderCreated {
+        character: CharacterName,
+        order: GeOrderCreatedSchema,
+    },
+    GeOrderCancelled {
+        character: CharacterName,
+        order: GeTransactionSchema,
+    },
+}
+
+impl SdkEvent {
+    /// Name of the character whose action emitted the event.
+    #[must_use]
+    pub const fn character(&self) -> &CharacterName {
+        match self {
+            Self::CooldownStarted { character, .. }
+            | Self::LeveledUp { character, .. }
+            | Self::ActionFailed { character, .. }
+            | Self::Moved { character, .. }
+            | Self::Transitioned { character, .. }
+            | Self::Fought { character, .. }
+            | Self::Rested { character, .. }
+            | Self::Gathered { character, .. }
+            | Self::Crafted { character, .. }
+            | Self::Recycled { character, .. }
+            | Self::ItemDeleted { character, .. }
+            | Self::ItemUsed { character, .. }
+            | Self::ItemDeposited { character, .. }
+            | Self::ItemWithdrawn { character, .. }
+            | Self::GoldDeposited { character, .. }
+            | Self::GoldWithdrawn { character, .. }
+            | Self::BankExpanded { character, .. }
+            | Self::Equipped { character, .. }
+            | Self::Unequipped { character, .. }
+            | Self::TaskAccepted { character, .. }
+            | Self::TaskCompleted { character, .. }
+            | Self::TaskCancelled { character }
+            | Self::TaskItemTraded { character, .. }
+            | Self::TasksCoinsExchanged { character, .. }
+            | Self::NpcBought { character, .. }
+            | Self::NpcSold { character, .. }
+            | Self::ItemGiven { character, .. }
+            | Self::GoldGiven { character, .. }
+            | Self::PendingItemClaimed { character, .. }
+            | Self::GeOrderFilled { character, .. }
+            | Self::GeOrderCreated { character, .. }
+            | Self::GeOrderCancelled { character, .. } => character,
+        }
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> SdkEventKind {
+        self.into()
+    }
+}
+
+/// Message received by a [`Subscription`].
+#[derive(Debug, Clone)]
+pub enum BusMessage {
+    Event(SdkEvent),
+    /// The subscription was full and this many events matching its filter
+    /// were dropped at this point of the stream.
+    Missed(u64),
+}
+
+/// Selects the events delivered to a [`Subscription`]. The default filter
+/// accepts every event.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    characters: Option<HashSet<CharacterName>>,
+    kinds: Option<HashSet<SdkEventKind>>,
+}
+
+impl EventFilter {
+    #[must_use]
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Accepts the events of `character`, in addition to the characters
+    /// already accepted.
+    #[must_use]
+    pub fn character(mut self, character: impl Into<CharacterName>) -> Self {
+        self.characters
+            .get_or_insert_default()
+            .insert(character.into());
+        self
+    }
+
+    /// Accepts the events of kind `kind`, in addition to the kinds already
+    /// accepted.
+    #[must_use]
+    pub fn kind(mut self, kind: SdkEventKind) -> Self {
+        self.kinds.get_or_insert_default().insert(kind);
+        self
+    }
+
+    #[must_use]
+    pub fn kinds(self, kinds: impl IntoIterator<Item = SdkEventKind>) -> Self {
+        kinds.into_iter().fold(self, Self::kind)
+    }
+
+    #[must_use]
+    pub fn matches(&self, event: &SdkEvent) -> bool {
+        self.characters
+            .as_ref()
+            .is_none_or(|c| c.contains(event.character()))
+            && self
+                .kinds
+                .as_ref()
+                .is_none_or(|k| k.contains(&event.kind()))
+    }
 }
 
+/// Broadcasts [`SdkEvent`]s to every [`Subscription`] whose filter accepts
+/// them.
+///
+/// Each subscription has its own bounded queue: a slow subscriber only loses
+/// its own events and is told how many with a [`BusMessage::Missed`] marker.
 #[derive(Clone)]
 pub struct EventBus {
-    bus: Arc<Mutex<Bus<SdkEvent>>>,
+    inner: Arc<EventBusInner>,
+}
+
+struct EventBusInner {
+    capacity: usize,
+    queues: Mutex<Vec<Weak<Queue>>>,
 }
 
 impl EventBus {
+    /// Creates a bus whose subscriptions hold up to `capacity` events by
+    /// default.
     #[must_use]
     pub fn new(capacity: usize) -> Self {
         Self {
-            bus: Arc::new(Mutex::new(Bus::new(capacity))),
+            inner: Arc::new(EventBusInner {
+                capacity,
+                queues: Mutex::new(vec![]),
+            }),
         }
     }
 
+    /// Subscribes to every event.
+    #[must_use]
+    pub fn subscribe(&self) -> Subscription {
+        self.subscribe_with(EventFilter::all(), self.inner.capacity)
+    }
+
+    /// Subscribes to the events accepted by `filter`, holding up to
+    /// `capacity` of them until they are received.
     #[must_use]
-    pub fn subscribe(&self) -> BusReader<SdkEvent> {
-        self.bus.lock().unwrap().add_rx()
+    pub fn subscribe_with(&self, filter: EventFilter, capacity: usize) -> Subscription {
+        let queue = Arc::new(Queue {
+            filter,
+            capacity: capacity.max(1),
+            state: Mutex::default(),
+            ready: Condvar::new(),
+        });
+        self.inner
+            .queues
+            .lock()
+            .unwrap()
+            .push(Arc::downgrade(&queue));
+        Subscription { queue }
     }
 
     pub fn emit(&self, event: SdkEvent) {
-        if self.bus.lock().unwrap().try_broadcast(event).is_err() {
-            warn!("event bus: buffer full, event dropped");
+        let mut queues = self.inner.queues.lock().unwrap();
+        queues.retain(|queue| queue.strong_count() > 0);
+        let matching = queues
+            .iter()
+            .filter_map(Weak::upgrade)
+            .filter(|queue| queue.filter.matches(&event))
+            .collect_vec();
+        drop(queues);
+        if let Some((last, others)) = matching.split_last() {
+            for queue in others {
+                queue.push(event.clone());
+            }
+            last.push(event);
+        }
+    }
+}
+
+impl Drop for EventBusInner {
+    fn drop(&mut self) {
+        for queue in self.queues.get_mut().unwrap().iter() {
+            if let Some(queue) = queue.upgrade() {
+                queue.close();
+            }
+        }
+    }
+}
+
+/// Receiving end of the [`EventBus`], usable both blocking and as a
+/// [`Stream`]. Receiving fails, and the stream ends, once every clone of the
+/// bus is dropped and the queued messages are received.
+pub struct Subscription {
+    queue: Arc<Queue>,
+}
+
+struct Queue {
+    filter: EventFilter,
+    capacity: usize,
+    state: Mutex<QueueState>,
+    ready: Condvar,
+}
+
+#[derive(Default)]
+struct QueueState {
+    messages: VecDeque<BusMessage>,
+    /// Events dropped since the last [`BusMessage::Missed`] marker.
+    dropped: u64,
+    missed: u64,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+impl Queue {
+    fn push(&self, event: SdkEvent) {
+        let mut state = self.state.lock().unwrap();
+        if state.messages.len() >= self.capacity {
+            if state.dropped == 0 {
+                warn!("event bus: subscriber lagging, dropping events");
+            }
+            state.dropped += 1;
+            state.missed += 1;
+            return;
+        }
+        if state.dropped > 0 {
+            let dropped = std::mem::take(&mut state.dropped);
+            state.messages.push_back(BusMessage::Missed(dropped));
         }
+        state.messages.push_back(BusMessage::Event(event));
+        self.notify(state);
+    }
+
+    fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        self.notify(state);
+    }
+
+    fn notify(&self, mut state: MutexGuard<'_, QueueState>) {
+        let waker = state.waker.take();
+        drop(state);
+        self.ready.notify_all();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl QueueState {
+    /// Returns the next message, the missed marker coming after the queued
+    /// events since they were emitted before the dropped ones.
+    fn pop(&mut self) -> Option<BusMessage> {
+        self.messages.pop_front().or_else(|| {
+            (self.dropped > 0).then(|| BusMessage::Missed(std::mem::take(&mut self.dropped)))
+        })
+    }
+}
+
+impl Subscription {
+    /// Blocks until a message is available.
+    pub fn recv(&self) -> Result<BusMessage, RecvError> {
+        let mut state = self.queue.state.lock().unwrap();
+        loop {
+            if let Some(message) = state.pop() {
+                return Ok(message);
+            }
+            if state.closed {
+                return Err(RecvError);
+            }
+            state = self.queue.ready.wait(state).unwrap();
+        }
+    }
+
+    /// Blocks until a message is available or `timeout` elapses.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<BusMessage, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.queue.state.lock().unwrap();
+        loop {
+            if let Some(message) = state.pop() {
+                return Ok(message);
+            }
+            if state.closed {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            state = self.queue.ready.wait_timeout(state, remaining).unwrap().0;
+        }
+    }
+
+    pub fn try_recv(&self) -> Result<BusMessage, TryRecvError> {
+        let mut state = self.queue.state.lock().unwrap();
+        match state.pop() {
+            Some(message) => Ok(message),
+            None if state.closed => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Number of messages waiting to be received.
+    #[must_use]
+    pub fn lag(&self) -> usize {
+        self.queue.state.lock().unwrap().messages.len()
+    }
+
+    /// Number of events dropped since the subscription was created.
+    #[must_use]
+    pub fn missed(&self) -> u64 {
+        self.queue.state.lock().unwrap().missed
+    }
+}
+
+impl Stream for Subscription {
+    type Item = BusMessage;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.queue.state.lock().unwrap();
+        if let Some(message) = state.pop() {
+            return Poll::Ready(Some(message));
+        }
+        if state.closed {
+            return Poll::Ready(None);
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{FutureExt, StreamExt};
+
+    fn rested(character: &str) -> SdkEvent {
+        SdkEvent::Rested {
+            character: character.into(),
+            hp_restored: 10,
+        }
+    }
+
+    fn moved(character: &str) -> SdkEvent {
+        SdkEvent::Moved {
+            character: character.into(),
+            destination: MapSchema::default(),
+        }
+    }
+
+    #[test]
+    fn filters_events_by_character_and_kind() {
+        let bus = EventBus::new(8);
+        let all = bus.subscribe();
+        let char1_moves = bus.subscribe_with(
+            EventFilter::all()
+                .character("char1")
+                .kind(SdkEventKind::Moved),
+            8,
+        );
+
+        bus.emit(rested("char1"));
+        bus.emit(moved("char2"));
+        bus.emit(moved("char1"));
+
+        assert_eq!(all.lag(), 3);
+        assert!(matches!(
+            char1_moves.try_recv(),
+            Ok(BusMessage::Event(SdkEvent::Moved { character, .. })) if *character == *"char1"
+        ));
+        assert!(matches!(char1_moves.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn marks_events_missed_by_slow_subscribers() {
+        let bus = EventBus::new(8);
+        let slow = bus.subscribe_with(EventFilter::all(), 2);
+        let fast = bus.subscribe();
+
+        for _ in 0..5 {
+            bus.emit(rested("char1"));
+        }
+        assert_eq!(fast.lag(), 5);
+        assert_eq!(slow.missed(), 3);
+        assert!(matches!(slow.try_recv(), Ok(BusMessage::Event(_))));
+        bus.emit(moved("char1"));
+        assert!(matches!(slow.try_recv(), Ok(BusMessage::Event(_))));
+        assert!(matches!(slow.try_recv(), Ok(BusMessage::Missed(3))));
+        assert!(matches!(
+            slow.try_recv(),
+            Ok(BusMessage::Event(SdkEvent::Moved { .. }))
+        ));
+        assert!(matches!(slow.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn streams_events_until_bus_is_dropped() {
+        let bus = EventBus::new(8);
+        let mut subscription = bus.subscribe();
+
+        assert!(subscription.next().now_or_never().is_none());
+        bus.emit(rested("char1"));
+        assert!(matches!(
+            subscription.next().now_or_never(),
+            Some(Some(BusMessage::Event(SdkEvent::Rested { .. })))
+        ));
+        drop(bus);
+        assert!(matches!(subscription.next().now_or_never(), Some(None)));
+        assert!(subscription.recv().is_err());
     }
 }
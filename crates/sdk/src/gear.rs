@@ -197,6 +197,32 @@ impl Gear {
         self
     }
 
+    /// Returns a copy of the gear with `item` in the given `slot`, `None` if the
+    /// same utility or artifact would then be equipped twice.
+    #[must_use]
+    pub fn with_item_in(&self, slot: Slot, item: Option<Item>) -> Option<Self> {
+        let mut gear = self.clone();
+        *gear.slot_mut(slot) = item;
+        Self::new(
+            gear.weapon,
+            gear.helmet,
+            gear.shield,
+            gear.body_armor,
+            gear.leg_armor,
+            gear.boots,
+            gear.amulet,
+            gear.ring1,
+            gear.ring2,
+            gear.utility1,
+            gear.utility2,
+            gear.artifact1,
+            gear.artifact2,
+            gear.artifact3,
+            gear.rune,
+            gear.bag,
+        )
+    }
+
     #[must_use]
     pub const fn item_in(&self, slot: Slot) -> Option<&Item> {
         match slot {
@@ -219,6 +245,27 @@ impl Gear {
         }
     }
 
+    const fn slot_mut(&mut self, slot: Slot) -> &mut Option<Item> {
+        match slot {
+            Slot::Weapon => &mut self.weapon,
+            Slot::Shield => &mut self.shield,
+            Slot::Helmet => &mut self.helmet,
+            Slot::BodyArmor => &mut self.body_armor,
+            Slot::LegArmor => &mut self.leg_armor,
+            Slot::Boots => &mut self.boots,
+            Slot::Ring1 => &mut self.ring1,
+            Slot::Ring2 => &mut self.ring2,
+            Slot::Amulet => &mut self.amulet,
+            Slot::Artifact1 => &mut self.artifact1,
+            Slot::Artifact2 => &mut self.artifact2,
+            Slot::Artifact3 => &mut self.artifact3,
+            Slot::Utility1 => &mut self.utility1,
+            Slot::Utility2 => &mut self.utility2,
+            Slot::Rune => &mut self.rune,
+            Slot::Bag => &mut self.bag,
+        }
+    }
+
     pub fn align_to(&mut self, other: &Self) {
         if self.ring1 == other.ring2 || self.ring2 == other.ring1 {
             swap(&mut self.ring1, &mut self.ring2);
@@ -525,4 +572,29 @@ mod tests {
         assert_eq!(gear3.utility2, gear1.utility2);
         assert_eq!(gear3.artifact1, gear1.artifact1);
     }
+
+    #[test]
+    fn item_in_slot_can_be_replaced() {
+        let gear = Gear {
+            weapon: ITEMS.get("copper_dagger"),
+            artifact1: ITEMS.get("life_crystal"),
+            ..Default::default()
+        };
+
+        let replaced = gear
+            .with_item_in(Slot::Weapon, ITEMS.get("wooden_stick"))
+            .unwrap();
+        assert_eq!(replaced.weapon, ITEMS.get("wooden_stick"));
+        assert_eq!(replaced.artifact1, gear.artifact1);
+        assert!(
+            gear.with_item_in(Slot::Weapon, None)
+                .unwrap()
+                .weapon
+                .is_none()
+        );
+        assert!(
+            gear.with_item_in(Slot::Artifact2, ITEMS.get("life_crystal"))
+                .is_none()
+        );
+    }
 }
@@ -1,82 +1,39 @@
-use log::error;
 use openapi::models::{
     AccessSchema, CharacterFightSchema, ConditionSchema, DropRateSchema, DropSchema,
     InventorySlotSchema, RewardsSchema, SimpleItemSchema, SkillInfoSchema, TaskTradeSchema,
     TransitionSchema,
 };
-use ron::ser::PrettyConfig;
-use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
-use std::fs;
 use std::ops::Deref;
-use std::string::String;
 
 pub use openapi::models;
 pub use sdk_derive::CollectionClient;
 
+pub use cache::CacheConfig;
+pub(crate) use cache::Cached;
 pub use client::*;
 pub use consts::*;
 pub use container::*;
+pub use craft_plan::{CraftPlan, CraftPlanner};
 pub use gear::*;
 pub use skill::*;
 
+mod cache;
 pub mod client;
 pub mod consts;
 pub mod container;
+pub mod craft_plan;
 pub mod entities;
 pub mod event_bus;
 pub mod gear;
 pub mod simulator;
 pub mod skill;
 
-pub use event_bus::{EventBus, SdkEvent};
+pub use event_bus::{BusMessage, EventBus, EventFilter, SdkEvent, SdkEventKind, Subscription};
 
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
-pub(crate) trait Cached<D>
-where
-    D: for<'a> Deserialize<'a> + Serialize,
-{
-    const FILE: &'static str;
-
-    fn cache_dir(&self) -> &str;
-
-    /// Returns cached data, falling back to `fetch_from_source` when cache is unavailable
-    fn fetch(&self) -> D {
-        self.fetch_from_cache::<D>().unwrap_or_else(|_| {
-            let data = self.fetch_from_source();
-            if let Err(e) = self.cache(&data) {
-                error!("failed to cache data: {e}");
-            }
-            data
-        })
-    }
-
-    /// Reads and deserializes data from the local cache file
-    fn fetch_from_cache<T: for<'a> Deserialize<'a>>(&self) -> anyhow::Result<T> {
-        Ok(ron::from_str(&fs::read_to_string(self.cache_path())?)?)
-    }
-
-    /// Writes data to the local cache file
-    fn cache<T: Serialize>(&self, data: T) -> anyhow::Result<()> {
-        Ok(fs::write(
-            self.cache_path(),
-            &ron::ser::to_string_pretty(&data, PrettyConfig::default())?,
-        )?)
-    }
-
-    fn cache_path(&self) -> String {
-        format!("{}/{}.{}", self.cache_dir(), Self::FILE, "ron")
-    }
-
-    /// Returns data from the source of truth (e.g., the `ArtifactMMO` API)
-    fn fetch_from_source(&self) -> D;
-
-    /// Updates the local cache directly from the source of truth
-    fn refresh(&self);
-}
-
 pub trait Code {
     fn code(&self) -> &str;
 }
@@ -370,12 +327,34 @@ pub const fn yields_xp(char_level: u32, entity_level: u32) -> bool {
     char_level >= entity_level && char_level.saturating_sub(entity_level) <= MAX_LEVEL_DIFF
 }
 
+/// Ranks the XP a character at `char_level` receives by crafting, killing, or
+/// gathering an entity at `entity_level` against other entities.
+///
+/// The game does not publish its XP formula, so this is not an amount of XP:
+/// only the level difference is accounted for, an entity of the character
+/// level ranking `1.0` and the rank fading as the character outlevels it, down
+/// to nothing past `MAX_LEVEL_DIFF`.
+#[must_use]
+pub fn level_xp_rank(char_level: u32, entity_level: u32) -> f64 {
+    if !yields_xp(char_level, entity_level) {
+        return 0.0;
+    }
+    1.0 - f64::from(char_level - entity_level) / f64::from(MAX_LEVEL_DIFF + 1)
+}
+
+/// Returns the XP multiplier granted by `wisdom`, each point adding 0.1%.
+#[must_use]
+pub fn wisdom_multiplier(wisdom: i32) -> f64 {
+    f64::from(wisdom).mul_add(0.001, 1.0)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        DropRateSchemaExt, HasDropTable,
+        DropRateSchemaExt, HasDropTable, level_xp_rank,
         models::DropRateSchema,
         test_utils::{monster, resource},
+        wisdom_multiplier,
     };
 
     fn assert_close(a: f32, b: f32) {
@@ -436,4 +415,19 @@ mod tests {
         assert_close_rel(drop.average_quantity(), 2_200_000_000.0);
         assert_close_rel(drop.expected_quantity(), 2_200_000_000.0);
     }
+
+    #[test]
+    fn level_xp_rank_favors_entities_close_to_the_character() {
+        assert!(level_xp_rank(10, 10) > level_xp_rank(10, 5));
+        assert!((level_xp_rank(20, 20) - level_xp_rank(10, 10)).abs() < f64::EPSILON);
+        assert!(level_xp_rank(10, 1) > 0.0);
+        assert!(level_xp_rank(20, 5) <= 0.0);
+        assert!(level_xp_rank(5, 10) <= 0.0);
+    }
+
+    #[test]
+    fn wisdom_increases_xp() {
+        assert!((wisdom_multiplier(0) - 1.0).abs() < f64::EPSILON);
+        assert!((wisdom_multiplier(50) - 1.05).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,257 @@
+use crate::{
+    Code,
+    simulator::{FightReport, FightSimulation},
+};
+use openapi::models::CombatSimulationDataSchema;
+use std::fmt::{self, Display, Formatter};
+
+const FINAL_HP_KEY: &str = "final_hp";
+
+/// Aggregated outcome of a batch of fights for the initiating character.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FightStats {
+    /// Ratio of won fights, between 0 and 1.
+    pub win_rate: f64,
+    pub avg_turns: f64,
+    /// `None` when the HP lost could not be read from the results.
+    pub avg_hp_lost: Option<f64>,
+}
+
+impl FightStats {
+    #[must_use]
+    pub fn from_reports(reports: &[FightReport]) -> Self {
+        let count = reports.len().max(1) as f64;
+        Self {
+            win_rate: reports.iter().filter(|r| r.is_winning()).count() as f64 / count,
+            avg_turns: reports.iter().map(|r| f64::from(r.turns)).sum::<f64>() / count,
+            avg_hp_lost: Some(reports.iter().map(|r| f64::from(r.hp_lost)).sum::<f64>() / count),
+        }
+    }
+
+    /// Aggregates the server simulation results. `starting_hp` is the HP of the
+    /// initiating character at the start of each fight, the server only
+    /// reporting its final HP.
+    #[must_use]
+    pub fn from_server(data: &CombatSimulationDataSchema, starting_hp: i32) -> Self {
+        let count = data.results.len().max(1) as f64;
+        let hp_lost = data
+            .results
+            .iter()
+            .map(|r| {
+                r.character_results
+                    .first()
+                    .and_then(|c| c.get(FINAL_HP_KEY))
+                    .and_then(serde_json::Value::as_f64)
+                    .map(|final_hp| f64::from(starting_hp) - final_hp)
+            })
+            .collect::<Option<Vec<_>>>();
+        Self {
+            win_rate: data.winrate / 100.0,
+            avg_turns: data.results.iter().map(|r| f64::from(r.turns)).sum::<f64>() / count,
+            avg_hp_lost: hp_lost
+                .filter(|h| !h.is_empty())
+                .map(|h| h.iter().sum::<f64>() / count),
+        }
+    }
+}
+
+/// Maximum accepted gap between the local and the server statistics.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub win_rate: f64,
+    pub turns: f64,
+    pub hp_lost: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            win_rate: 0.05,
+            turns: 1.0,
+            hp_lost: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Discrepancy {
+    WinRate { local: f64, server: f64 },
+    Turns { local: f64, server: f64 },
+    HpLost { local: f64, server: f64 },
+}
+
+impl Display for Discrepancy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WinRate { local, server } => write!(
+                f,
+                "win rate: local {:.1}%, server {:.1}%",
+                local * 100.0,
+                server * 100.0
+            ),
+            Self::Turns { local, server } => {
+                write!(f, "turns: local {local:.1}, server {server:.1}")
+            }
+            Self::HpLost { local, server } => {
+                write!(f, "HP lost: local {local:.1}, server {server:.1}")
+            }
+        }
+    }
+}
+
+/// Local and server statistics of the same fight, run the same number of times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationReport {
+    pub monster: String,
+    pub iterations: u32,
+    pub local: FightStats,
+    pub server: FightStats,
+}
+
+impl CalibrationReport {
+    /// Runs the local `simulation` `iterations` times and compares it against
+    /// the `server` results of the same fight.
+    ///
+    /// The server has no notion of missing HP so participants are simulated at
+    /// full health.
+    #[must_use]
+    pub fn new(
+        simulation: &FightSimulation,
+        iterations: u32,
+        server: &CombatSimulationDataSchema,
+    ) -> Self {
+        let simulation = simulation.at_full_health();
+        let reports = (0..iterations)
+            .map(|_| simulation.run())
+            .collect::<Vec<_>>();
+        let starting_hp = reports.first().map_or(0, |r| r.hp + r.hp_lost);
+        Self {
+            monster: simulation.monster().code().to_owned(),
+            iterations,
+            local: FightStats::from_reports(&reports),
+            server: FightStats::from_server(server, starting_hp),
+        }
+    }
+
+    /// Returns the statistics on which the local simulation drifted from the
+    /// server beyond the given `tolerance`.
+    #[must_use]
+    pub fn discrepancies(&self, tolerance: &Tolerance) -> Vec<Discrepancy> {
+        let mut discrepancies = vec![];
+        let (local, server) = (&self.local, &self.server);
+        if (local.win_rate - server.win_rate).abs() > tolerance.win_rate {
+            discrepancies.push(Discrepancy::WinRate {
+                local: local.win_rate,
+                server: server.win_rate,
+            });
+        }
+        if (local.avg_turns - server.avg_turns).abs() > tolerance.turns {
+            discrepancies.push(Discrepancy::Turns {
+                local: local.avg_turns,
+                server: server.avg_turns,
+            });
+        }
+        if let (Some(local), Some(server)) = (local.avg_hp_lost, server.avg_hp_lost)
+            && (local - server).abs() > tolerance.hp_lost
+        {
+            discrepancies.push(Discrepancy::HpLost { local, server });
+        }
+        discrepancies
+    }
+
+    #[must_use]
+    pub fn is_calibrated(&self, tolerance: &Tolerance) -> bool {
+        self.discrepancies(tolerance).is_empty()
+    }
+}
+
+impl Display for CalibrationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} ({} fights):", self.monster, self.iterations)?;
+        for (source, stats) in [("local", &self.local), ("server", &self.server)] {
+            writeln!(
+                f,
+                "  {source}: win rate {:.1}%, turns {:.1}, HP lost {}",
+                stats.win_rate * 100.0,
+                stats.avg_turns,
+                stats
+                    .avg_hp_lost
+                    .map_or_else(|| "unknown".to_owned(), |h| format!("{h:.1}"))
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Gear,
+        simulator::Participant,
+        test_utils::{item, monster},
+    };
+    use openapi::models::CombatResultSchema;
+    use std::collections::HashMap;
+
+    fn server_result(result: &str, turns: i32, final_hp: i32) -> CombatResultSchema {
+        CombatResultSchema::new(
+            result.to_owned(),
+            turns,
+            vec![],
+            vec![HashMap::from([(
+                FINAL_HP_KEY.to_owned(),
+                serde_json::Value::from(final_hp),
+            )])],
+        )
+    }
+
+    #[test]
+    fn server_stats_are_averaged() {
+        let data = CombatSimulationDataSchema::new(
+            vec![server_result("win", 10, 100), server_result("loss", 20, 0)],
+            1,
+            1,
+            50.0,
+        );
+        let stats = FightStats::from_server(&data, 200);
+
+        assert!((stats.win_rate - 0.5).abs() < f64::EPSILON);
+        assert!((stats.avg_turns - 15.0).abs() < f64::EPSILON);
+        assert_eq!(stats.avg_hp_lost, Some(150.0));
+    }
+
+    #[test]
+    fn server_stats_without_final_hp() {
+        let mut result = server_result("win", 10, 100);
+        result.character_results.clear();
+        let data = CombatSimulationDataSchema::new(vec![result], 1, 0, 100.0);
+
+        assert_eq!(FightStats::from_server(&data, 200).avg_hp_lost, None);
+    }
+
+    #[test]
+    fn report_flags_drifting_win_rate() {
+        let simulation = FightSimulation::new(
+            Participant::new("char1".into())
+                .with_level(10)
+                .with_gear(Gear {
+                    weapon: Some(item("copper_dagger")),
+                    ..Default::default()
+                }),
+            monster("chicken"),
+        );
+        let server =
+            CombatSimulationDataSchema::new(vec![server_result("loss", 100, 0)], 0, 1, 0.0);
+        let report = CalibrationReport::new(&simulation, 10, &server);
+
+        assert!(
+            report
+                .discrepancies(&Tolerance::default())
+                .contains(&Discrepancy::WinRate {
+                    local: report.local.win_rate,
+                    server: 0.0
+                })
+        );
+    }
+}
@@ -1,7 +1,9 @@
+use serde::Serialize;
 use strum_macros::{AsRefStr, EnumIter, EnumString};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, AsRefStr, EnumIter, EnumString)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, AsRefStr, EnumIter, EnumString, Serialize)]
 #[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum DamageType {
     Fire,
     Earth,
@@ -1,51 +1,132 @@
 use crate::{
-    Gear,
+    Code, Gear,
     entities::{CharacterName, Item, Monster},
     simulator::{
         BASE_HP, BASE_INITIATIVE, BURN_MULTIPLIER, HEAL_INTERVAL, HP_PER_LEVEL, HasEffects,
-        Participant, damage_type::DamageType,
+        Participant,
+        damage_type::DamageType,
+        trace::{TraceEvent, Tracer},
     },
 };
 use dyn_clone::DynClone;
+use rand::{Rng, RngExt};
 use std::{cell::RefCell, rc::Rc};
 
+/// How the weapon swing of a turn deals its damage.
+#[derive(Clone, Copy)]
+pub(super) enum Swing {
+    /// Critical strikes are spread over every hit.
+    Averaged,
+    Rolled {
+        is_crit: bool,
+    },
+}
+
+impl Swing {
+    pub(super) fn roll(attacker: &dyn HasEffects, averaged: bool, rng: &mut dyn Rng) -> Self {
+        if averaged {
+            Self::Averaged
+        } else {
+            Self::Rolled {
+                is_crit: rng.random_range(1..=100) <= attacker.critical_strike(),
+            }
+        }
+    }
+}
+
 pub(super) trait SimulationEntity: HasEffects + DynClone {
-    fn turn_against(&mut self, target: &mut dyn SimulationEntity, turn: u32, averaged: bool) {
+    fn turn_against(
+        &mut self,
+        target: &mut dyn SimulationEntity,
+        turn: u32,
+        swing: Swing,
+        tracer: &mut Tracer,
+    ) {
         if turn == self.reconstitution() as u32 {
             self.set_health(self.max_hp());
+            tracer.record(turn, self, || TraceEvent::Reconstitution {
+                hp: self.current_health(),
+            });
         }
         if self.current_health() < self.max_hp() / 2 {
-            self.consume_restore_utilities();
+            self.consume_restore_utilities(turn, tracer);
         }
         if self.current_turn().is_multiple_of(HEAL_INTERVAL) {
+            let hp = self.current_health();
             self.receive_healing();
+            if self.current_health() > hp {
+                tracer.record(turn, self, || TraceEvent::Healing {
+                    hp: self.current_health() - hp,
+                });
+            }
         }
         if self.burning() > 0 {
             self.suffer_burning();
-            if self.current_health() < 1 {
+            tracer.record(turn, self, || TraceEvent::Burn {
+                dmg: self.burning(),
+                hp: self.current_health(),
+            });
+            if self.is_dead() {
                 return;
             }
         }
         if self.poisoned() > 0 {
             self.suffer_poisoning();
-            if self.current_health() < 1 {
+            tracer.record(turn, self, || TraceEvent::Poison {
+                dmg: self.poisoned(),
+                hp: self.current_health(),
+            });
+            if self.is_dead() {
                 return;
             }
         }
         if self.current_turn() == 1 {
             self.apply_burn(target);
+            if target.burning() > 0 {
+                tracer.record(turn, self, || TraceEvent::ApplyBurn {
+                    target: target.name(),
+                    dmg: target.burning(),
+                });
+            }
             self.apply_poison(target);
+            if target.poisoned() > 0 {
+                tracer.record(turn, self, || TraceEvent::ApplyPoison {
+                    target: target.name(),
+                    dmg: target.poisoned(),
+                });
+            }
         }
-        for hit in &self.hits_against(target, averaged) {
+        let hits = match swing {
+            Swing::Averaged => self.averaged_hits_against(target),
+            Swing::Rolled { is_crit } => self.rolled_hits_against(target, is_crit),
+        };
+        for hit in &hits {
             target.dec_health(hit.dmg);
+            tracer.record(turn, self, || TraceEvent::Hit {
+                target: target.name(),
+                element: hit.r#type,
+                dmg: hit.dmg,
+                is_crit: hit.is_crit,
+                target_hp: target.current_health(),
+            });
             if hit.is_crit {
+                let hp = self.current_health();
                 self.inc_health(hit.dmg * self.lifesteal() / 100);
+                if self.current_health() > hp {
+                    tracer.record(turn, self, || TraceEvent::Lifesteal {
+                        hp: self.current_health() - hp,
+                    });
+                }
             }
-            if target.current_health() < 1 {
+            if target.is_dead() {
                 return;
             }
             if target.corrupted() > 0 {
                 target.suffer_corruption(hit.r#type);
+                tracer.record(turn, target, || TraceEvent::Corruption {
+                    element: hit.r#type,
+                    res: target.res(hit.r#type),
+                });
             }
         }
         self.inc_turn();
@@ -63,14 +144,19 @@ pub(super) trait SimulationEntity: HasEffects + DynClone {
         self.inc_health((self.max_hp() as f32 * self.healing() as f32 * 0.01).round() as i32);
     }
 
-    fn consume_restore_utilities(&mut self) {
+    fn consume_restore_utilities(&mut self, turn: u32, tracer: &mut Tracer) {
         if let Some(utility1) = self.utility1()
             && self.utility1_quantity() > 0
         {
             let restore = utility1.restore();
             if restore > 0 {
+                let hp = self.current_health();
                 self.inc_health(restore);
                 self.dec_utility1();
+                tracer.record(turn, self, || TraceEvent::Utility {
+                    code: utility1.code().to_owned(),
+                    hp: self.current_health() - hp,
+                });
             }
         }
         if let Some(utility2) = self.utility2()
@@ -78,8 +164,13 @@ pub(super) trait SimulationEntity: HasEffects + DynClone {
         {
             let restore = utility2.restore();
             if restore > 0 {
+                let hp = self.current_health();
                 self.inc_health(restore);
                 self.dec_utility2();
+                tracer.record(turn, self, || TraceEvent::Utility {
+                    code: utility2.code().to_owned(),
+                    hp: self.current_health() - hp,
+                });
             }
         }
     }
@@ -98,9 +189,19 @@ pub(super) trait SimulationEntity: HasEffects + DynClone {
     fn inc_turn(&mut self);
     fn starting_hp(&self) -> i32;
     fn max_hp(&self) -> i32;
+    /// Starts the fight with `hp`, capped to the max HP.
+    fn start_at(&mut self, hp: i32);
+    fn ignores_death(&self) -> bool;
+    /// Keeps the entity fighting with no HP left.
+    fn ignore_death(&mut self);
 
     fn is_alive(&self) -> bool {
-        self.current_health() > 0
+        self.ignores_death() || !self.is_knocked_out()
+    }
+
+    /// Returns whether the entity ran out of HP, even when ignoring death.
+    fn is_knocked_out(&self) -> bool {
+        self.current_health() < 1
     }
 
     fn is_dead(&self) -> bool {
@@ -148,6 +249,34 @@ pub(super) trait SimulationEntity: HasEffects + DynClone {
     fn dec_utility1(&mut self) {}
     fn dec_utility2(&mut self) {}
     fn is_monster(&self) -> bool;
+
+    /// Returns everything about the entity that can change during a fight.
+    fn state(&self) -> EntityState {
+        EntityState {
+            turn: self.current_turn(),
+            health: self.current_health(),
+            burning: self.burning(),
+            poisoned: self.poisoned(),
+            res: [
+                DamageType::Fire,
+                DamageType::Earth,
+                DamageType::Water,
+                DamageType::Air,
+            ]
+            .map(|t| self.res(t)),
+            utilities: [self.utility1_quantity(), self.utility2_quantity()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct EntityState {
+    turn: u32,
+    health: i32,
+    burning: i32,
+    poisoned: i32,
+    res: [i32; 4],
+    utilities: [u32; 2],
 }
 
 dyn_clone::clone_trait_object!(SimulationEntity);
@@ -155,6 +284,14 @@ dyn_clone::clone_trait_object!(SimulationEntity);
 #[derive(Clone)]
 pub(super) struct SimulationCharacter(Rc<RefCell<BaseSimulationCharacter>>);
 
+impl SimulationCharacter {
+    /// Returns a copy of the character that does not share its state.
+    pub(super) fn detached(&self) -> Self {
+        Self(Rc::new(RefCell::new(self.0.borrow().clone())))
+    }
+}
+
+#[derive(Clone)]
 pub struct BaseSimulationCharacter {
     name: CharacterName,
     gear: Gear,
@@ -175,6 +312,8 @@ pub struct BaseSimulationCharacter {
 
     utility1_quantity: u32,
     utility2_quantity: u32,
+
+    ignore_death: bool,
 }
 
 impl BaseSimulationCharacter {
@@ -205,6 +344,7 @@ impl BaseSimulationCharacter {
             utility2_quantity,
             burning: 0,
             poisoned: 0,
+            ignore_death: false,
         }
     }
 }
@@ -304,6 +444,20 @@ impl SimulationEntity for SimulationCharacter {
     fn starting_hp(&self) -> i32 {
         self.0.borrow().starting_hp
     }
+
+    fn start_at(&mut self, hp: i32) {
+        let mut inner = self.0.borrow_mut();
+        inner.starting_hp = hp.min(inner.max_hp);
+        inner.current_health = inner.starting_hp;
+    }
+
+    fn ignores_death(&self) -> bool {
+        self.0.borrow().ignore_death
+    }
+
+    fn ignore_death(&mut self) {
+        self.0.borrow_mut().ignore_death = true;
+    }
 }
 
 impl HasEffects for SimulationCharacter {
@@ -329,12 +483,19 @@ impl HasEffects for SimulationCharacter {
 #[derive(Clone)]
 pub(super) struct SimulationMonster(Rc<RefCell<BaseSimulationMonster>>);
 
-impl SimulationMonster {}
+impl SimulationMonster {
+    /// Returns a copy of the monster that does not share its state.
+    pub(super) fn detached(&self) -> Self {
+        Self(Rc::new(RefCell::new(self.0.borrow().clone())))
+    }
+}
 
+#[derive(Clone)]
 struct BaseSimulationMonster {
     monster: Monster,
 
     current_turn: u32,
+    starting_hp: i32,
     pub(super) current_health: i32,
 
     fire_res: i32,
@@ -344,11 +505,14 @@ struct BaseSimulationMonster {
 
     burning: i32,
     poisoned: i32,
+
+    ignore_death: bool,
 }
 
 impl From<&Monster> for SimulationMonster {
     fn from(monster: &Monster) -> Self {
         Self(Rc::new(RefCell::new(BaseSimulationMonster {
+            starting_hp: monster.health(),
             current_health: monster.health(),
             current_turn: 1,
             burning: 0,
@@ -358,6 +522,7 @@ impl From<&Monster> for SimulationMonster {
             water_res: monster.res(DamageType::Water),
             air_res: monster.res(DamageType::Air),
             monster: monster.clone(),
+            ignore_death: false,
         })))
     }
 }
@@ -419,7 +584,22 @@ impl SimulationEntity for SimulationMonster {
     }
 
     fn starting_hp(&self) -> i32 {
-        self.max_hp()
+        self.0.borrow().starting_hp
+    }
+
+    fn start_at(&mut self, hp: i32) {
+        let hp = hp.min(self.max_hp());
+        let mut inner = self.0.borrow_mut();
+        inner.starting_hp = hp;
+        inner.current_health = hp;
+    }
+
+    fn ignores_death(&self) -> bool {
+        self.0.borrow().ignore_death
+    }
+
+    fn ignore_death(&mut self) {
+        self.0.borrow_mut().ignore_death = true;
     }
 }
 
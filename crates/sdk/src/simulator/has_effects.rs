@@ -109,33 +109,40 @@ pub trait HasEffects {
 
     fn effect_value(&self, effect: &str) -> i32;
 
-    /// Models one weapon swing where every element either crits or none do.
-    fn hits_against(&self, target: &dyn HasEffects, averaged: bool) -> Vec<Hit> {
-        let is_crit = if averaged {
-            false
-        } else {
-            rand::random_range(1..=100) <= self.critical_strike()
-        };
+    /// Models one weapon swing with the critical strike already rolled.
+    fn rolled_hits_against(&self, target: &dyn HasEffects, is_crit: bool) -> Vec<Hit> {
+        DamageType::iter()
+            .filter_map(|t| {
+                let attack_dmg = self.attack_dmg(t);
+                (attack_dmg > 0)
+                    .then(|| Hit::new(attack_dmg, self.dmg_increase(t), target.res(t), t, is_crit))
+            })
+            .collect_vec()
+    }
+
+    /// Models one weapon swing with the critical strike spread over every hit.
+    fn averaged_hits_against(&self, target: &dyn HasEffects) -> Vec<Hit> {
         DamageType::iter()
             .filter_map(|t| {
                 let attack_dmg = self.attack_dmg(t);
                 (attack_dmg > 0).then(|| {
-                    if averaged {
-                        Hit::averaged(
-                            attack_dmg,
-                            self.dmg_increase(t),
-                            self.critical_strike(),
-                            target.res(t),
-                            t,
-                        )
-                    } else {
-                        Hit::new(attack_dmg, self.dmg_increase(t), target.res(t), t, is_crit)
-                    }
+                    Hit::averaged(
+                        attack_dmg,
+                        self.dmg_increase(t),
+                        self.critical_strike(),
+                        target.res(t),
+                        t,
+                    )
                 })
             })
             .collect_vec()
     }
 
+    /// Returns the probability of a weapon swing to be a critical strike.
+    fn crit_chance(&self) -> f64 {
+        f64::from(self.critical_strike().clamp(0, 100)) / 100.0
+    }
+
     fn critless_dmg_against(&self, target: &dyn HasEffects) -> i32 {
         DamageType::iter()
             .map(|t| {
@@ -1,24 +1,35 @@
 use crate::{
-    CharacterClient, Gear, Level, Slot,
+    CharacterClient, Code, Gear, Level, Slot,
     entities::{Character, CharacterName, Monster},
-    simulator::entity::{SimulationCharacter, SimulationEntity, SimulationMonster},
+    simulator::{
+        entity::{SimulationCharacter, SimulationEntity, SimulationMonster, Swing},
+        trace::Tracer,
+    },
 };
 use itertools::Itertools;
-use openapi::models::FightResult;
-use rand::seq::IndexedRandom;
-use std::cmp::max;
+use openapi::models::{FakeCharacterSchema, FightResult};
+use rand::{Rng, RngExt, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use std::{
+    cmp::max,
+    fmt::{self, Display, Formatter},
+};
 
 pub use damage_type::DamageType;
 pub use effect_code::EffectCode;
 pub use has_effects::HasEffects;
 pub use hit::Hit;
+pub use solver::FightOdds;
+pub use trace::{FightTrace, TraceEntry, TraceEvent};
 
 mod entity;
 
+pub mod calibration;
 pub mod damage_type;
 pub mod effect_code;
 pub mod has_effects;
 pub mod hit;
+pub mod solver;
+pub mod trace;
 
 const BASE_HP: u32 = 115;
 const HP_PER_LEVEL: u32 = 5;
@@ -36,6 +47,9 @@ const BURN_MULTIPLIER: f32 = 0.90;
 const THREAT_TARGET_CHANCE: u32 = 90;
 const HEAL_INTERVAL: u32 = 3;
 
+/// z-score of the 95% confidence interval reported by [`WinRate`].
+const CONFIDENCE_Z: f64 = 1.96;
+
 #[derive(Clone)]
 pub struct FightSimulation {
     participants: Vec<Participant>,
@@ -65,19 +79,34 @@ impl FightSimulation {
         self
     }
 
+    /// Runs the fight once. The random number generator is seeded from the
+    /// params seed if any, or from a random one otherwise; the seed used is
+    /// recorded in the report so the fight can be replayed.
     #[must_use]
     pub fn run(&self) -> FightReport {
-        let chars = self
-            .participants
-            .iter()
-            .map(SimulationCharacter::from)
-            .collect_vec();
+        let seed = self.params.seed.unwrap_or_else(rand::random);
+        FightReport {
+            seed: Some(seed),
+            ..self.run_with(&mut StdRng::seed_from_u64(seed))
+        }
+    }
+
+    /// Runs the fight once, drawing every random roll from `rng`.
+    #[must_use]
+    pub fn run_with(&self, rng: &mut impl Rng) -> FightReport {
+        let (mut monster, chars) = self.entities();
         let initiator = chars[0].clone();
-        let mut monster = SimulationMonster::from(&self.monster);
         let mut fighters: Vec<Box<dyn SimulationEntity>> = vec![Box::new(monster.clone())];
         for char in &chars {
             fighters.push(Box::new(char.clone()));
         }
+        let mut tracer = Tracer::new(self.params.trace);
+        for fighter in &fighters {
+            tracer.record(0, fighter.as_ref(), || TraceEvent::Start {
+                hp: fighter.current_health(),
+                max_hp: fighter.max_hp(),
+            });
+        }
         let mut remaining_fighters = Vec::with_capacity(fighters.len());
         remaining_fighters.clone_from(&fighters);
         let mut turn = 1;
@@ -86,17 +115,19 @@ impl FightSimulation {
             if remaining_fighters.is_empty() {
                 remaining_fighters.clone_from(&fighters);
             }
-            let Some(mut next_fighter) = get_next_fighter(&remaining_fighters) else {
+            let Some(mut next_fighter) = get_next_fighter(&remaining_fighters, rng) else {
                 break;
             };
             remaining_fighters.retain(|f| f.name() != next_fighter.name());
             if next_fighter.is_monster() {
-                let Some(mut target) = pick_monster_target(&chars) else {
+                let Some(mut target) = pick_monster_target(&chars, rng) else {
                     break;
                 };
-                monster.turn_against(&mut target, turn, self.params.averaged);
+                let swing = Swing::roll(&monster, self.params.averaged, rng);
+                monster.turn_against(&mut target, turn, swing, &mut tracer);
             } else {
-                next_fighter.turn_against(&mut monster, turn, self.params.averaged);
+                let swing = Swing::roll(next_fighter.as_ref(), self.params.averaged, rng);
+                next_fighter.turn_against(&mut monster, turn, swing, &mut tracer);
             }
             turn += 1;
         }
@@ -106,39 +137,113 @@ impl FightSimulation {
             hp_percent: initiator.health_percent(),
             hp_lost: initiator.starting_hp() - initiator.current_health(),
             monster_hp: monster.current_health(),
-            result: if monster.is_dead() {
+            dmg_dealt: monster.starting_hp() - monster.current_health(),
+            result: if monster.is_knocked_out() {
                 FightResult::Win
             } else {
                 FightResult::Loss
             },
             cd: compute_fight_cd(initiator.haste(), turn),
+            seed: None,
+            trace: tracer.finish(),
+        }
+    }
+
+    /// Returns the monster and the characters set up as described by the params.
+    fn entities(&self) -> (SimulationMonster, Vec<SimulationCharacter>) {
+        let mut monster = SimulationMonster::from(&self.monster);
+        let mut chars = self
+            .participants
+            .iter()
+            .map(|p| {
+                if self.params.no_utilities {
+                    let mut p = p.clone();
+                    p.gear.utility1 = None;
+                    p.gear.utility2 = None;
+                    SimulationCharacter::from(&p)
+                } else {
+                    SimulationCharacter::from(p)
+                }
+            })
+            .collect_vec();
+        if let Some(hp) = self.params.monster_hp {
+            monster.start_at(hp);
+        }
+        if let Some(hp) = self.params.hp
+            && let Some(initiator) = chars.first_mut()
+        {
+            initiator.start_at(hp);
         }
+        if self.params.ignore_death {
+            monster.ignore_death();
+            chars.iter_mut().for_each(SimulationEntity::ignore_death);
+        }
+        (monster, chars)
     }
 
+    /// Runs the fight `samples` times from a single random number generator,
+    /// seeded like in [`Self::run`], and returns the ratio of fights won.
     #[must_use]
-    pub fn win_rate(&self, samples: u32) -> f64 {
-        let wins = (0..samples).filter(|_| self.run().is_winning()).count();
-        wins as f64 / f64::from(samples)
+    pub fn win_rate(&self, samples: u32) -> WinRate {
+        let seed = self.params.seed.unwrap_or_else(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let wins = (0..samples)
+            .filter(|_| self.run_with(&mut rng).is_winning())
+            .count() as u32;
+        WinRate {
+            wins,
+            samples,
+            seed,
+        }
+    }
+
+    /// Returns the participants loadouts as expected by the server simulation.
+    #[must_use]
+    pub fn fake_characters(&self) -> Vec<FakeCharacterSchema> {
+        self.participants
+            .iter()
+            .map(FakeCharacterSchema::from)
+            .collect()
+    }
+
+    #[must_use]
+    pub const fn monster(&self) -> &Monster {
+        &self.monster
+    }
+
+    fn at_full_health(&self) -> Self {
+        let mut simulation = self.clone();
+        simulation
+            .participants
+            .iter_mut()
+            .for_each(|p| p.missing_hp = 0);
+        simulation
     }
 }
 
-fn get_next_fighter(fighters: &[Box<dyn SimulationEntity>]) -> Option<Box<dyn SimulationEntity>> {
+fn get_next_fighter(
+    fighters: &[Box<dyn SimulationEntity>],
+    rng: &mut impl Rng,
+) -> Option<Box<dyn SimulationEntity>> {
     fighters
         .iter()
         .filter(|f| f.is_alive())
         .max_set_by_key(|f| f.initiative())
         .into_iter()
         .max_set_by_key(|f| f.current_health())
-        .choose(&mut rand::rng())
+        .choose(rng)
         .map(|&c| c.clone())
 }
 
-fn pick_monster_target(chars: &[SimulationCharacter]) -> Option<SimulationCharacter> {
+fn pick_monster_target(
+    chars: &[SimulationCharacter],
+    rng: &mut impl Rng,
+) -> Option<SimulationCharacter> {
     let chars_alive = chars.iter().filter(|c| c.is_alive()).collect_vec();
     if chars_alive.is_empty() {
         return None;
     }
-    let use_threat = rand::random_ratio(THREAT_TARGET_CHANCE, 100);
+    let use_threat = rng.random_ratio(THREAT_TARGET_CHANCE, 100);
     let targets = if use_threat {
         chars_alive.into_iter().max_set_by_key(HasEffects::threat)
     } else {
@@ -147,7 +252,7 @@ fn pick_monster_target(chars: &[SimulationCharacter]) -> Option<SimulationCharac
     targets
         .iter()
         .min_set_by_key(|c| c.current_health())
-        .choose(&mut rand::rng())
+        .choose(rng)
         .map(|&&c| c.clone())
 }
 
@@ -227,10 +332,46 @@ impl From<&CharacterClient> for Participant {
     }
 }
 
+/// Converts the participant into the loadout expected by the server fight
+/// simulation. Missing HP is not part of the server schema: server fights
+/// always start at full health.
+impl From<&Participant> for FakeCharacterSchema {
+    fn from(value: &Participant) -> Self {
+        let code = |slot| value.gear.item_in(slot).map(|i| i.code().to_owned());
+        let utility_quantity = |slot, quantity| value.gear.item_in(slot).map(|_| quantity);
+        Self {
+            weapon_slot: code(Slot::Weapon),
+            rune_slot: code(Slot::Rune),
+            shield_slot: code(Slot::Shield),
+            helmet_slot: code(Slot::Helmet),
+            body_armor_slot: code(Slot::BodyArmor),
+            leg_armor_slot: code(Slot::LegArmor),
+            boots_slot: code(Slot::Boots),
+            ring1_slot: code(Slot::Ring1),
+            ring2_slot: code(Slot::Ring2),
+            amulet_slot: code(Slot::Amulet),
+            artifact1_slot: code(Slot::Artifact1),
+            artifact2_slot: code(Slot::Artifact2),
+            artifact3_slot: code(Slot::Artifact3),
+            utility1_slot: code(Slot::Utility1),
+            utility1_slot_quantity: utility_quantity(Slot::Utility1, value.utility1_quantity),
+            utility2_slot: code(Slot::Utility2),
+            utility2_slot_quantity: utility_quantity(Slot::Utility2, value.utility2_quantity),
+            ..Self::new(value.level)
+        }
+    }
+}
+
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Default)]
 pub struct FightParams {
     averaged: bool,
     ignore_death: bool,
+    no_utilities: bool,
+    hp: Option<i32>,
+    monster_hp: Option<i32>,
+    seed: Option<u64>,
+    trace: bool,
 }
 
 impl FightParams {
@@ -239,25 +380,73 @@ impl FightParams {
         Self {
             averaged: true,
             ignore_death: false,
+            no_utilities: false,
+            hp: None,
+            monster_hp: None,
+            seed: None,
+            trace: false,
         }
     }
 
+    /// Records every event of the fight in the report trace.
+    #[must_use]
+    pub const fn with_trace(mut self) -> Self {
+        self.trace = true;
+        self
+    }
+
+    /// Seeds the random number generator so that runs can be replayed exactly.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Keeps both sides fighting for the full `MAX_TURN` turns whatever their
+    /// HP, to measure the damage dealt by a loadout.
     #[must_use]
     pub const fn ignore_death(mut self) -> Self {
         self.ignore_death = true;
         self
     }
+
+    /// Fights without the utilities equipped by the participants.
+    #[must_use]
+    pub const fn without_utilities(mut self) -> Self {
+        self.no_utilities = true;
+        self
+    }
+
+    /// Starts the initiator with `hp`, overriding its missing HP.
+    #[must_use]
+    pub const fn with_hp(mut self, hp: i32) -> Self {
+        self.hp = Some(hp);
+        self
+    }
+
+    /// Starts the monster with `hp` instead of its max HP.
+    #[must_use]
+    pub const fn with_monster_hp(mut self, hp: i32) -> Self {
+        self.monster_hp = Some(hp);
+        self
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FightReport {
     pub turns: u32,
     pub hp: i32,
     pub hp_lost: i32,
     pub monster_hp: i32,
+    /// Damage dealt to the monster, which can exceed its HP when ignoring death.
+    pub dmg_dealt: i32,
     pub result: FightResult,
     pub cd: u32,
     pub hp_percent: i32,
+    /// Seed the fight was run with, unset when the RNG was injected.
+    pub seed: Option<u64>,
+    /// Turn by turn events, recorded when tracing is enabled.
+    pub trace: Option<FightTrace>,
 }
 
 impl FightReport {
@@ -272,6 +461,55 @@ impl FightReport {
     }
 }
 
+/// Number of fights won over a number of simulated samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WinRate {
+    pub wins: u32,
+    pub samples: u32,
+    /// Seed the samples were run with.
+    pub seed: u64,
+}
+
+impl WinRate {
+    #[must_use]
+    pub fn rate(&self) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        f64::from(self.wins) / f64::from(self.samples)
+    }
+
+    /// Returns the bounds of the 95% Wilson score interval of the win rate.
+    #[must_use]
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        if self.samples == 0 {
+            return (0.0, 1.0);
+        }
+        let n = f64::from(self.samples);
+        let p = self.rate();
+        let z2 = CONFIDENCE_Z * CONFIDENCE_Z;
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let margin =
+            CONFIDENCE_Z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n);
+        ((center - margin).max(0.0), (center + margin).min(1.0))
+    }
+}
+
+impl Display for WinRate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (low, high) = self.confidence_interval();
+        write!(
+            f,
+            "{:.1}% [{:.1}%, {:.1}%] over {} fights (seed {})",
+            self.rate() * 100.0,
+            low * 100.0,
+            high * 100.0,
+            self.samples,
+            self.seed
+        )
+    }
+}
+
 /// Compute the average damage an attack will do against the given `target_resistance`.
 #[inline]
 #[must_use]
@@ -339,6 +577,146 @@ pub fn compute_gathering_cd(resource_level: u32, cooldown_reduction: i32) -> u32
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{item, monster};
+
+    fn simulation() -> FightSimulation {
+        FightSimulation::new(
+            Participant::new("char1".into())
+                .with_level(2)
+                .with_gear(Gear {
+                    weapon: Some(item("copper_dagger")),
+                    ..Default::default()
+                }),
+            monster("chicken"),
+        )
+    }
+
+    #[test]
+    fn seeded_runs_are_replayable() {
+        let report = simulation()
+            .with_params(FightParams::default().with_seed(42))
+            .run();
+        let replay = simulation()
+            .with_params(FightParams::default().with_seed(report.seed.unwrap()))
+            .run();
+
+        assert_eq!(report.seed, Some(42));
+        assert_eq!(report, replay);
+    }
+
+    #[test]
+    fn unseeded_runs_record_their_seed() {
+        let report = simulation().run();
+        let replay = simulation()
+            .with_params(FightParams::default().with_seed(report.seed.unwrap()))
+            .run();
+
+        assert_eq!(report, replay);
+    }
+
+    #[test]
+    fn traced_run_records_every_hit() {
+        let params = FightParams::default().with_seed(42);
+        let report = simulation().with_params(params.clone().with_trace()).run();
+        let trace = report.trace.clone().unwrap();
+        let hits = trace
+            .entries()
+            .iter()
+            .filter(|e| matches!(e.event, TraceEvent::Hit { .. }))
+            .count();
+
+        assert!(matches!(trace.entries()[0].event, TraceEvent::Start { .. }));
+        assert!(hits as u32 >= report.turns - 1);
+        assert_eq!(
+            FightReport {
+                trace: None,
+                ..report
+            },
+            simulation().with_params(params).run()
+        );
+    }
+
+    #[test]
+    fn ignoring_death_runs_every_turn() {
+        let report = simulation()
+            .with_params(FightParams::default().ignore_death().with_seed(1))
+            .run();
+
+        assert_eq!(report.turns, MAX_TURN + 1);
+        assert!(report.is_winning());
+        assert!(report.dmg_dealt > monster("chicken").health());
+    }
+
+    #[test]
+    fn starting_hp_overrides_both_sides() {
+        let report = simulation()
+            .with_params(FightParams::averaged().with_hp(1).with_monster_hp(1_000))
+            .run();
+
+        assert!(report.is_losing());
+        assert_eq!(report.hp_lost + report.hp, 1);
+    }
+
+    #[test]
+    fn utilities_can_be_disabled() {
+        let sim = FightSimulation::new(
+            Participant::new("char1".into())
+                .with_level(1)
+                .with_gear(Gear {
+                    weapon: Some(item("copper_dagger")),
+                    utility1: Some(item("small_health_potion")),
+                    ..Default::default()
+                }),
+            monster("green_slime"),
+        );
+        let uses_potion = |params: FightParams| {
+            sim.clone()
+                .with_params(params.with_trace())
+                .run()
+                .trace
+                .unwrap()
+                .entries()
+                .iter()
+                .any(|e| matches!(e.event, TraceEvent::Utility { .. }))
+        };
+
+        assert!(uses_potion(FightParams::averaged()));
+        assert!(!uses_potion(FightParams::averaged().without_utilities()));
+    }
+
+    #[test]
+    fn seeded_win_rate_is_replayable() {
+        let sim = simulation().with_params(FightParams::default().with_seed(7));
+
+        assert_eq!(sim.win_rate(100), sim.win_rate(100));
+    }
+
+    #[test]
+    fn win_rate_interval_bounds_rate() {
+        let rate = WinRate {
+            wins: 95,
+            samples: 100,
+            seed: 0,
+        };
+        let (low, high) = rate.confidence_interval();
+
+        assert!(low < rate.rate() && rate.rate() < high);
+        assert!((low - 0.888).abs() < 0.001);
+        assert!((high - 0.978).abs() < 0.001);
+    }
+
+    #[test]
+    fn win_rate_interval_is_clamped() {
+        let rate = WinRate {
+            wins: 10,
+            samples: 10,
+            seed: 0,
+        };
+        let (low, high) = rate.confidence_interval();
+
+        assert!(low > 0.6);
+        assert!((high - 1.0).abs() < f64::EPSILON);
+    }
 
     #[test]
     fn check_gather_cd() {
@@ -0,0 +1,345 @@
+use crate::simulator::{
+    FightReport, FightSimulation, HasEffects, MAX_TURN, THREAT_TARGET_CHANCE, compute_fight_cd,
+    entity::{EntityState, SimulationCharacter, SimulationEntity, SimulationMonster, Swing},
+    trace::Tracer,
+};
+use itertools::Itertools;
+use rand::{SeedableRng, rngs::StdRng};
+use std::collections::HashMap;
+
+/// Number of fight states past which the odds are sampled instead, long
+/// crit-heavy fights between several participants branching too much to be
+/// followed exhaustively.
+const MAX_STATES: usize = 20_000;
+/// Number of fights run when the odds are sampled.
+const ODDS_SAMPLES: u32 = 1_000;
+/// Seed of the sampled odds of unseeded simulations, so that the odds of a
+/// fight do not change from one call to the next.
+const ODDS_SEED: u64 = 0;
+
+/// Outcome of a fight, weighted over every possible sequence of critical
+/// strikes, initiative ties and monster targets, see [`FightSimulation::odds`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FightOdds {
+    pub win_rate: f64,
+    pub expected_turns: f64,
+    /// Expected HP lost by the initiator.
+    pub expected_hp_lost: f64,
+    /// Expected fight cooldown of the initiator.
+    pub expected_cd: f64,
+}
+
+impl FightSimulation {
+    /// Computes the exact odds of the fight by following every outcome of each
+    /// turn and merging the branches that end up in the same fight state.
+    ///
+    /// Once more than `MAX_STATES` states were followed, the odds are estimated
+    /// from `ODDS_SAMPLES` sampled fights instead, seeded with `ODDS_SEED` when
+    /// the simulation is not seeded.
+    #[must_use]
+    pub fn odds(&self) -> FightOdds {
+        self.exact_odds(MAX_STATES).unwrap_or_else(|| {
+            self.sampled_odds_from(ODDS_SAMPLES, self.params.seed.unwrap_or(ODDS_SEED))
+        })
+    }
+
+    /// Computes the exact odds of the fight, or `None` if more than
+    /// `max_states` states would have to be followed.
+    fn exact_odds(&self, max_states: usize) -> Option<FightOdds> {
+        let mut odds = FightOdds::default();
+        let start = Branch::new(self);
+        let mut branches = HashMap::from([(start.key(), (1.0, start))]);
+        let mut states = 0;
+        while !branches.is_empty() {
+            states += branches.len();
+            if states > max_states {
+                return None;
+            }
+            let mut next: HashMap<BranchKey, (f64, Branch)> = HashMap::new();
+            for (probability, branch) in branches.into_values() {
+                let Some(successors) = branch.successors(self.params.averaged) else {
+                    branch.conclude(probability, &mut odds);
+                    continue;
+                };
+                for (p, successor) in successors {
+                    let (mass, _) = next
+                        .entry(successor.key())
+                        .or_insert_with(|| (0.0, successor));
+                    *mass = probability.mul_add(p, *mass);
+                }
+            }
+            branches = next;
+        }
+        Some(odds)
+    }
+
+    /// Estimates the odds of the fight by running it `samples` times from a
+    /// single random number generator, seeded like in [`Self::win_rate`].
+    #[must_use]
+    pub fn sampled_odds(&self, samples: u32) -> FightOdds {
+        self.sampled_odds_from(samples, self.params.seed.unwrap_or_else(rand::random))
+    }
+
+    fn sampled_odds_from(&self, samples: u32, seed: u64) -> FightOdds {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let reports = (0..samples.max(1))
+            .map(|_| self.run_with(&mut rng))
+            .collect_vec();
+        let mean = |value: fn(&FightReport) -> f64| {
+            reports.iter().map(value).sum::<f64>() / reports.len() as f64
+        };
+        FightOdds {
+            win_rate: mean(|r| if r.is_winning() { 1.0 } else { 0.0 }),
+            expected_turns: mean(|r| f64::from(r.turns)),
+            expected_hp_lost: mean(|r| f64::from(r.hp_lost)),
+            expected_cd: mean(|r| f64::from(r.cd)),
+        }
+    }
+}
+
+type BranchKey = (u32, Vec<usize>, EntityState, Vec<EntityState>);
+
+/// One possible state of the fight, mirroring the loop of `FightSimulation::run_with`.
+struct Branch {
+    monster: SimulationMonster,
+    chars: Vec<SimulationCharacter>,
+    /// Fighters that did not play yet this round: 0 is the monster, `i + 1`
+    /// is `chars[i]`.
+    remaining: Vec<usize>,
+    turn: u32,
+}
+
+impl Branch {
+    fn new(simulation: &FightSimulation) -> Self {
+        let (monster, chars) = simulation.entities();
+        Self {
+            monster,
+            chars,
+            remaining: vec![],
+            turn: 1,
+        }
+    }
+
+    fn key(&self) -> BranchKey {
+        (
+            self.turn,
+            self.remaining.clone(),
+            self.monster.state(),
+            self.chars.iter().map(SimulationEntity::state).collect_vec(),
+        )
+    }
+
+    fn fighter(&self, index: usize) -> &dyn SimulationEntity {
+        if index == 0 {
+            &self.monster
+        } else {
+            &self.chars[index - 1]
+        }
+    }
+
+    /// Returns the branches reachable by playing the next turn along with their
+    /// probability, or `None` if the fight is over.
+    fn successors(&self, averaged: bool) -> Option<Vec<(f64, Self)>> {
+        if self.turn > MAX_TURN
+            || self.monster.is_dead()
+            || !self.chars.iter().any(SimulationEntity::is_alive)
+        {
+            return None;
+        }
+        let remaining = if self.remaining.is_empty() {
+            (0..=self.chars.len()).collect_vec()
+        } else {
+            self.remaining.clone()
+        };
+        let fighters = remaining
+            .iter()
+            .filter(|&&i| self.fighter(i).is_alive())
+            .max_set_by_key(|&&i| self.fighter(i).initiative())
+            .into_iter()
+            .max_set_by_key(|&&i| self.fighter(i).current_health());
+        if fighters.is_empty() {
+            return None;
+        }
+        let pick = 1.0 / fighters.len() as f64;
+        let mut successors = vec![];
+        for &fighter in fighters {
+            let remaining = remaining
+                .iter()
+                .copied()
+                .filter(|&i| i != fighter)
+                .collect_vec();
+            if fighter == 0 {
+                for (target, p) in self.monster_targets() {
+                    for (swing, q) in swings(&self.monster, averaged) {
+                        let mut next = self.detached(remaining.clone());
+                        next.monster.turn_against(
+                            &mut next.chars[target],
+                            self.turn,
+                            swing,
+                            &mut Tracer::disabled(),
+                        );
+                        successors.push((pick * p * q, next));
+                    }
+                }
+            } else {
+                for (swing, q) in swings(self.fighter(fighter), averaged) {
+                    let mut next = self.detached(remaining.clone());
+                    next.chars[fighter - 1].turn_against(
+                        &mut next.monster,
+                        self.turn,
+                        swing,
+                        &mut Tracer::disabled(),
+                    );
+                    successors.push((pick * q, next));
+                }
+            }
+        }
+        Some(successors)
+    }
+
+    /// Returns the characters the monster can target along with their
+    /// probability, mirroring `pick_monster_target`.
+    fn monster_targets(&self) -> HashMap<usize, f64> {
+        let alive = (0..self.chars.len())
+            .filter(|&i| self.chars[i].is_alive())
+            .collect_vec();
+        let threat = f64::from(THREAT_TARGET_CHANCE) / 100.0;
+        let mut targets = HashMap::new();
+        for (candidates, p) in [
+            (
+                alive
+                    .iter()
+                    .copied()
+                    .max_set_by_key(|&i| self.chars[i].threat()),
+                threat,
+            ),
+            (alive, 1.0 - threat),
+        ] {
+            let candidates = candidates
+                .into_iter()
+                .min_set_by_key(|&i| self.chars[i].current_health());
+            let share = p / candidates.len() as f64;
+            for i in candidates {
+                *targets.entry(i).or_insert(0.0) += share;
+            }
+        }
+        targets
+    }
+
+    fn detached(&self, remaining: Vec<usize>) -> Self {
+        Self {
+            monster: self.monster.detached(),
+            chars: self
+                .chars
+                .iter()
+                .map(SimulationCharacter::detached)
+                .collect_vec(),
+            remaining,
+            turn: self.turn + 1,
+        }
+    }
+
+    fn conclude(&self, probability: f64, odds: &mut FightOdds) {
+        let initiator = &self.chars[0];
+        if self.monster.is_knocked_out() {
+            odds.win_rate += probability;
+        }
+        let hp_lost = initiator.starting_hp() - initiator.current_health();
+        let cd = compute_fight_cd(initiator.haste(), self.turn);
+        odds.expected_turns = probability.mul_add(f64::from(self.turn), odds.expected_turns);
+        odds.expected_hp_lost = probability.mul_add(f64::from(hp_lost), odds.expected_hp_lost);
+        odds.expected_cd = probability.mul_add(f64::from(cd), odds.expected_cd);
+    }
+}
+
+/// Returns the possible swings of `attacker` along with their probability.
+fn swings(attacker: &dyn HasEffects, averaged: bool) -> Vec<(Swing, f64)> {
+    if averaged {
+        return vec![(Swing::Averaged, 1.0)];
+    }
+    let crit = attacker.crit_chance();
+    [
+        (Swing::Rolled { is_crit: true }, crit),
+        (Swing::Rolled { is_crit: false }, 1.0 - crit),
+    ]
+    .into_iter()
+    .filter(|(_, p)| *p > 0.0)
+    .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Gear,
+        simulator::{FightParams, Participant},
+        test_utils::{item, monster},
+    };
+
+    fn simulation(level: u32, monster_code: &str) -> FightSimulation {
+        FightSimulation::new(
+            Participant::new("char1".into())
+                .with_level(level)
+                .with_gear(Gear {
+                    weapon: Some(item("copper_dagger")),
+                    ..Default::default()
+                }),
+            monster(monster_code),
+        )
+    }
+
+    #[test]
+    fn averaged_odds_match_averaged_run() {
+        let sim = simulation(2, "chicken").with_params(FightParams::averaged());
+        let report = sim.run();
+        let odds = sim.odds();
+
+        assert!((odds.win_rate - if report.is_winning() { 1.0 } else { 0.0 }).abs() < 1e-9);
+        assert!((odds.expected_turns - f64::from(report.turns)).abs() < 1e-9);
+        assert!((odds.expected_hp_lost - f64::from(report.hp_lost)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn close_fight_odds() {
+        let odds = simulation(9, "green_slime").odds();
+
+        assert!(0.0 < odds.win_rate && odds.win_rate < 1.0);
+        assert!(odds.expected_turns > 1.0);
+    }
+
+    #[test]
+    fn odds_match_sampled_win_rate() {
+        let sim = simulation(9, "green_slime").with_params(FightParams::default().with_seed(3));
+        let odds = sim.odds();
+        let (low, high) = sim.win_rate(2000).confidence_interval();
+
+        assert!(
+            (low - 0.01..=high + 0.01).contains(&odds.win_rate),
+            "{} not in [{low}, {high}]",
+            odds.win_rate
+        );
+    }
+
+    #[test]
+    fn crit_heavy_group_fight_odds_are_sampled_deterministically() {
+        let participant = |name: &str| {
+            Participant::new(name.into())
+                .with_level(20)
+                .with_gear(Gear {
+                    weapon: Some(item("wooden_club")),
+                    ring1: Some(item("mithril_ring")),
+                    ..Default::default()
+                })
+        };
+        // Nobody dies so that every turn branches on each critical strike.
+        let sim = FightSimulation::new(participant("char1"), monster("king_slime"))
+            .with_participants(vec![participant("char2"), participant("char3")])
+            .with_params(FightParams::default().ignore_death());
+        let odds = sim.odds();
+
+        assert!(sim.exact_odds(MAX_STATES).is_none());
+        assert_eq!(odds, sim.odds());
+        assert!((0.0..=1.0).contains(&odds.win_rate));
+        assert!(odds.expected_turns > 1.0);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::simulator::{DamageType, entity::SimulationEntity};
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter};
+
+/// Turn by turn record of a simulated fight.
+///
+/// Rendered as text, each event is a line worded like the server fight logs
+/// so both can be diffed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct FightTrace(Vec<TraceEntry>);
+
+impl FightTrace {
+    #[must_use]
+    pub fn entries(&self) -> &[TraceEntry] {
+        &self.0
+    }
+}
+
+impl Display for FightTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for entry in &self.0 {
+            writeln!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TraceEntry {
+    pub turn: u32,
+    /// Name of the fighter the event happened to.
+    pub actor: String,
+    #[serde(flatten)]
+    pub event: TraceEvent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TraceEvent {
+    Start {
+        hp: i32,
+        max_hp: i32,
+    },
+    Hit {
+        target: String,
+        element: DamageType,
+        dmg: i32,
+        is_crit: bool,
+        target_hp: i32,
+    },
+    Lifesteal {
+        hp: i32,
+    },
+    ApplyBurn {
+        target: String,
+        dmg: i32,
+    },
+    ApplyPoison {
+        target: String,
+        dmg: i32,
+    },
+    Burn {
+        dmg: i32,
+        hp: i32,
+    },
+    Poison {
+        dmg: i32,
+        hp: i32,
+    },
+    Healing {
+        hp: i32,
+    },
+    Utility {
+        code: String,
+        hp: i32,
+    },
+    Reconstitution {
+        hp: i32,
+    },
+    Corruption {
+        element: DamageType,
+        res: i32,
+    },
+}
+
+impl Display for TraceEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let actor = &self.actor;
+        if let TraceEvent::Start { hp, max_hp } = self.event {
+            return write!(f, "Fight start: {actor} HP: {hp}/{max_hp}");
+        }
+        write!(f, "Turn {}: ", self.turn)?;
+        match &self.event {
+            TraceEvent::Start { .. } => Ok(()),
+            TraceEvent::Hit {
+                target,
+                element,
+                dmg,
+                is_crit,
+                target_hp,
+            } => write!(
+                f,
+                "{actor} used {} attack and dealt {dmg} damage{}. ({target} HP: {target_hp})",
+                element.as_ref(),
+                if *is_crit { " (Critical strike)" } else { "" }
+            ),
+            TraceEvent::Lifesteal { hp } => write!(f, "{actor} restored {hp} HP (Lifesteal)."),
+            TraceEvent::ApplyBurn { target, dmg } => {
+                write!(f, "{actor} applied burning to {target} ({dmg} damage).")
+            }
+            TraceEvent::ApplyPoison { target, dmg } => {
+                write!(f, "{actor} applied poison to {target} ({dmg} damage).")
+            }
+            TraceEvent::Burn { dmg, hp } => {
+                write!(
+                    f,
+                    "{actor} suffered {dmg} burning damage. ({actor} HP: {hp})"
+                )
+            }
+            TraceEvent::Poison { dmg, hp } => {
+                write!(
+                    f,
+                    "{actor} suffered {dmg} poison damage. ({actor} HP: {hp})"
+                )
+            }
+            TraceEvent::Healing { hp } => write!(f, "{actor} healed {hp} HP."),
+            TraceEvent::Utility { code, hp } => {
+                write!(f, "{actor} used {code} and restored {hp} HP.")
+            }
+            TraceEvent::Reconstitution { hp } => {
+                write!(f, "{actor} reconstituted to {hp} HP.")
+            }
+            TraceEvent::Corruption { element, res } => write!(
+                f,
+                "{actor} {} resistance was corrupted to {res}%.",
+                element.as_ref()
+            ),
+        }
+    }
+}
+
+/// Records the events of a fight when tracing is enabled.
+pub(super) struct Tracer(Option<FightTrace>);
+
+impl Tracer {
+    pub(super) fn new(enabled: bool) -> Self {
+        Self(enabled.then(FightTrace::default))
+    }
+
+    pub(super) const fn disabled() -> Self {
+        Self(None)
+    }
+
+    /// Records the event built by `event`, which is only called when tracing.
+    pub(super) fn record<E: SimulationEntity + ?Sized>(
+        &mut self,
+        turn: u32,
+        actor: &E,
+        event: impl FnOnce() -> TraceEvent,
+    ) {
+        if let Some(trace) = &mut self.0 {
+            trace.0.push(TraceEntry {
+                turn,
+                actor: actor.name(),
+                event: event(),
+            });
+        }
+    }
+
+    pub(super) fn finish(self) -> Option<FightTrace> {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_like_server_logs() {
+        let entry = TraceEntry {
+            turn: 3,
+            actor: "char1".to_owned(),
+            event: TraceEvent::Hit {
+                target: "Chicken".to_owned(),
+                element: DamageType::Earth,
+                dmg: 12,
+                is_crit: true,
+                target_hp: 48,
+            },
+        };
+
+        assert_eq!(
+            entry.to_string(),
+            "Turn 3: char1 used earth attack and dealt 12 damage (Critical strike). (Chicken HP: 48)"
+        );
+        assert_eq!(
+            serde_json::to_value(&entry).unwrap(),
+            serde_json::json!({
+                "turn": 3,
+                "actor": "char1",
+                "type": "hit",
+                "target": "Chicken",
+                "element": "earth",
+                "dmg": 12,
+                "is_crit": true,
+                "target_hp": 48,
+            })
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use openapi::models::{CraftSkill, GatheringSkill};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use strum_macros::{AsRefStr, Display, EnumIs, EnumIter, EnumString};
 
 #[derive(
@@ -11,6 +11,7 @@ use strum_macros::{AsRefStr, Display, EnumIs, EnumIter, EnumString};
     PartialEq,
     Default,
     Deserialize,
+    Serialize,
     Display,
     AsRefStr,
     EnumIter,
@@ -18,7 +19,7 @@ use strum_macros::{AsRefStr, Display, EnumIs, EnumIter, EnumString};
     EnumIs,
 )]
 #[strum(serialize_all = "snake_case")]
-#[serde(rename_all(deserialize = "snake_case"))]
+#[serde(rename_all = "snake_case")]
 pub enum Skill {
     #[default]
     Combat,
@@ -6,78 +6,86 @@ use openapi::models::{
 };
 
 use crate::{
-    AccountClient, CharacterClient, CollectionClient, EventsClient, GrandExchangeClient,
-    MapsClient, NpcsClient, NpcsItemsClient, ResourcesClient, TasksClient, TasksRewardsClient,
+    AccountClient, CACHE_TTL, CacheConfig, CharacterClient, ClientError, CollectionClient,
+    EventsClient, GrandExchangeClient, MapsClient, NpcsClient, NpcsItemsClient, ResourcesClient,
+    TasksClient, TasksRewardsClient,
     character::{CharacterRequestHandler, InventoryClient, error::RequestError},
     client::{bank::BankClient, items::ItemsClient, monsters::MonstersClient},
     entities::{CharacterHandle, Item, Monster, RawMap, Resource},
 };
 use std::{
-    collections::HashMap,
     sync::{Arc, LazyLock},
     time::Duration,
 };
 
 const PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
 
+/// Fixtures are plain RON files, read as stale data since they have no
+/// envelope. Sources are unreachable so that they are never overwritten.
+static CACHE: LazyLock<CacheConfig> = LazyLock::new(|| CacheConfig::new(PATH, CACHE_TTL));
+
+const fn unavailable<T>() -> Result<T, ClientError> {
+    Err(ClientError::Unavailable("test fixtures"))
+}
+
 pub static ITEMS: LazyLock<ItemsClient> = LazyLock::new(|| {
     let client = ItemsClient::new(
-        PATH,
-        Box::new(HashMap::new),
+        CACHE.clone(),
+        Box::new(unavailable),
         RESOURCES.clone(),
         MONSTERS.clone(),
         TASKS_REWARDS.clone(),
         NPCS.clone(),
     );
-    client.init();
+    client.init().unwrap();
     client
 });
 
 pub static EVENTS: LazyLock<EventsClient> = LazyLock::new(|| {
-    let client = EventsClient::new(PATH, Box::new(HashMap::new), Box::new(Vec::new));
-    client.init();
+    let client = EventsClient::new(CACHE.clone(), Box::new(unavailable), Box::new(unavailable));
+    client.init().unwrap();
     client
 });
 
 pub static MAPS: LazyLock<MapsClient> = LazyLock::new(|| {
-    let client = MapsClient::new(PATH, Box::new(HashMap::new), EVENTS.clone());
-    client.init();
+    let client = MapsClient::new(CACHE.clone(), Box::new(unavailable), EVENTS.clone());
+    client.init().unwrap();
     client
 });
 
 pub static RESOURCES: LazyLock<ResourcesClient> = LazyLock::new(|| {
-    let client = ResourcesClient::new(PATH, Box::new(HashMap::new), EVENTS.clone());
-    client.init();
+    let client = ResourcesClient::new(CACHE.clone(), Box::new(unavailable), EVENTS.clone());
+    client.init().unwrap();
     client
 });
 
 pub static MONSTERS: LazyLock<MonstersClient> = LazyLock::new(|| {
-    let client = MonstersClient::new(PATH, Box::new(HashMap::new), EVENTS.clone());
-    client.init();
+    let client = MonstersClient::new(CACHE.clone(), Box::new(unavailable), EVENTS.clone());
+    client.init().unwrap();
     client
 });
 
 pub static NPCS: LazyLock<NpcsClient> = LazyLock::new(|| {
-    let client = NpcsClient::new(PATH, Box::new(HashMap::new), NPCS_ITEMS.clone());
-    client.init();
+    let client = NpcsClient::new(CACHE.clone(), Box::new(unavailable), NPCS_ITEMS.clone());
+    client.init().unwrap();
     client
 });
 
 pub static NPCS_ITEMS: LazyLock<NpcsItemsClient> = LazyLock::new(|| {
-    let client = NpcsItemsClient::new(PATH, Box::new(HashMap::new));
-    client.init();
+    let client = NpcsItemsClient::new(CACHE.clone(), Box::new(unavailable));
+    client.init().unwrap();
     client
 });
 
 pub static TASKS: LazyLock<TasksClient> = LazyLock::new(|| {
-    let client = TasksClient::new(PATH, Box::new(HashMap::new), TASKS_REWARDS.clone());
-    client.init();
+    let client = TasksClient::new(CACHE.clone(), Box::new(unavailable), TASKS_REWARDS.clone());
+    client.init().unwrap();
     client
 });
 
 pub static TASKS_REWARDS: LazyLock<TasksRewardsClient> = LazyLock::new(|| {
-    let client = TasksRewardsClient::new(PATH, Box::new(HashMap::new));
-    client.init();
+    let client = TasksRewardsClient::new(CACHE.clone(), Box::new(unavailable));
+    client.init().unwrap();
     client
 });
 
@@ -103,6 +111,7 @@ pub static BANK: LazyLock<BankClient> = LazyLock::new(|| {
 pub static ACCOUNT: LazyLock<AccountClient> = LazyLock::new(|| {
     AccountClient::new(
         "test_account".into(),
+        CACHE.clone(),
         BANK.clone(),
         Box::new(|_| panic!("test account")),
         Box::new(|_| panic!("test account")),
@@ -148,7 +157,7 @@ impl CharacterRequestHandler for MockCharacterRequestHandler {
 
     fn request_fight(
         &self,
-        _participants: Option<&[String; 2]>,
+        _participants: Option<&[String]>,
     ) -> Result<CharacterFightSchema, RequestError> {
         todo!()
     }
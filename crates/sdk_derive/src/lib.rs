@@ -35,7 +35,17 @@ fn get_data_path(attrs: &[syn::Attribute]) -> Expr {
     syn::parse_quote!(self.0.data)
 }
 
-#[proc_macro_derive(CollectionClient, attributes(element, key, data_path))]
+fn get_snapshot_path(attrs: &[syn::Attribute]) -> Option<Expr> {
+    attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("snapshot_path"))
+        .map(|attr| {
+            attr.parse_args::<Expr>()
+                .expect("expected expression path, e.g. #[snapshot_path(self.0.snapshot)]")
+        })
+}
+
+#[proc_macro_derive(CollectionClient, attributes(element, key, data_path, snapshot_path))]
 pub fn collection_client_derive(input: TokenStream) -> TokenStream {
     let ast = syn::parse_macro_input!(input as DeriveInput);
     match ast.data {
@@ -43,7 +53,13 @@ pub fn collection_client_derive(input: TokenStream) -> TokenStream {
             let name = &ast.ident;
             let entity_type = get_element_type(&ast.attrs);
             let key_type = get_key_type(&ast.attrs);
-            let data_path = get_data_path(&ast.attrs);
+            let data = get_snapshot_path(&ast.attrs).map_or_else(
+                || {
+                    let data_path = get_data_path(&ast.attrs);
+                    quote! { #data_path.load_full() }
+                },
+                |snapshot_path| quote! { #snapshot_path.load().data.clone() },
+            );
             let expanded = quote! {
                 impl crate::client::private::Sealed for #name {}
                 impl crate::CollectionClient for #name {}
@@ -52,7 +68,7 @@ pub fn collection_client_derive(input: TokenStream) -> TokenStream {
                     type Key = #key_type;
 
                     fn data(&self) -> std::sync::Arc<std::collections::HashMap<Self::Key, Self::Entity>> {
-                        #data_path.load_full()
+                        #data
                     }
                 }
             };
@@ -1,18 +1,13 @@
-use bot::Bot;
+use bot::{Bot, bot_config};
 use color_eyre::eyre::eyre;
-use sdk::Client;
-use std::env;
 use tui::App;
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
     let logs = tui::init_logger().map_err(|e| eyre!(e.to_string()))?;
-    let client = Client::new(
-        "https://api.artifactsmmo.com".into(),
-        env::var("ARTIFACTSMMO_TOKEN").unwrap_or_default(),
-        ".cache",
-    );
-    client.init();
+    let client = bot_config::client_builder()
+        .map_err(|e| eyre!(e.to_string()))?
+        .build()?;
     let bot = Bot::new(client.clone());
     let order_board = bot.order_board.clone();
     bot.run();